@@ -0,0 +1,355 @@
+//! User account listing, import/export and reconciliation.
+use super::Client;
+use super::channel_tree::{ApplyOptions, PlanAction, StepOutcome};
+use crate::events::{Error, Event, Result};
+use crate::types::{ChannelId, UserAccount, UserRights, UserTypes};
+use crate::utils::csv;
+use std::collections::BTreeMap;
+
+/// Default page size used by `Client::fetch_all_user_accounts`.
+pub const ACCOUNT_PAGE_SIZE: i32 = 100;
+
+/// Column order used by the account CSV format.
+pub const CSV_HEADER: [&str; 11] = [
+    "username",
+    "password",
+    "user_type",
+    "user_rights",
+    "note",
+    "init_channel",
+    "user_data",
+    "auto_operator_channels",
+    "audio_codec_bps_limit",
+    "commands_limit",
+    "commands_interval_ms",
+];
+
+impl Client {
+    /// Lists every user account by paging through the server.
+    pub fn fetch_all_user_accounts(&self) -> Result<Vec<UserAccount>> {
+        self.fetch_user_accounts(ACCOUNT_PAGE_SIZE, 5000)
+    }
+
+    /// Lists every user account using the given page size and per-page timeout.
+    pub fn fetch_user_accounts(&self, page_size: i32, timeout_ms: i32) -> Result<Vec<UserAccount>> {
+        let page_size = page_size.max(1);
+        let mut accounts = Vec::new();
+        let mut index = 0;
+        loop {
+            let page = self.fetch_user_account_page(index, page_size, timeout_ms)?;
+            let done = (page.len() as i32) < page_size;
+            index += page.len() as i32;
+            accounts.extend(page);
+            if done {
+                return Ok(accounts);
+            }
+        }
+    }
+
+    fn fetch_user_account_page(
+        &self,
+        index: i32,
+        count: i32,
+        timeout_ms: i32,
+    ) -> Result<Vec<UserAccount>> {
+        let cmd_id = self.list_user_accounts(index, count);
+        self.collect_command(cmd_id, timeout_ms, |event, msg| match event {
+            Event::UserAccount => msg.account(),
+            _ => None,
+        })
+        .map(|(_, page)| page)
+    }
+
+    /// Updates an existing account; the server replaces accounts with the same username.
+    pub fn update_user_account(&self, account: &UserAccount) -> i32 {
+        self.create_user_account(account)
+    }
+
+    /// Fetches the server accounts and applies the changes needed to match `declared`.
+    pub fn reconcile_user_accounts(
+        &self,
+        declared: &[UserAccount],
+        delete_missing: bool,
+        options: &ApplyOptions,
+    ) -> Result<AccountReport> {
+        let current = self.fetch_all_user_accounts()?;
+        let mut plan = AccountPlan::diff(declared, &current, delete_missing);
+        if let Some(me) = self.get_my_user_account() {
+            plan.steps
+                .retain(|s| s.action != PlanAction::Remove || s.account.username != me.username);
+        }
+        Ok(plan.apply(self, options))
+    }
+}
+
+/// Writes accounts as CSV with a header row.
+pub fn accounts_to_csv(accounts: &[UserAccount]) -> String {
+    let mut out = csv::write_row(&CSV_HEADER);
+    for a in accounts {
+        let channels: Vec<String> = a
+            .auto_operator_channels
+            .iter()
+            .map(|id| id.0.to_string())
+            .collect();
+        out.push_str(&csv::write_row(&[
+            a.username.clone(),
+            a.password.clone(),
+            a.user_type.raw().to_string(),
+            a.user_rights.raw().to_string(),
+            a.note.clone(),
+            a.init_channel.clone(),
+            a.user_data.to_string(),
+            channels.join(" "),
+            a.audio_codec_bps_limit.to_string(),
+            a.abuse_prevent.commands_limit.to_string(),
+            a.abuse_prevent.commands_interval_ms.to_string(),
+        ]));
+    }
+    out
+}
+
+/// Reads accounts from CSV; columns are matched by header name.
+pub fn accounts_from_csv(text: &str) -> Result<Vec<UserAccount>> {
+    let mut rows = csv::parse(text)?.into_iter();
+    let Some(header) = rows.next() else {
+        return Ok(Vec::new());
+    };
+    let columns: Vec<String> = header.iter().map(|h| h.trim().to_lowercase()).collect();
+    if !columns.iter().any(|c| c == "username") {
+        return Err(Error::Format("missing username column".to_string()));
+    }
+    let mut accounts = Vec::new();
+    for (line, row) in rows.enumerate() {
+        let line = line + 2;
+        let mut account = UserAccount::default();
+        for (column, value) in columns.iter().zip(&row) {
+            let value = value.as_str();
+            match column.as_str() {
+                "username" => account.username = value.to_string(),
+                "password" => account.password = value.to_string(),
+                "user_type" => {
+                    account.user_type = UserTypes::from_raw(number(value, column, line)?)
+                }
+                "user_rights" => {
+                    account.user_rights = UserRights::from_raw(number(value, column, line)?)
+                }
+                "note" => account.note = value.to_string(),
+                "init_channel" => account.init_channel = value.to_string(),
+                "user_data" => account.user_data = number(value, column, line)?,
+                "auto_operator_channels" => {
+                    account.auto_operator_channels = value
+                        .split([' ', ';'])
+                        .filter(|id| !id.is_empty())
+                        .map(|id| number(id, column, line).map(ChannelId))
+                        .collect::<Result<_>>()?;
+                }
+                "audio_codec_bps_limit" => {
+                    account.audio_codec_bps_limit = number(value, column, line)?
+                }
+                "commands_limit" => {
+                    account.abuse_prevent.commands_limit = number(value, column, line)?
+                }
+                "commands_interval_ms" => {
+                    account.abuse_prevent.commands_interval_ms = number(value, column, line)?
+                }
+                _ => {}
+            }
+        }
+        if account.username.is_empty() {
+            return Err(Error::Format(format!("line {line}: empty username")));
+        }
+        accounts.push(account);
+    }
+    Ok(accounts)
+}
+
+fn number<T: std::str::FromStr + Default>(value: &str, column: &str, line: usize) -> Result<T> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(T::default());
+    }
+    value
+        .parse()
+        .map_err(|_| Error::Format(format!("line {line}: invalid {column} '{value}'")))
+}
+
+/// Writes accounts as pretty JSON.
+#[cfg(feature = "json")]
+pub fn accounts_to_json(accounts: &[UserAccount]) -> Result<String> {
+    serde_json::to_string_pretty(accounts).map_err(|e| Error::Format(e.to_string()))
+}
+
+/// Reads accounts from a JSON array.
+#[cfg(feature = "json")]
+pub fn accounts_from_json(text: &str) -> Result<Vec<UserAccount>> {
+    serde_json::from_str(text).map_err(|e| Error::Format(e.to_string()))
+}
+
+/// Single change in an account plan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountStep {
+    pub action: PlanAction,
+    pub account: UserAccount,
+    pub changes: Vec<&'static str>,
+}
+
+/// Ordered list of account changes produced by `AccountPlan::diff`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AccountPlan {
+    pub steps: Vec<AccountStep>,
+}
+
+fn account_changes(want: &UserAccount, have: &UserAccount) -> Vec<&'static str> {
+    let mut out = Vec::new();
+    if !want.password.is_empty() && want.password != have.password {
+        out.push("password");
+    }
+    if want.user_type != have.user_type {
+        out.push("user_type");
+    }
+    if want.user_rights != have.user_rights {
+        out.push("user_rights");
+    }
+    if want.note != have.note {
+        out.push("note");
+    }
+    if want.init_channel != have.init_channel {
+        out.push("init_channel");
+    }
+    if want.user_data != have.user_data {
+        out.push("user_data");
+    }
+    if want.auto_operator_channels != have.auto_operator_channels {
+        out.push("auto_operator_channels");
+    }
+    if want.audio_codec_bps_limit != have.audio_codec_bps_limit {
+        out.push("audio_codec_bps_limit");
+    }
+    if want.abuse_prevent != have.abuse_prevent {
+        out.push("abuse_prevent");
+    }
+    out
+}
+
+impl AccountPlan {
+    /// Computes the changes that turn `current` into `declared`.
+    pub fn diff(declared: &[UserAccount], current: &[UserAccount], delete_missing: bool) -> Self {
+        let wanted: BTreeMap<&str, &UserAccount> =
+            declared.iter().map(|a| (a.username.as_str(), a)).collect();
+        let live: BTreeMap<&str, &UserAccount> =
+            current.iter().map(|a| (a.username.as_str(), a)).collect();
+        let mut steps = Vec::new();
+        for (name, want) in &wanted {
+            match live.get(name) {
+                None => steps.push(AccountStep {
+                    action: PlanAction::Create,
+                    account: (*want).clone(),
+                    changes: Vec::new(),
+                }),
+                Some(have) => {
+                    let changes = account_changes(want, have);
+                    if !changes.is_empty() {
+                        let mut account = (*want).clone();
+                        if account.password.is_empty() {
+                            account.password = have.password.clone();
+                        }
+                        steps.push(AccountStep {
+                            action: PlanAction::Update,
+                            account,
+                            changes,
+                        });
+                    }
+                }
+            }
+        }
+        if delete_missing {
+            for (name, have) in &live {
+                if !wanted.contains_key(name) {
+                    steps.push(AccountStep {
+                        action: PlanAction::Remove,
+                        account: (*have).clone(),
+                        changes: Vec::new(),
+                    });
+                }
+            }
+        }
+        Self { steps }
+    }
+
+    /// Returns true if the plan has no steps.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Returns the number of steps with the given action.
+    pub fn count(&self, action: PlanAction) -> usize {
+        self.steps.iter().filter(|s| s.action == action).count()
+    }
+
+    /// Executes the plan and waits for each command to complete.
+    pub fn apply(&self, client: &Client, options: &ApplyOptions) -> AccountReport {
+        let mut report = AccountReport {
+            dry_run: options.dry_run,
+            steps: Vec::with_capacity(self.steps.len()),
+        };
+        let mut failed = false;
+        for step in &self.steps {
+            let (cmd_id, outcome) = if options.dry_run {
+                (None, StepOutcome::Planned)
+            } else if failed && options.stop_on_error {
+                (None, StepOutcome::Skipped)
+            } else {
+                let cmd_id = match step.action {
+                    PlanAction::Create => client.create_user_account(&step.account),
+                    PlanAction::Update => client.update_user_account(&step.account),
+                    PlanAction::Remove => client.delete_user_account(&step.account.username),
+                };
+                let outcome = match client.wait_for_command(cmd_id, options.timeout_ms) {
+                    Ok(_) => StepOutcome::Applied,
+                    Err(err) => {
+                        failed = true;
+                        StepOutcome::Failed(err)
+                    }
+                };
+                ((cmd_id > 0).then_some(cmd_id), outcome)
+            };
+            report.steps.push(AccountResult {
+                step: step.clone(),
+                cmd_id,
+                outcome,
+            });
+        }
+        report
+    }
+}
+
+/// Account plan step together with its outcome.
+#[derive(Debug)]
+pub struct AccountResult {
+    pub step: AccountStep,
+    pub cmd_id: Option<i32>,
+    pub outcome: StepOutcome,
+}
+
+/// Per-step results of applying an account plan.
+#[derive(Debug, Default)]
+pub struct AccountReport {
+    pub dry_run: bool,
+    pub steps: Vec<AccountResult>,
+}
+
+impl AccountReport {
+    /// Returns true if no step failed or was skipped.
+    pub fn is_success(&self) -> bool {
+        self.steps
+            .iter()
+            .all(|s| matches!(s.outcome, StepOutcome::Planned | StepOutcome::Applied))
+    }
+
+    /// Returns the failed steps.
+    pub fn failures(&self) -> impl Iterator<Item = &AccountResult> {
+        self.steps
+            .iter()
+            .filter(|s| matches!(s.outcome, StepOutcome::Failed(_)))
+    }
+}
@@ -0,0 +1,491 @@
+//! Per-user presence, channel and talk time tracking.
+use super::Message;
+use crate::events::{Error, Event, Result};
+use crate::types::{ChannelId, User, UserId, UserPresence};
+use crate::utils::csv;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Output format for activity exports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    /// Requires the `json` feature; exporting fails without it.
+    Json,
+}
+
+/// Settings for `ActivityTracker`.
+#[derive(Debug, Clone)]
+pub struct ActivityConfig {
+    pub idle_after: Duration,
+    pub export_path: Option<PathBuf>,
+    pub export_format: ExportFormat,
+    pub export_interval: Duration,
+}
+
+impl Default for ActivityConfig {
+    fn default() -> Self {
+        Self {
+            idle_after: Duration::from_secs(600),
+            export_path: None,
+            export_format: ExportFormat::Csv,
+            export_interval: Duration::from_secs(300),
+        }
+    }
+}
+
+impl ActivityConfig {
+    /// Creates a configuration with defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how long without talking or status changes marks a user idle.
+    pub fn idle_after(mut self, idle_after: Duration) -> Self {
+        self.idle_after = idle_after;
+        self
+    }
+
+    /// Enables periodic export to a file.
+    pub fn export(
+        mut self,
+        path: impl AsRef<Path>,
+        format: ExportFormat,
+        interval: Duration,
+    ) -> Self {
+        self.export_path = Some(path.as_ref().to_path_buf());
+        self.export_format = format;
+        self.export_interval = interval;
+        self
+    }
+}
+
+/// Accumulated activity of a single user.
+#[derive(Debug, Clone)]
+pub struct UserActivity {
+    pub user_id: UserId,
+    pub username: String,
+    pub nickname: String,
+    pub presence: UserPresence,
+    pub sessions: u32,
+    pub online_since: Option<SystemTime>,
+    pub online_time: Duration,
+    pub channel: Option<ChannelId>,
+    pub channel_since: Option<SystemTime>,
+    pub channel_time: HashMap<ChannelId, Duration>,
+    pub talking_since: Option<SystemTime>,
+    pub talk_time: HashMap<ChannelId, Duration>,
+    pub last_seen: SystemTime,
+    pub last_active: SystemTime,
+}
+
+impl UserActivity {
+    fn new(user: &User, now: SystemTime) -> Self {
+        Self {
+            user_id: user.id,
+            username: user.username.clone(),
+            nickname: user.nickname.clone(),
+            presence: user.status.presence,
+            sessions: 0,
+            online_since: None,
+            online_time: Duration::ZERO,
+            channel: None,
+            channel_since: None,
+            channel_time: HashMap::new(),
+            talking_since: None,
+            talk_time: HashMap::new(),
+            last_seen: now,
+            last_active: now,
+        }
+    }
+
+    /// Returns true while the user is logged in.
+    pub fn is_online(&self) -> bool {
+        self.online_since.is_some()
+    }
+
+    /// Returns the total time online, including the current session.
+    pub fn total_online(&self, now: SystemTime) -> Duration {
+        self.online_time + elapsed(self.online_since, now)
+    }
+
+    /// Returns the total time spent in a channel, including the current visit.
+    pub fn time_in_channel(&self, channel: ChannelId, now: SystemTime) -> Duration {
+        let current = if self.channel == Some(channel) {
+            elapsed(self.channel_since, now)
+        } else {
+            Duration::ZERO
+        };
+        self.channel_time.get(&channel).copied().unwrap_or_default() + current
+    }
+
+    /// Returns the talk time in a channel, including an ongoing talk spurt.
+    pub fn talk_time_in_channel(&self, channel: ChannelId, now: SystemTime) -> Duration {
+        let current = if self.channel == Some(channel) {
+            elapsed(self.talking_since, now)
+        } else {
+            Duration::ZERO
+        };
+        self.talk_time.get(&channel).copied().unwrap_or_default() + current
+    }
+
+    /// Returns the talk time across all channels.
+    pub fn total_talk_time(&self, now: SystemTime) -> Duration {
+        self.talk_time.values().sum::<Duration>() + elapsed(self.talking_since, now)
+    }
+
+    /// Returns true if the user is away or has been inactive for `idle_after`.
+    pub fn is_idle(&self, idle_after: Duration, now: SystemTime) -> bool {
+        self.talking_since.is_none()
+            && (self.presence == UserPresence::Away
+                || now.duration_since(self.last_active).unwrap_or_default() >= idle_after)
+    }
+
+    fn leave_channel(&mut self, now: SystemTime) {
+        self.stop_talking(now);
+        if let Some(channel) = self.channel.take() {
+            *self.channel_time.entry(channel).or_default() += elapsed(self.channel_since, now);
+        }
+        self.channel_since = None;
+    }
+
+    fn enter_channel(&mut self, channel: ChannelId, now: SystemTime) {
+        if self.channel == Some(channel) {
+            return;
+        }
+        self.leave_channel(now);
+        if channel.0 > 0 {
+            self.channel = Some(channel);
+            self.channel_since = Some(now);
+        }
+    }
+
+    fn stop_talking(&mut self, now: SystemTime) {
+        if let Some(since) = self.talking_since.take() {
+            let channel = self.channel.unwrap_or_default();
+            *self.talk_time.entry(channel).or_default() += elapsed(Some(since), now);
+            self.last_active = now;
+        }
+    }
+}
+
+/// Per-channel figures in an `ActivitySummary`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelActivity {
+    pub channel_id: ChannelId,
+    pub time_secs: u64,
+    pub talk_secs: u64,
+}
+
+/// Point-in-time activity figures for a user, used for export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActivitySummary {
+    pub user_id: UserId,
+    pub username: String,
+    pub nickname: String,
+    pub online: bool,
+    pub idle: bool,
+    pub sessions: u32,
+    pub online_secs: u64,
+    pub talk_secs: u64,
+    pub last_seen: u64,
+    pub channels: Vec<ChannelActivity>,
+}
+
+const CSV_HEADER: [&str; 9] = [
+    "user_id",
+    "username",
+    "nickname",
+    "online",
+    "idle",
+    "sessions",
+    "online_secs",
+    "talk_secs",
+    "last_seen",
+];
+
+/// Tracks sessions, channel visits and talk time from polled events.
+pub struct ActivityTracker {
+    config: ActivityConfig,
+    users: HashMap<UserId, UserActivity>,
+    last_export: Option<SystemTime>,
+}
+
+impl Default for ActivityTracker {
+    fn default() -> Self {
+        Self::new(ActivityConfig::default())
+    }
+}
+
+impl ActivityTracker {
+    /// Creates a tracker with the provided configuration.
+    pub fn new(config: ActivityConfig) -> Self {
+        Self {
+            config,
+            users: HashMap::new(),
+            last_export: None,
+        }
+    }
+
+    /// Returns the active configuration.
+    pub fn config(&self) -> &ActivityConfig {
+        &self.config
+    }
+
+    /// Processes a polled event.
+    pub fn handle(&mut self, event: Event, msg: &Message) {
+        if is_user_event(event)
+            && let Some(user) = msg.user()
+        {
+            self.record(event, &user, SystemTime::now());
+        }
+    }
+
+    /// Records an event for a user at the given time.
+    pub fn record(&mut self, event: Event, user: &User, now: SystemTime) {
+        if user.id.0 <= 0 || !is_user_event(event) {
+            return;
+        }
+        let entry = self
+            .users
+            .entry(user.id)
+            .or_insert_with(|| UserActivity::new(user, now));
+        entry.last_seen = now;
+        if !user.username.is_empty() {
+            entry.username = user.username.clone();
+        }
+        if !user.nickname.is_empty() {
+            entry.nickname = user.nickname.clone();
+        }
+        match event {
+            Event::UserLoggedIn => {
+                if entry.online_since.is_none() {
+                    entry.online_since = Some(now);
+                    entry.sessions += 1;
+                }
+                entry.presence = user.status.presence;
+                entry.last_active = now;
+            }
+            Event::UserLoggedOut => {
+                entry.leave_channel(now);
+                entry.online_time += elapsed(entry.online_since.take(), now);
+            }
+            Event::UserJoined => {
+                if entry.online_since.is_none() {
+                    entry.online_since = Some(now);
+                    entry.sessions += 1;
+                }
+                entry.enter_channel(user.channel_id, now);
+                entry.last_active = now;
+            }
+            Event::UserLeft => entry.leave_channel(now),
+            Event::UserUpdate if entry.presence != user.status.presence => {
+                entry.presence = user.status.presence;
+                entry.last_active = now;
+            }
+            Event::UserStateChange => {
+                if user.state.is_talking() {
+                    if entry.talking_since.is_none() {
+                        entry.talking_since = Some(now);
+                    }
+                    entry.last_active = now;
+                } else {
+                    entry.stop_talking(now);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the activity for a user.
+    pub fn user(&self, user_id: UserId) -> Option<&UserActivity> {
+        self.users.get(&user_id)
+    }
+
+    /// Returns all tracked users.
+    pub fn users(&self) -> impl Iterator<Item = &UserActivity> {
+        self.users.values()
+    }
+
+    /// Returns when a user was last seen.
+    pub fn last_seen(&self, user_id: UserId) -> Option<SystemTime> {
+        self.users.get(&user_id).map(|u| u.last_seen)
+    }
+
+    /// Returns true if an online user is idle.
+    pub fn is_idle(&self, user_id: UserId, now: SystemTime) -> bool {
+        self.users
+            .get(&user_id)
+            .is_some_and(|u| u.is_online() && u.is_idle(self.config.idle_after, now))
+    }
+
+    /// Returns the online users that are idle.
+    pub fn idle_users(&self, now: SystemTime) -> Vec<UserId> {
+        let mut ids: Vec<UserId> = self
+            .users
+            .keys()
+            .copied()
+            .filter(|id| self.is_idle(*id, now))
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// Returns the users sorted by total talk time, longest first.
+    pub fn top_talkers(&self, now: SystemTime) -> Vec<(UserId, Duration)> {
+        let mut out: Vec<(UserId, Duration)> = self
+            .users
+            .values()
+            .map(|u| (u.user_id, u.total_talk_time(now)))
+            .collect();
+        out.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        out
+    }
+
+    /// Forgets a user.
+    pub fn remove_user(&mut self, user_id: UserId) {
+        self.users.remove(&user_id);
+    }
+
+    /// Returns a summary per user, sorted by user id.
+    pub fn summaries(&self, now: SystemTime) -> Vec<ActivitySummary> {
+        let mut out: Vec<ActivitySummary> = self
+            .users
+            .values()
+            .map(|u| {
+                let mut channels: BTreeMap<ChannelId, ChannelActivity> = BTreeMap::new();
+                for id in u
+                    .channel_time
+                    .keys()
+                    .chain(u.talk_time.keys())
+                    .chain(u.channel.iter())
+                {
+                    channels.entry(*id).or_insert_with(|| ChannelActivity {
+                        channel_id: *id,
+                        time_secs: u.time_in_channel(*id, now).as_secs(),
+                        talk_secs: u.talk_time_in_channel(*id, now).as_secs(),
+                    });
+                }
+                ActivitySummary {
+                    user_id: u.user_id,
+                    username: u.username.clone(),
+                    nickname: u.nickname.clone(),
+                    online: u.is_online(),
+                    idle: u.is_online() && u.is_idle(self.config.idle_after, now),
+                    sessions: u.sessions,
+                    online_secs: u.total_online(now).as_secs(),
+                    talk_secs: u.total_talk_time(now).as_secs(),
+                    last_seen: unix_secs(u.last_seen),
+                    channels: channels.into_values().collect(),
+                }
+            })
+            .collect();
+        out.sort_by_key(|s| s.user_id);
+        out
+    }
+
+    /// Writes per-user summaries as CSV with a header row.
+    pub fn to_csv(&self, now: SystemTime) -> String {
+        let mut out = csv::write_row(&CSV_HEADER);
+        for s in self.summaries(now) {
+            out.push_str(&csv::write_row(&[
+                s.user_id.0.to_string(),
+                s.username,
+                s.nickname,
+                s.online.to_string(),
+                s.idle.to_string(),
+                s.sessions.to_string(),
+                s.online_secs.to_string(),
+                s.talk_secs.to_string(),
+                s.last_seen.to_string(),
+            ]));
+        }
+        out
+    }
+
+    /// Writes per-user, per-channel figures as CSV with a header row.
+    pub fn channels_to_csv(&self, now: SystemTime) -> String {
+        let mut out = csv::write_row(&["user_id", "channel_id", "time_secs", "talk_secs"]);
+        for s in self.summaries(now) {
+            for c in s.channels {
+                out.push_str(&csv::write_row(&[
+                    s.user_id.0.to_string(),
+                    c.channel_id.0.to_string(),
+                    c.time_secs.to_string(),
+                    c.talk_secs.to_string(),
+                ]));
+            }
+        }
+        out
+    }
+
+    /// Writes per-user summaries as pretty JSON.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self, now: SystemTime) -> Result<String> {
+        serde_json::to_string_pretty(&self.summaries(now)).map_err(|e| Error::Format(e.to_string()))
+    }
+
+    /// Writes the configured export file when the export interval has elapsed.
+    pub fn tick(&mut self, now: SystemTime) -> Result<bool> {
+        if self.config.export_path.is_none() {
+            return Ok(false);
+        }
+        if let Some(last) = self.last_export
+            && now.duration_since(last).unwrap_or_default() < self.config.export_interval
+        {
+            return Ok(false);
+        }
+        self.export(now)?;
+        Ok(true)
+    }
+
+    /// Writes the configured export file immediately.
+    pub fn export(&mut self, now: SystemTime) -> Result<()> {
+        let Some(path) = self.config.export_path.clone() else {
+            return Ok(());
+        };
+        let text = match self.config.export_format {
+            ExportFormat::Csv => self.to_csv(now),
+            #[cfg(feature = "json")]
+            ExportFormat::Json => self.to_json(now)?,
+            #[cfg(not(feature = "json"))]
+            ExportFormat::Json => {
+                return Err(Error::Format(
+                    "JSON export requires the `json` feature".to_string(),
+                ));
+            }
+        };
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, text)?;
+        std::fs::rename(&tmp, &path)?;
+        self.last_export = Some(now);
+        Ok(())
+    }
+}
+
+fn is_user_event(event: Event) -> bool {
+    matches!(
+        event,
+        Event::UserLoggedIn
+            | Event::UserLoggedOut
+            | Event::UserJoined
+            | Event::UserLeft
+            | Event::UserUpdate
+            | Event::UserStateChange
+    )
+}
+
+fn elapsed(since: Option<SystemTime>, now: SystemTime) -> Duration {
+    since
+        .and_then(|since| now.duration_since(since).ok())
+        .unwrap_or_default()
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
@@ -1,6 +1,7 @@
 //! Audio device and audio stream APIs.
 use super::Client;
-use crate::types::{AudioPreprocessor, SoundDevice, UserId};
+use crate::types::{AudioPreprocessor, SoundDevice, StreamTypes, UserId};
+use std::time::Duration;
 use teamtalk_sys as ffi;
 
 impl Client {
@@ -243,12 +244,17 @@ impl Client {
     }
 
     /// Enables audio block events for a user.
-    pub fn enable_audio_block_event(&self, user_id: UserId, types: u32, enable: bool) -> bool {
+    pub fn enable_audio_block_event(
+        &self,
+        user_id: UserId,
+        types: StreamTypes,
+        enable: bool,
+    ) -> bool {
         unsafe {
             ffi::api().TT_EnableAudioBlockEvent(
                 self.ptr,
                 user_id.0,
-                types,
+                types.raw(),
                 if enable { 1 } else { 0 },
             ) == 1
         }
@@ -272,7 +278,7 @@ impl Client {
     pub fn enable_audio_block_event_ex(
         &self,
         user_id: UserId,
-        types: u32,
+        types: StreamTypes,
         format: Option<&ffi::AudioFormat>,
         enable: bool,
     ) -> bool {
@@ -281,7 +287,7 @@ impl Client {
             ffi::api().TT_EnableAudioBlockEventEx(
                 self.ptr,
                 user_id.0,
-                types,
+                types.raw(),
                 fmt_ptr,
                 if enable { 1 } else { 0 },
             ) == 1
@@ -336,11 +342,11 @@ impl Client {
     /// Acquires an audio block for a user.
     pub fn acquire_user_audio_block(
         &self,
-        types: u32,
+        types: StreamTypes,
         user_id: UserId,
     ) -> Option<*mut ffi::AudioBlock> {
         unsafe {
-            let ptr = ffi::api().TT_AcquireUserAudioBlock(self.ptr, types, user_id.0);
+            let ptr = ffi::api().TT_AcquireUserAudioBlock(self.ptr, types.raw(), user_id.0);
             if ptr.is_null() { None } else { Some(ptr) }
         }
     }
@@ -350,6 +356,32 @@ impl Client {
         unsafe { ffi::api().TT_InsertAudioBlock(self.ptr, block) == 1 }
     }
 
+    /// Discards audio blocks inserted with `insert_audio_block` that are not yet sent.
+    pub fn flush_audio_input(&self) -> bool {
+        unsafe { ffi::api().TT_InsertAudioBlock(self.ptr, std::ptr::null()) == 1 }
+    }
+
+    /// Acquires the next queued audio block for a user, released when the guard drops.
+    pub fn acquire_audio_block(
+        &self,
+        user_id: UserId,
+        types: StreamTypes,
+    ) -> Option<AudioBlockGuard<'_>> {
+        self.acquire_user_audio_block(types, user_id)
+            .and_then(|block| AudioBlockGuard::new(BlockOwner::Client(self), block))
+    }
+
+    /// Returns an iterator draining all queued audio blocks for a user.
+    ///
+    /// Call after `Event::AudioBlock`; each block is released when its guard drops.
+    pub fn audio_blocks(&self, user_id: UserId, types: StreamTypes) -> AudioBlocks<'_> {
+        AudioBlocks {
+            client: self,
+            user_id,
+            types,
+        }
+    }
+
     #[allow(clippy::missing_safety_doc)]
     /// Releases a previously acquired audio block.
     ///
@@ -405,3 +437,111 @@ impl Client {
         unsafe { ffi::api().TT_CloseSoundLoopbackTest(loopback) == 1 }
     }
 }
+
+/// Releases an `AudioBlockGuard`'s block when it drops.
+pub(crate) enum BlockOwner<'a> {
+    Client(&'a Client),
+    #[cfg(feature = "mock")]
+    Mock(&'a std::cell::Cell<usize>),
+}
+
+/// Audio block acquired from the client, released when dropped.
+pub struct AudioBlockGuard<'a> {
+    owner: BlockOwner<'a>,
+    block: *mut ffi::AudioBlock,
+}
+
+impl<'a> AudioBlockGuard<'a> {
+    /// Wraps an acquired block; returns `None` when the SDK had no block.
+    pub(crate) fn new(owner: BlockOwner<'a>, block: *mut ffi::AudioBlock) -> Option<Self> {
+        if block.is_null() {
+            return None;
+        }
+        Some(Self { owner, block })
+    }
+
+    fn block(&self) -> &ffi::AudioBlock {
+        unsafe { &*self.block }
+    }
+
+    /// Returns the stream id, which changes when a user starts a new transmission.
+    pub fn stream_id(&self) -> i32 {
+        self.block().nStreamID
+    }
+
+    /// Returns the sample rate in Hz.
+    pub fn sample_rate(&self) -> i32 {
+        self.block().nSampleRate
+    }
+
+    /// Returns the number of interleaved channels.
+    pub fn channels(&self) -> i32 {
+        self.block().nChannels
+    }
+
+    /// Returns the number of samples per channel.
+    pub fn sample_count(&self) -> usize {
+        self.block().nSamples.max(0) as usize
+    }
+
+    /// Returns the index of the first sample since the stream started.
+    pub fn sample_index(&self) -> u32 {
+        self.block().uSampleIndex
+    }
+
+    /// Returns the stream types mixed into the block.
+    pub fn stream_types(&self) -> StreamTypes {
+        StreamTypes::from_raw(self.block().uStreamTypes)
+    }
+
+    /// Returns the block duration.
+    pub fn duration(&self) -> Duration {
+        let rate = self.sample_rate();
+        if rate <= 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(self.sample_count() as f64 / rate as f64)
+    }
+
+    /// Returns the interleaved 16-bit PCM samples.
+    pub fn samples(&self) -> &[i16] {
+        let block = self.block();
+        let len = self.sample_count() * block.nChannels.max(0) as usize;
+        if block.lpRawAudio.is_null() || len == 0 {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(block.lpRawAudio as *const i16, len) }
+    }
+
+    /// Returns the underlying SDK block.
+    pub fn raw(&self) -> &ffi::AudioBlock {
+        self.block()
+    }
+}
+
+impl Drop for AudioBlockGuard<'_> {
+    fn drop(&mut self) {
+        match self.owner {
+            BlockOwner::Client(client) => unsafe {
+                client.release_user_audio_block(self.block);
+            },
+            #[cfg(feature = "mock")]
+            BlockOwner::Mock(released) => released.set(released.get() + 1),
+        }
+    }
+}
+
+/// Iterator over queued audio blocks returned by `Client::audio_blocks`.
+pub struct AudioBlocks<'a> {
+    client: &'a Client,
+    user_id: UserId,
+    types: StreamTypes,
+}
+
+impl<'a> Iterator for AudioBlocks<'a> {
+    type Item = AudioBlockGuard<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.client.acquire_audio_block(self.user_id, self.types)
+    }
+}
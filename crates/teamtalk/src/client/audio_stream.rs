@@ -0,0 +1,388 @@
+//! Per-user PCM audio buffering built on audio block events.
+use super::audio::AudioBlockGuard;
+use super::{Client, Message};
+use crate::events::{Event, Result};
+use crate::types::{LOCAL_TX_USER_ID, LOCAL_USER_ID, MUXED_USER_ID, StreamTypes, UserId};
+use crate::utils::wav::{RotatingWavWriter, Rotation, WavSpec};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Owned copy of an audio block.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AudioChunk {
+    pub stream_id: i32,
+    pub sample_rate: i32,
+    pub channels: i32,
+    pub sample_index: u32,
+    pub samples: Vec<i16>,
+}
+
+impl AudioChunk {
+    /// Returns the number of samples per channel.
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels.max(1) as usize
+    }
+}
+
+impl From<&AudioBlockGuard<'_>> for AudioChunk {
+    fn from(block: &AudioBlockGuard<'_>) -> Self {
+        Self {
+            stream_id: block.stream_id(),
+            sample_rate: block.sample_rate(),
+            channels: block.channels(),
+            sample_index: block.sample_index(),
+            samples: block.samples().to_vec(),
+        }
+    }
+}
+
+/// Settings for `UserAudioStream` and `AudioStreams`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioStreamConfig {
+    pub max_buffer: Duration,
+    pub max_gap: Duration,
+    pub wav_dir: Option<PathBuf>,
+    pub rotation: Rotation,
+}
+
+impl Default for AudioStreamConfig {
+    fn default() -> Self {
+        Self {
+            max_buffer: Duration::from_secs(60),
+            max_gap: Duration::from_secs(1),
+            wav_dir: None,
+            rotation: Rotation::default(),
+        }
+    }
+}
+
+impl AudioStreamConfig {
+    /// Creates the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how much audio is kept before the oldest samples are dropped.
+    pub fn max_buffer(mut self, duration: Duration) -> Self {
+        self.max_buffer = duration;
+        self
+    }
+
+    /// Sets the longest gap filled with silence; longer gaps are skipped.
+    pub fn max_gap(mut self, duration: Duration) -> Self {
+        self.max_gap = duration;
+        self
+    }
+
+    /// Writes each stream to WAV files in a directory.
+    pub fn wav_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.wav_dir = Some(dir.into());
+        self
+    }
+
+    /// Sets the WAV file rotation policy.
+    pub fn rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+}
+
+/// Returns the WAV file prefix for a user, naming the muxed and local streams.
+pub fn stream_name(user_id: UserId) -> String {
+    match user_id {
+        MUXED_USER_ID => "muxed".to_string(),
+        LOCAL_USER_ID => "local".to_string(),
+        LOCAL_TX_USER_ID => "local_tx".to_string(),
+        UserId(id) => format!("user{id}"),
+    }
+}
+
+/// Buffered PCM audio of one user, with gaps filled from sample indices.
+pub struct UserAudioStream {
+    user_id: UserId,
+    config: AudioStreamConfig,
+    stream_id: Option<i32>,
+    sample_rate: i32,
+    channels: i32,
+    next_index: Option<u32>,
+    buffer: Vec<i16>,
+    silence_frames: u64,
+    skipped_frames: u64,
+    dropped_frames: u64,
+    writer: Option<RotatingWavWriter>,
+    finished: Vec<PathBuf>,
+}
+
+impl UserAudioStream {
+    /// Creates an empty stream for a user.
+    pub fn new(user_id: UserId, config: AudioStreamConfig) -> Self {
+        Self {
+            user_id,
+            config,
+            stream_id: None,
+            sample_rate: 0,
+            channels: 0,
+            next_index: None,
+            buffer: Vec::new(),
+            silence_frames: 0,
+            skipped_frames: 0,
+            dropped_frames: 0,
+            writer: None,
+            finished: Vec::new(),
+        }
+    }
+
+    /// Returns the user id of the stream.
+    pub fn user_id(&self) -> UserId {
+        self.user_id
+    }
+
+    /// Returns the current stream id.
+    pub fn stream_id(&self) -> Option<i32> {
+        self.stream_id
+    }
+
+    /// Returns the sample rate of the buffered audio.
+    pub fn sample_rate(&self) -> i32 {
+        self.sample_rate
+    }
+
+    /// Returns the number of interleaved channels.
+    pub fn channels(&self) -> i32 {
+        self.channels
+    }
+
+    /// Appends a block, filling gaps with silence and trimming overlaps.
+    ///
+    /// A new stream id or format starts a new WAV file.
+    pub fn push(&mut self, chunk: &AudioChunk) -> Result<()> {
+        if chunk.channels <= 0 || chunk.sample_rate <= 0 {
+            return Ok(());
+        }
+        let channels = chunk.channels as usize;
+        let restart = self.stream_id != Some(chunk.stream_id)
+            || self.sample_rate != chunk.sample_rate
+            || self.channels != chunk.channels;
+        if restart {
+            if self.sample_rate != chunk.sample_rate || self.channels != chunk.channels {
+                self.buffer.clear();
+            }
+            if let Some(writer) = self.writer.take() {
+                self.finished.extend(writer.finish()?);
+            }
+            self.stream_id = Some(chunk.stream_id);
+            self.sample_rate = chunk.sample_rate;
+            self.channels = chunk.channels;
+            self.next_index = None;
+        }
+        let mut samples = chunk.samples.as_slice();
+        if let Some(expected) = self.next_index {
+            let ahead = chunk.sample_index.wrapping_sub(expected);
+            if ahead < u32::MAX / 2 {
+                let gap = ahead as u64;
+                if gap > 0 {
+                    let max_gap = self.config.max_gap.as_secs_f64() * self.sample_rate as f64;
+                    if gap as f64 <= max_gap {
+                        self.append(&vec![0; gap as usize * channels])?;
+                        self.silence_frames += gap;
+                    } else {
+                        self.skipped_frames += gap;
+                    }
+                }
+            } else {
+                let overlap = expected.wrapping_sub(chunk.sample_index) as usize * channels;
+                samples = &samples[overlap.min(samples.len())..];
+            }
+        }
+        let end = chunk
+            .sample_index
+            .wrapping_add((chunk.samples.len() / channels) as u32);
+        // A stale chunk ending before the expected index must not move it back.
+        self.next_index = match self.next_index {
+            Some(expected) if end.wrapping_sub(expected) >= u32::MAX / 2 => Some(expected),
+            _ => Some(end),
+        };
+        self.append(samples)
+    }
+
+    fn append(&mut self, samples: &[i16]) -> Result<()> {
+        if samples.is_empty() {
+            return Ok(());
+        }
+        if let Some(dir) = self.config.wav_dir.as_ref()
+            && self.writer.is_none()
+        {
+            let prefix = format!(
+                "{}_{}",
+                stream_name(self.user_id),
+                self.stream_id.unwrap_or(0)
+            );
+            self.writer = Some(RotatingWavWriter::new(
+                dir,
+                &prefix,
+                WavSpec::new(self.sample_rate as u32, self.channels as u16),
+                self.config.rotation,
+            ));
+        }
+        if let Some(writer) = self.writer.as_mut() {
+            writer.write_samples(samples)?;
+        }
+        self.buffer.extend_from_slice(samples);
+        let channels = self.channels.max(1) as usize;
+        let limit =
+            (self.config.max_buffer.as_secs_f64() * self.sample_rate as f64) as usize * channels;
+        if self.buffer.len() > limit {
+            let excess = self.buffer.len() - limit;
+            self.buffer.drain(..excess);
+            self.dropped_frames += (excess / channels) as u64;
+        }
+        Ok(())
+    }
+
+    /// Returns the buffered interleaved samples.
+    pub fn buffered(&self) -> &[i16] {
+        &self.buffer
+    }
+
+    /// Returns the buffered audio duration.
+    pub fn buffered_duration(&self) -> Duration {
+        if self.sample_rate <= 0 || self.channels <= 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(
+            self.buffer.len() as f64 / (self.sample_rate as f64 * self.channels as f64),
+        )
+    }
+
+    /// Removes and returns all buffered samples.
+    pub fn take(&mut self) -> Vec<i16> {
+        std::mem::take(&mut self.buffer)
+    }
+
+    /// Returns the number of silent frames inserted for gaps.
+    pub fn silence_frames(&self) -> u64 {
+        self.silence_frames
+    }
+
+    /// Returns the number of frames missing in gaps too long to fill.
+    pub fn skipped_frames(&self) -> u64 {
+        self.skipped_frames
+    }
+
+    /// Returns the number of frames dropped from a full buffer.
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped_frames
+    }
+
+    /// Returns and clears the WAV files completed since the last call.
+    pub fn take_finished_files(&mut self) -> Vec<PathBuf> {
+        if let Some(writer) = self.writer.as_mut() {
+            self.finished.extend(writer.take_finished());
+        }
+        std::mem::take(&mut self.finished)
+    }
+
+    /// Closes the current WAV file and returns all completed files not yet taken.
+    pub fn finish(&mut self) -> Result<Vec<PathBuf>> {
+        if let Some(writer) = self.writer.take() {
+            self.finished.extend(writer.finish()?);
+        }
+        Ok(std::mem::take(&mut self.finished))
+    }
+}
+
+/// Collection of per-user streams fed from `Event::AudioBlock`.
+pub struct AudioStreams {
+    config: AudioStreamConfig,
+    streams: HashMap<UserId, UserAudioStream>,
+}
+
+impl AudioStreams {
+    /// Creates an empty collection.
+    pub fn new(config: AudioStreamConfig) -> Self {
+        Self {
+            config,
+            streams: HashMap::new(),
+        }
+    }
+
+    /// Enables audio block events for a user, including `MUXED_USER_ID` and `LOCAL_USER_ID`.
+    pub fn enable(&self, client: &Client, user_id: UserId, types: StreamTypes) -> bool {
+        client.enable_audio_block_event(user_id, types, true)
+    }
+
+    /// Disables audio block events for a user and closes their stream.
+    pub fn disable(
+        &mut self,
+        client: &Client,
+        user_id: UserId,
+        types: StreamTypes,
+    ) -> Result<Vec<PathBuf>> {
+        client.enable_audio_block_event(user_id, types, false);
+        self.remove(user_id)
+    }
+
+    /// Drains the queued audio blocks of an `Event::AudioBlock`.
+    ///
+    /// Returns the user id whose stream received audio.
+    pub fn handle(
+        &mut self,
+        client: &Client,
+        event: Event,
+        msg: &Message,
+    ) -> Result<Option<UserId>> {
+        if event != Event::AudioBlock {
+            return Ok(None);
+        }
+        let user_id = UserId(msg.source());
+        let types = msg.stream_type().unwrap_or(StreamTypes::VOICE);
+        let mut received = false;
+        for block in client.audio_blocks(user_id, types) {
+            self.push(user_id, &AudioChunk::from(&block))?;
+            received = true;
+        }
+        Ok(received.then_some(user_id))
+    }
+
+    /// Appends a chunk to a user's stream, creating it when needed.
+    pub fn push(&mut self, user_id: UserId, chunk: &AudioChunk) -> Result<()> {
+        let config = &self.config;
+        self.streams
+            .entry(user_id)
+            .or_insert_with(|| UserAudioStream::new(user_id, config.clone()))
+            .push(chunk)
+    }
+
+    /// Returns a user's stream.
+    pub fn get(&self, user_id: UserId) -> Option<&UserAudioStream> {
+        self.streams.get(&user_id)
+    }
+
+    /// Returns a user's stream mutably.
+    pub fn get_mut(&mut self, user_id: UserId) -> Option<&mut UserAudioStream> {
+        self.streams.get_mut(&user_id)
+    }
+
+    /// Returns all streams.
+    pub fn iter(&self) -> impl Iterator<Item = &UserAudioStream> {
+        self.streams.values()
+    }
+
+    /// Closes and removes a user's stream, returning its completed WAV files.
+    pub fn remove(&mut self, user_id: UserId) -> Result<Vec<PathBuf>> {
+        match self.streams.remove(&user_id) {
+            Some(mut stream) => stream.finish(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Closes all streams, returning their completed WAV files.
+    pub fn finish(&mut self) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for (_, mut stream) in self.streams.drain() {
+            files.extend(stream.finish()?);
+        }
+        Ok(files)
+    }
+}
@@ -0,0 +1,308 @@
+//! Ban list fetching, import/export and expiring bans.
+use super::Client;
+use super::channel_tree::{ApplyOptions, StepOutcome};
+use crate::events::{Error, Event, Result};
+use crate::types::{BanTypes, BannedUser, ChannelId};
+use crate::utils::csv;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Default page size used by `Client::fetch_bans`.
+pub const BAN_PAGE_SIZE: i32 = 100;
+
+impl Client {
+    /// Lists every ban for a channel, or server bans for `ChannelId(0)`.
+    pub fn fetch_bans(&self, channel_id: ChannelId) -> Result<Vec<BannedUser>> {
+        self.fetch_bans_paged(channel_id, BAN_PAGE_SIZE, 5000)
+    }
+
+    /// Lists every ban using the given page size and per-page timeout.
+    pub fn fetch_bans_paged(
+        &self,
+        channel_id: ChannelId,
+        page_size: i32,
+        timeout_ms: i32,
+    ) -> Result<Vec<BannedUser>> {
+        let page_size = page_size.max(1);
+        let mut bans = Vec::new();
+        let mut index = 0;
+        loop {
+            let cmd_id = self.list_bans(channel_id, index, page_size);
+            let (_, page) = self.collect_command(cmd_id, timeout_ms, |event, msg| match event {
+                Event::BannedUser => msg.banned_user(),
+                _ => None,
+            })?;
+            let done = (page.len() as i32) < page_size;
+            index += page.len() as i32;
+            bans.extend(page);
+            if done {
+                return Ok(bans);
+            }
+        }
+    }
+
+    /// Adds bans that are not already present on the server.
+    pub fn import_bans(
+        &self,
+        channel_id: ChannelId,
+        bans: &[BannedUser],
+        options: &ApplyOptions,
+    ) -> Result<BanReport> {
+        let existing = self.fetch_bans(channel_id)?;
+        let mut report = BanReport {
+            dry_run: options.dry_run,
+            steps: Vec::new(),
+        };
+        let mut failed = false;
+        for ban in bans
+            .iter()
+            .filter(|ban| !existing.iter().any(|e| same_ban(e, ban)))
+        {
+            let (cmd_id, outcome) = if options.dry_run {
+                (None, StepOutcome::Planned)
+            } else if failed && options.stop_on_error {
+                (None, StepOutcome::Skipped)
+            } else {
+                let cmd_id = self.ban(ban);
+                let outcome = match self.wait_for_command(cmd_id, options.timeout_ms) {
+                    Ok(_) => StepOutcome::Applied,
+                    Err(err) => {
+                        failed = true;
+                        StepOutcome::Failed(err)
+                    }
+                };
+                ((cmd_id > 0).then_some(cmd_id), outcome)
+            };
+            report.steps.push(BanResult {
+                ban: ban.clone(),
+                cmd_id,
+                outcome,
+            });
+        }
+        Ok(report)
+    }
+}
+
+fn same_ban(a: &BannedUser, b: &BannedUser) -> bool {
+    a.ip == b.ip
+        && a.username == b.username
+        && a.channel_path == b.channel_path
+        && a.ban_types == b.ban_types
+}
+
+/// Writes a ban list as pretty JSON.
+#[cfg(feature = "json")]
+pub fn bans_to_json(bans: &[BannedUser]) -> Result<String> {
+    serde_json::to_string_pretty(bans).map_err(|e| Error::Format(e.to_string()))
+}
+
+/// Reads a ban list from a JSON array.
+#[cfg(feature = "json")]
+pub fn bans_from_json(text: &str) -> Result<Vec<BannedUser>> {
+    serde_json::from_str(text).map_err(|e| Error::Format(e.to_string()))
+}
+
+/// Imported ban together with its outcome.
+#[derive(Debug)]
+pub struct BanResult {
+    pub ban: BannedUser,
+    pub cmd_id: Option<i32>,
+    pub outcome: StepOutcome,
+}
+
+/// Per-ban results of `Client::import_bans`.
+#[derive(Debug, Default)]
+pub struct BanReport {
+    pub dry_run: bool,
+    pub steps: Vec<BanResult>,
+}
+
+impl BanReport {
+    /// Returns true if no ban failed or was skipped.
+    pub fn is_success(&self) -> bool {
+        self.steps
+            .iter()
+            .all(|s| matches!(s.outcome, StepOutcome::Planned | StepOutcome::Applied))
+    }
+
+    /// Returns the failed bans.
+    pub fn failures(&self) -> impl Iterator<Item = &BanResult> {
+        self.steps
+            .iter()
+            .filter(|s| matches!(s.outcome, StepOutcome::Failed(_)))
+    }
+}
+
+/// Ban scheduled to be lifted at a given time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TempBan {
+    pub ban: BannedUser,
+    pub expires_at: SystemTime,
+}
+
+const TEMP_BAN_HEADER: [&str; 8] = [
+    "expires_at",
+    "ip",
+    "channel_path",
+    "nickname",
+    "username",
+    "ban_time",
+    "ban_types",
+    "owner",
+];
+
+/// Lifts bans with `unban_ex` once they expire; entries persist in a CSV file.
+pub struct TempBanScheduler {
+    path: PathBuf,
+    entries: Vec<TempBan>,
+}
+
+impl TempBanScheduler {
+    /// Opens a scheduler backed by `path`, loading entries if the file exists.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(text) => parse_entries(&text)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Self { path, entries })
+    }
+
+    /// Returns the backing file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the scheduled bans.
+    pub fn entries(&self) -> &[TempBan] {
+        &self.entries
+    }
+
+    /// Bans on the server and schedules the ban to be lifted after `duration`.
+    pub fn ban_for(&mut self, client: &Client, ban: BannedUser, duration: Duration) -> Result<i32> {
+        let cmd_id = client.ban(&ban);
+        if cmd_id > 0 {
+            self.schedule(ban, duration)?;
+        }
+        Ok(cmd_id)
+    }
+
+    /// Schedules an existing ban to be lifted after `duration`.
+    pub fn schedule(&mut self, ban: BannedUser, duration: Duration) -> Result<()> {
+        self.schedule_at(ban, SystemTime::now() + duration)
+    }
+
+    /// Schedules an existing ban to be lifted at `expires_at`.
+    pub fn schedule_at(&mut self, ban: BannedUser, expires_at: SystemTime) -> Result<()> {
+        self.entries.retain(|entry| !same_ban(&entry.ban, &ban));
+        self.entries.push(TempBan { ban, expires_at });
+        self.save()
+    }
+
+    /// Drops a scheduled ban without lifting it.
+    pub fn cancel(&mut self, ban: &BannedUser) -> Result<bool> {
+        let before = self.entries.len();
+        self.entries.retain(|entry| !same_ban(&entry.ban, ban));
+        if self.entries.len() == before {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Returns the bans that have expired at `now`.
+    pub fn due(&self, now: SystemTime) -> Vec<&TempBan> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.expires_at <= now)
+            .collect()
+    }
+
+    /// Returns the earliest expiry time.
+    pub fn next_expiry(&self) -> Option<SystemTime> {
+        self.entries.iter().map(|entry| entry.expires_at).min()
+    }
+
+    /// Lifts expired bans and returns them with their command ids.
+    pub fn tick(&mut self, client: &Client) -> Result<Vec<(BannedUser, i32)>> {
+        let now = SystemTime::now();
+        let mut lifted = Vec::new();
+        self.entries.retain(|entry| {
+            if entry.expires_at > now {
+                return true;
+            }
+            let cmd_id = client.unban_ex(&entry.ban);
+            if cmd_id > 0 {
+                lifted.push((entry.ban.clone(), cmd_id));
+                false
+            } else {
+                true
+            }
+        });
+        if !lifted.is_empty() {
+            self.save()?;
+        }
+        Ok(lifted)
+    }
+
+    /// Writes the entries to the backing file.
+    pub fn save(&self) -> Result<()> {
+        let mut text = csv::write_row(&TEMP_BAN_HEADER);
+        for entry in &self.entries {
+            let expires = entry
+                .expires_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let ban = &entry.ban;
+            text.push_str(&csv::write_row(&[
+                expires.to_string(),
+                ban.ip.clone(),
+                ban.channel_path.clone(),
+                ban.nickname.clone(),
+                ban.username.clone(),
+                ban.ban_time.clone(),
+                ban.ban_types.raw().to_string(),
+                ban.owner.clone(),
+            ]));
+        }
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, text)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+fn parse_entries(text: &str) -> Result<Vec<TempBan>> {
+    let mut entries = Vec::new();
+    for (line, row) in csv::parse(text)?.into_iter().enumerate().skip(1) {
+        if row.len() != TEMP_BAN_HEADER.len() {
+            return Err(Error::Format(format!(
+                "line {}: expected {} fields",
+                line + 1,
+                TEMP_BAN_HEADER.len()
+            )));
+        }
+        let number = |value: &str| {
+            value
+                .parse::<u64>()
+                .map_err(|_| Error::Format(format!("line {}: invalid number", line + 1)))
+        };
+        let expires = number(&row[0])?;
+        let ban_types = number(&row[6])? as u32;
+        entries.push(TempBan {
+            expires_at: UNIX_EPOCH + Duration::from_secs(expires),
+            ban: BannedUser {
+                ip: row[1].clone(),
+                channel_path: row[2].clone(),
+                nickname: row[3].clone(),
+                username: row[4].clone(),
+                ban_time: row[5].clone(),
+                ban_types: BanTypes::from_raw(ban_types),
+                owner: row[7].clone(),
+            },
+        });
+    }
+    Ok(entries)
+}
@@ -0,0 +1,501 @@
+//! Declarative channel tree export, diff and apply.
+use super::Client;
+use crate::events::{Error, Result};
+use crate::types::{AudioCodec, AudioConfig, Channel, ChannelPath, ChannelTypes};
+use std::collections::BTreeMap;
+
+/// Desired settings for a channel; `None` fields are left as they are.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ChannelSpec {
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub topic: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub channel_type: Option<ChannelTypes>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub max_users: Option<i32>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub disk_quota: Option<i64>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub user_data: Option<i32>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub audio_codec: Option<AudioCodec>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub audio_cfg: Option<AudioConfig>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub queue_delay_ms: Option<i32>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub timeout_voice_ms: Option<i32>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub timeout_media_ms: Option<i32>,
+}
+
+impl ChannelSpec {
+    /// Creates an empty spec that manages no settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a spec that pins every setting of an existing channel.
+    pub fn from_channel(channel: &Channel) -> Self {
+        Self {
+            topic: Some(channel.topic.clone()),
+            channel_type: Some(channel.channel_type),
+            max_users: Some(channel.max_users),
+            disk_quota: Some(channel.disk_quota),
+            user_data: Some(channel.user_data),
+            audio_codec: Some(channel.audio_codec),
+            audio_cfg: Some(channel.audio_cfg),
+            queue_delay_ms: Some(channel.queue_delay_ms),
+            timeout_voice_ms: Some(channel.timeout_voice_ms),
+            timeout_media_ms: Some(channel.timeout_media_ms),
+        }
+    }
+
+    /// Sets the channel topic.
+    pub fn topic(mut self, topic: &str) -> Self {
+        self.topic = Some(topic.to_string());
+        self
+    }
+
+    /// Sets the channel type flags.
+    pub fn channel_type(mut self, t: ChannelTypes) -> Self {
+        self.channel_type = Some(t);
+        self
+    }
+
+    /// Sets the maximum number of users.
+    pub fn max_users(mut self, max: i32) -> Self {
+        self.max_users = Some(max);
+        self
+    }
+
+    /// Sets the disk quota in bytes.
+    pub fn disk_quota(mut self, quota: i64) -> Self {
+        self.disk_quota = Some(quota);
+        self
+    }
+
+    /// Sets the user data value.
+    pub fn user_data(mut self, data: i32) -> Self {
+        self.user_data = Some(data);
+        self
+    }
+
+    /// Sets the audio codec configuration.
+    pub fn codec(mut self, codec: AudioCodec) -> Self {
+        self.audio_codec = Some(codec);
+        self
+    }
+
+    /// Sets the audio input configuration.
+    pub fn audio_cfg(mut self, cfg: AudioConfig) -> Self {
+        self.audio_cfg = Some(cfg);
+        self
+    }
+
+    /// Returns the names of managed settings that differ from `current`.
+    pub fn changes(&self, current: &ChannelSpec) -> Vec<&'static str> {
+        fn differs<T: PartialEq>(want: &Option<T>, have: &Option<T>) -> bool {
+            want.is_some() && want != have
+        }
+        let mut out = Vec::new();
+        if differs(&self.topic, &current.topic) {
+            out.push("topic");
+        }
+        if differs(&self.channel_type, &current.channel_type) {
+            out.push("channel_type");
+        }
+        if differs(&self.max_users, &current.max_users) {
+            out.push("max_users");
+        }
+        if differs(&self.disk_quota, &current.disk_quota) {
+            out.push("disk_quota");
+        }
+        if differs(&self.user_data, &current.user_data) {
+            out.push("user_data");
+        }
+        if differs(&self.audio_codec, &current.audio_codec) {
+            out.push("audio_codec");
+        }
+        if differs(&self.audio_cfg, &current.audio_cfg) {
+            out.push("audio_cfg");
+        }
+        if differs(&self.queue_delay_ms, &current.queue_delay_ms) {
+            out.push("queue_delay_ms");
+        }
+        if differs(&self.timeout_voice_ms, &current.timeout_voice_ms) {
+            out.push("timeout_voice_ms");
+        }
+        if differs(&self.timeout_media_ms, &current.timeout_media_ms) {
+            out.push("timeout_media_ms");
+        }
+        out
+    }
+
+    /// Writes the managed settings into a channel.
+    pub fn apply_to(&self, channel: &mut Channel) {
+        if let Some(topic) = &self.topic {
+            channel.topic = topic.clone();
+        }
+        if let Some(t) = self.channel_type {
+            channel.channel_type = t;
+        }
+        if let Some(max) = self.max_users {
+            channel.max_users = max;
+        }
+        if let Some(quota) = self.disk_quota {
+            channel.disk_quota = quota;
+        }
+        if let Some(data) = self.user_data {
+            channel.user_data = data;
+        }
+        if let Some(codec) = self.audio_codec {
+            channel.audio_codec = codec;
+        }
+        if let Some(cfg) = self.audio_cfg {
+            channel.audio_cfg = cfg;
+        }
+        if let Some(delay) = self.queue_delay_ms {
+            channel.queue_delay_ms = delay;
+        }
+        if let Some(timeout) = self.timeout_voice_ms {
+            channel.timeout_voice_ms = timeout;
+        }
+        if let Some(timeout) = self.timeout_media_ms {
+            channel.timeout_media_ms = timeout;
+        }
+    }
+}
+
+/// Channel layout keyed by channel path.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ChannelTree {
+    /// Removes live channels missing from the tree when set.
+    pub prune: bool,
+    pub channels: BTreeMap<ChannelPath, ChannelSpec>,
+}
+
+impl ChannelTree {
+    /// Creates an empty tree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables or disables removal of channels missing from the tree.
+    pub fn prune(mut self, prune: bool) -> Self {
+        self.prune = prune;
+        self
+    }
+
+    /// Adds or replaces a channel spec.
+    pub fn channel(mut self, path: ChannelPath, spec: ChannelSpec) -> Self {
+        self.insert(path, spec);
+        self
+    }
+
+    /// Adds or replaces a channel spec.
+    pub fn insert(&mut self, path: ChannelPath, spec: ChannelSpec) {
+        self.channels.insert(path, spec);
+    }
+
+    /// Returns the spec for a path.
+    pub fn get(&self, path: &ChannelPath) -> Option<&ChannelSpec> {
+        self.channels.get(path)
+    }
+
+    /// Reads the live channel tree from a client.
+    pub fn export(client: &Client) -> Self {
+        let mut tree = Self::new();
+        for channel in client.get_server_channels() {
+            if let Ok(path) = client.channel_path(channel.id) {
+                tree.insert(path, ChannelSpec::from_channel(&channel));
+            }
+        }
+        tree
+    }
+
+    fn with_ancestors(&self) -> BTreeMap<ChannelPath, ChannelSpec> {
+        let mut out = self.channels.clone();
+        for path in self.channels.keys() {
+            for ancestor in path.ancestors() {
+                out.entry(ancestor).or_default();
+            }
+        }
+        out
+    }
+
+    /// Computes the steps that turn `current` into this tree.
+    pub fn diff(&self, current: &ChannelTree) -> ChannelPlan {
+        let desired = self.with_ancestors();
+        let live = current.with_ancestors();
+        let mut steps = Vec::new();
+        for (path, spec) in &desired {
+            match live.get(path) {
+                Some(have) => {
+                    let changes = spec.changes(have);
+                    if !changes.is_empty() {
+                        steps.push(PlanStep {
+                            action: PlanAction::Update,
+                            path: path.clone(),
+                            spec: spec.clone(),
+                            changes,
+                        });
+                    }
+                }
+                None if !path.is_root() => steps.push(PlanStep {
+                    action: PlanAction::Create,
+                    path: path.clone(),
+                    spec: spec.clone(),
+                    changes: spec.changes(&ChannelSpec::default()),
+                }),
+                None => {}
+            }
+        }
+        if self.prune {
+            for path in live.keys().rev() {
+                if !path.is_root() && !desired.contains_key(path) {
+                    steps.push(PlanStep {
+                        action: PlanAction::Remove,
+                        path: path.clone(),
+                        spec: ChannelSpec::default(),
+                        changes: Vec::new(),
+                    });
+                }
+            }
+        }
+        ChannelPlan { steps }
+    }
+
+    /// Parses a tree from JSON.
+    #[cfg(feature = "json")]
+    pub fn from_json(text: &str) -> Result<Self> {
+        serde_json::from_str(text).map_err(|e| Error::Format(e.to_string()))
+    }
+
+    /// Serializes the tree as pretty JSON.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| Error::Format(e.to_string()))
+    }
+
+    /// Parses a tree from TOML.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<Self> {
+        toml::from_str(text).map_err(|e| Error::Format(e.to_string()))
+    }
+
+    /// Serializes the tree as TOML.
+    #[cfg(feature = "toml")]
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).map_err(|e| Error::Format(e.to_string()))
+    }
+
+    /// Parses a tree from YAML.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(text: &str) -> Result<Self> {
+        serde_yaml::from_str(text).map_err(|e| Error::Format(e.to_string()))
+    }
+
+    /// Serializes the tree as YAML.
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml(&self) -> Result<String> {
+        serde_yaml::to_string(self).map_err(|e| Error::Format(e.to_string()))
+    }
+}
+
+/// Kind of change in a channel plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanAction {
+    Create,
+    Update,
+    Remove,
+}
+
+/// Single change in a channel plan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanStep {
+    pub action: PlanAction,
+    pub path: ChannelPath,
+    pub spec: ChannelSpec,
+    pub changes: Vec<&'static str>,
+}
+
+/// Ordered list of changes produced by `ChannelTree::diff`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ChannelPlan {
+    pub steps: Vec<PlanStep>,
+}
+
+impl ChannelPlan {
+    /// Returns true if the plan has no steps.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Returns the number of steps with the given action.
+    pub fn count(&self, action: PlanAction) -> usize {
+        self.steps.iter().filter(|s| s.action == action).count()
+    }
+
+    /// Executes the plan and waits for each command to complete.
+    pub fn apply(&self, client: &Client, options: &ApplyOptions) -> ApplyReport {
+        let mut report = ApplyReport {
+            dry_run: options.dry_run,
+            steps: Vec::with_capacity(self.steps.len()),
+        };
+        let mut failed = false;
+        for step in &self.steps {
+            let (cmd_id, outcome) = if options.dry_run {
+                (None, StepOutcome::Planned)
+            } else if failed && options.stop_on_error {
+                (None, StepOutcome::Skipped)
+            } else {
+                match run_step(client, step, options.timeout_ms) {
+                    (cmd_id, Ok(())) => (cmd_id, StepOutcome::Applied),
+                    (cmd_id, Err(err)) => {
+                        failed = true;
+                        (cmd_id, StepOutcome::Failed(err))
+                    }
+                }
+            };
+            report.steps.push(StepResult {
+                step: step.clone(),
+                cmd_id,
+                outcome,
+            });
+        }
+        report
+    }
+}
+
+fn run_step(client: &Client, step: &PlanStep, timeout_ms: i32) -> (Option<i32>, Result<()>) {
+    let cmd_id = match step.action {
+        PlanAction::Create => {
+            let parent = step.path.parent().unwrap_or_default();
+            let parent_id = match client.channel_id_by_path(&parent) {
+                Ok(id) => id,
+                Err(err) => return (None, Err(err)),
+            };
+            let name = step.path.name().unwrap_or_default();
+            let mut channel = Channel::builder(name).parent(parent_id).build();
+            step.spec.apply_to(&mut channel);
+            client.make_channel(&channel)
+        }
+        PlanAction::Update => {
+            let id = match client.channel_id_by_path(&step.path) {
+                Ok(id) => id,
+                Err(err) => return (None, Err(err)),
+            };
+            let Some(mut channel) = client.get_channel(id) else {
+                return (None, Err(Error::InvalidParam));
+            };
+            step.spec.apply_to(&mut channel);
+            client.update_channel(&channel)
+        }
+        PlanAction::Remove => match client.channel_id_by_path(&step.path) {
+            Ok(id) => client.remove_channel(id),
+            Err(err) => return (None, Err(err)),
+        },
+    };
+    let result = client.wait_for_command(cmd_id, timeout_ms).map(|_| ());
+    ((cmd_id > 0).then_some(cmd_id), result)
+}
+
+/// Settings for `ChannelPlan::apply`.
+#[derive(Debug, Clone)]
+pub struct ApplyOptions {
+    pub dry_run: bool,
+    pub timeout_ms: i32,
+    pub stop_on_error: bool,
+}
+
+impl Default for ApplyOptions {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            timeout_ms: 5000,
+            stop_on_error: true,
+        }
+    }
+}
+
+impl ApplyOptions {
+    /// Creates options with defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reports the plan without sending any command.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Sets how long to wait for each command reply.
+    pub fn timeout_ms(mut self, timeout_ms: i32) -> Self {
+        self.timeout_ms = timeout_ms;
+        self
+    }
+
+    /// Skips remaining steps after the first failure.
+    pub fn stop_on_error(mut self, stop: bool) -> Self {
+        self.stop_on_error = stop;
+        self
+    }
+}
+
+/// Result of a single plan step.
+#[derive(Debug)]
+pub enum StepOutcome {
+    Planned,
+    Applied,
+    Skipped,
+    Failed(Error),
+}
+
+/// Plan step together with its outcome.
+#[derive(Debug)]
+pub struct StepResult {
+    pub step: PlanStep,
+    pub cmd_id: Option<i32>,
+    pub outcome: StepOutcome,
+}
+
+/// Per-step results of applying a plan.
+#[derive(Debug, Default)]
+pub struct ApplyReport {
+    pub dry_run: bool,
+    pub steps: Vec<StepResult>,
+}
+
+impl ApplyReport {
+    /// Returns true if no step failed or was skipped.
+    pub fn is_success(&self) -> bool {
+        self.steps
+            .iter()
+            .all(|s| matches!(s.outcome, StepOutcome::Planned | StepOutcome::Applied))
+    }
+
+    /// Returns the failed steps.
+    pub fn failures(&self) -> impl Iterator<Item = &StepResult> {
+        self.steps
+            .iter()
+            .filter(|s| matches!(s.outcome, StepOutcome::Failed(_)))
+    }
+}
+
+impl Client {
+    /// Reads the live channel tree.
+    pub fn export_channel_tree(&self) -> ChannelTree {
+        ChannelTree::export(self)
+    }
+
+    /// Diffs a tree against the live channels and applies the resulting plan.
+    pub fn apply_channel_tree(&self, tree: &ChannelTree, options: &ApplyOptions) -> ApplyReport {
+        tree.diff(&self.export_channel_tree()).apply(self, options)
+    }
+}
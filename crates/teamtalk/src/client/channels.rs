@@ -1,6 +1,7 @@
 //! Channel management APIs.
 use super::Client;
-use crate::types::{Channel, ChannelId, TT_STRLEN, UserId};
+use crate::events::{Error, Result};
+use crate::types::{Channel, ChannelId, ChannelPath, TT_STRLEN, UserId};
 use crate::utils::{ToTT, strings::tt_buf};
 use teamtalk_sys as ffi;
 
@@ -46,17 +47,161 @@ impl Client {
         ChannelId(unsafe { ffi::api().TT_GetChannelIDFromPath(self.ptr, path.tt().as_ptr()) })
     }
 
+    /// Returns the path of a channel.
+    pub fn channel_path(&self, id: ChannelId) -> Result<ChannelPath> {
+        let path = self.get_channel_path(id);
+        if path.is_empty() {
+            return Err(Error::ChannelNotFound(format!("#{}", id.0)));
+        }
+        Ok(ChannelPath::from_sdk_path(&path))
+    }
+
+    /// Returns the id of the channel at a path.
+    pub fn channel_id_by_path(&self, path: &ChannelPath) -> Result<ChannelId> {
+        if path.is_root() {
+            let root = self.get_root_channel_id();
+            if root.0 > 0 {
+                return Ok(root);
+            }
+        } else {
+            let id = self.get_channel_id_from_path(&path.to_sdk_path());
+            if id.0 > 0 {
+                return Ok(id);
+            }
+        }
+        Err(Error::ChannelNotFound(path.to_string()))
+    }
+
+    /// Returns the channel at a path.
+    pub fn channel_by_path(&self, path: &ChannelPath) -> Result<Channel> {
+        let id = self.channel_id_by_path(path)?;
+        self.get_channel(id)
+            .ok_or_else(|| Error::ChannelNotFound(path.to_string()))
+    }
+
+    /// Joins the channel at a path.
+    pub fn join_channel_by_path(&self, path: &ChannelPath, password: &str) -> Result<i32> {
+        let id = self.channel_id_by_path(path)?;
+        let cmd_id = self.join_channel(id, password);
+        if cmd_id > 0 {
+            Ok(cmd_id)
+        } else {
+            Err(Error::CommandFailed {
+                code: -1,
+                message: format!("join {path} was not sent"),
+            })
+        }
+    }
+
+    /// Returns server channels whose path matches a glob, sorted by path.
+    ///
+    /// See `ChannelPath::matches` for the pattern syntax.
+    pub fn find_channels(&self, glob: &str) -> Vec<(ChannelPath, Channel)> {
+        let mut found: Vec<(ChannelPath, Channel)> = self
+            .get_server_channels()
+            .into_iter()
+            .filter_map(|channel| {
+                let path = self.channel_path(channel.id).ok()?;
+                path.matches(glob).then_some((path, channel))
+            })
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        found
+    }
+
     /// Joins a channel by id.
     pub fn join_channel(&self, id: ChannelId, password: &str) -> i32 {
+        let cmd_id = self.send_join_channel(id, password);
+        if cmd_id > 0 {
+            let mut auto = self.auto_reconnect.borrow_mut();
+            auto.last_channel = Some(id);
+            auto.temp_channel = None;
+            auto.pending_temp = None;
+        }
+        cmd_id
+    }
+
+    fn send_join_channel(&self, id: ChannelId, password: &str) -> i32 {
         let cmd_id =
             unsafe { ffi::api().TT_DoJoinChannelByID(self.ptr, id.0, password.tt().as_ptr()) };
         if cmd_id > 0 {
             self.set_connection_state(crate::events::ConnectionState::Joining(id));
-            self.auto_reconnect.borrow_mut().last_channel = Some(id);
         }
         cmd_id
     }
 
+    /// Creates a temporary channel and joins it.
+    ///
+    /// Once the server confirms the command, the channel is remembered so
+    /// auto-reconnect can recreate it, or rejoin it if other users kept it alive.
+    pub fn join_new_channel(&self, channel: &Channel) -> i32 {
+        let cmd_id = self.send_join_new_channel(channel);
+        if cmd_id > 0 {
+            let parent = self.channel_path(channel.parent_id).ok();
+            let mut auto = self.auto_reconnect.borrow_mut();
+            auto.last_channel = None;
+            auto.temp_channel = None;
+            auto.pending_temp = Some((
+                cmd_id,
+                super::TempChannel {
+                    channel: channel.clone(),
+                    parent,
+                },
+            ));
+        }
+        cmd_id
+    }
+
+    fn send_join_new_channel(&self, channel: &Channel) -> i32 {
+        let mut raw = channel.to_ffi();
+        raw.nChannelID = 0;
+        let cmd_id = unsafe { ffi::api().TT_DoJoinChannel(self.ptr, &raw) };
+        if cmd_id > 0 {
+            self.set_connection_state(crate::events::ConnectionState::Creating);
+        }
+        cmd_id
+    }
+
+    pub(crate) fn rejoin_temp_channel(&self, channel: Channel) -> i32 {
+        let existing = self
+            .get_server_channels()
+            .into_iter()
+            .find(|c| c.parent_id == channel.parent_id && c.name == channel.name);
+        match existing {
+            Some(existing) => self.send_join_channel(existing.id, &channel.password),
+            None => self.send_join_new_channel(&channel),
+        }
+    }
+
+    /// Returns the temporary channel remembered by `join_new_channel`, if any.
+    pub fn temp_channel(&self) -> Option<Channel> {
+        self.auto_reconnect
+            .borrow()
+            .temp_channel
+            .as_ref()
+            .map(|temp| temp.channel.clone())
+    }
+
+    /// Forgets the remembered temporary channel.
+    pub fn clear_temp_channel(&self) {
+        let mut auto = self.auto_reconnect.borrow_mut();
+        auto.temp_channel = None;
+        auto.pending_temp = None;
+    }
+
+    /// Settles the pending `join_new_channel` command; returns true if `cmd_id` was it.
+    pub(crate) fn resolve_pending_temp(&self, cmd_id: i32, success: bool) -> bool {
+        let mut auto = self.auto_reconnect.borrow_mut();
+        if !matches!(auto.pending_temp, Some((pending, _)) if pending == cmd_id) {
+            return false;
+        }
+        let pending = auto.pending_temp.take().map(|(_, temp)| temp);
+        if success {
+            auto.temp_channel = pending;
+        }
+        true
+    }
+
     /// Joins the root channel.
     pub fn join_root(&self) -> i32 {
         let root_id = self.get_root_channel_id();
@@ -67,7 +212,10 @@ impl Client {
     pub fn leave_channel(&self) -> i32 {
         let cmd_id = unsafe { ffi::api().TT_DoLeaveChannel(self.ptr) };
         if cmd_id > 0 {
-            self.auto_reconnect.borrow_mut().last_channel = None;
+            let mut auto = self.auto_reconnect.borrow_mut();
+            auto.last_channel = None;
+            auto.temp_channel = None;
+            auto.pending_temp = None;
         }
         cmd_id
     }
@@ -0,0 +1,283 @@
+//! Classroom and solo-transmit channel moderation.
+use super::{Client, Message};
+use crate::events::{Error, Event, Result};
+use crate::types::{Channel, ChannelId, ChannelTypes, StreamTypes, UserId};
+use std::collections::{HashMap, VecDeque};
+use teamtalk_sys as ffi;
+
+impl Client {
+    /// Allows a user to transmit streams in a classroom channel.
+    pub fn allow_transmit(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        streams: StreamTypes,
+    ) -> Result<i32> {
+        let mut channel = self.classroom_channel(channel_id)?;
+        if !channel.allow_transmit(user_id, streams) {
+            return Err(Error::CommandFailed {
+                code: -1,
+                message: format!("transmit list of channel {} is full", channel_id.0),
+            });
+        }
+        Ok(self.update_channel(&channel))
+    }
+
+    /// Revokes a user's permission to transmit streams in a classroom channel.
+    ///
+    /// Returns `Ok(0)` when the user had none of the streams.
+    pub fn revoke_transmit(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        streams: StreamTypes,
+    ) -> Result<i32> {
+        let mut channel = self.classroom_channel(channel_id)?;
+        if !channel.revoke_transmit(user_id, streams) {
+            return Ok(0);
+        }
+        Ok(self.update_channel(&channel))
+    }
+
+    /// Returns the users queued to transmit in a solo-transmit channel.
+    pub fn transmit_queue(&self, channel_id: ChannelId) -> Result<Vec<UserId>> {
+        self.get_channel(channel_id)
+            .map(|channel| channel.transmit_users_queue)
+            .ok_or_else(|| Error::ChannelNotFound(format!("#{}", channel_id.0)))
+    }
+
+    fn classroom_channel(&self, channel_id: ChannelId) -> Result<Channel> {
+        let channel = self
+            .get_channel(channel_id)
+            .ok_or_else(|| Error::ChannelNotFound(format!("#{}", channel_id.0)))?;
+        if !channel.channel_type.contains(ChannelTypes::CLASSROOM) {
+            return Err(Error::InvalidParam);
+        }
+        Ok(channel)
+    }
+}
+
+struct TransmitState {
+    users: Vec<(UserId, StreamTypes)>,
+    queue: Vec<UserId>,
+}
+
+/// Raises transmit events by comparing channel updates.
+#[derive(Default)]
+pub struct TransmitMonitor {
+    channels: HashMap<ChannelId, TransmitState>,
+}
+
+impl TransmitMonitor {
+    /// Creates an empty monitor.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seeds the monitor with the current server channels.
+    pub fn sync(&mut self, client: &Client) {
+        self.channels.clear();
+        for channel in client.get_server_channels() {
+            self.record(&channel);
+        }
+    }
+
+    /// Processes a polled event and fires hooks for derived transmit events.
+    pub fn handle(
+        &mut self,
+        client: &Client,
+        event: Event,
+        msg: &Message,
+    ) -> Vec<(Event, Message)> {
+        let events = match event {
+            Event::ChannelCreated | Event::ChannelUpdated => match msg.channel() {
+                Some(channel) => self.record(&channel),
+                None => Vec::new(),
+            },
+            Event::ChannelRemoved => {
+                if let Some(channel) = msg.channel() {
+                    self.channels.remove(&channel.id);
+                }
+                Vec::new()
+            }
+            _ => Vec::new(),
+        };
+        events
+            .into_iter()
+            .map(|event| {
+                let mut raw = *msg.raw();
+                raw.nClientEvent = ffi::ClientEvent::CLIENTEVENT_CMD_CHANNEL_UPDATE;
+                let derived = Message::from_raw(raw);
+                client.invoke_hooks(event, &derived);
+                (event, derived)
+            })
+            .collect()
+    }
+
+    /// Records a channel snapshot and returns the transmit events it causes.
+    pub fn record(&mut self, channel: &Channel) -> Vec<Event> {
+        let current = TransmitState {
+            users: channel.transmit_users.clone(),
+            queue: channel.transmit_users_queue.clone(),
+        };
+        let Some(previous) = self.channels.insert(channel.id, current) else {
+            return Vec::new();
+        };
+        let mut events = Vec::new();
+        if previous.users != channel.transmit_users {
+            events.push(Event::TransmitUsersChanged {
+                channel: channel.id,
+            });
+        }
+        if previous.queue != channel.transmit_users_queue {
+            events.push(Event::TransmitQueueChanged {
+                channel: channel.id,
+            });
+        }
+        events
+    }
+
+    /// Returns the last known transmit queue of a channel.
+    pub fn queue(&self, channel_id: ChannelId) -> &[UserId] {
+        self.channels
+            .get(&channel_id)
+            .map_or(&[], |state| state.queue.as_slice())
+    }
+
+    /// Returns the last known transmit list of a channel.
+    pub fn transmit_users(&self, channel_id: ChannelId) -> &[(UserId, StreamTypes)] {
+        self.channels
+            .get(&channel_id)
+            .map_or(&[], |state| state.users.as_slice())
+    }
+}
+
+/// "Raise hand" queue granting the floor in a classroom channel to one user at a time.
+#[derive(Debug, Clone)]
+pub struct RaiseHandQueue {
+    channel_id: ChannelId,
+    streams: StreamTypes,
+    hands: VecDeque<UserId>,
+    speaker: Option<UserId>,
+}
+
+impl RaiseHandQueue {
+    /// Creates a queue which grants `streams` in the channel.
+    pub fn new(channel_id: ChannelId, streams: StreamTypes) -> Self {
+        Self {
+            channel_id,
+            streams,
+            hands: VecDeque::new(),
+            speaker: None,
+        }
+    }
+
+    /// Returns the moderated channel.
+    pub fn channel_id(&self) -> ChannelId {
+        self.channel_id
+    }
+
+    /// Queues a user and returns their position, starting at zero.
+    pub fn raise(&mut self, user_id: UserId) -> usize {
+        if let Some(pos) = self.position(user_id) {
+            return pos;
+        }
+        self.hands.push_back(user_id);
+        self.hands.len() - 1
+    }
+
+    /// Removes a user from the queue; returns true if they were queued.
+    pub fn lower(&mut self, user_id: UserId) -> bool {
+        let before = self.hands.len();
+        self.hands.retain(|id| *id != user_id);
+        self.hands.len() != before
+    }
+
+    /// Returns a user's position in the queue.
+    pub fn position(&self, user_id: UserId) -> Option<usize> {
+        self.hands.iter().position(|id| *id == user_id)
+    }
+
+    /// Returns the queued users in order.
+    pub fn pending(&self) -> impl Iterator<Item = UserId> + '_ {
+        self.hands.iter().copied()
+    }
+
+    /// Returns the number of queued users.
+    pub fn len(&self) -> usize {
+        self.hands.len()
+    }
+
+    /// Returns true if nobody is queued.
+    pub fn is_empty(&self) -> bool {
+        self.hands.is_empty()
+    }
+
+    /// Returns the user who currently has the floor.
+    pub fn speaker(&self) -> Option<UserId> {
+        self.speaker
+    }
+
+    /// Revokes the current speaker and grants the floor to the next queued user.
+    ///
+    /// Both changes are sent as a single channel update; on failure the queue
+    /// and speaker are left unchanged.
+    pub fn next(&mut self, client: &Client) -> Result<Option<UserId>> {
+        if self.speaker.is_none() && self.hands.is_empty() {
+            return Ok(None);
+        }
+        let mut channel = client.classroom_channel(self.channel_id)?;
+        let mut advanced = self.clone();
+        let next = advanced.advance(&mut channel)?;
+        if client.update_channel(&channel) <= 0 {
+            return Err(Error::CommandFailed {
+                code: -1,
+                message: "Command rejected".to_string(),
+            });
+        }
+        *self = advanced;
+        Ok(next)
+    }
+
+    /// Applies the handover to a channel snapshot without sending it.
+    ///
+    /// Revokes the current speaker's streams, grants them to the next queued
+    /// user and makes that user the speaker. The queue is unchanged on error.
+    pub fn advance(&mut self, channel: &mut Channel) -> Result<Option<UserId>> {
+        if let Some(speaker) = self.speaker {
+            channel.revoke_transmit(speaker, self.streams);
+        }
+        let next = self.hands.front().copied();
+        if let Some(user_id) = next
+            && !channel.allow_transmit(user_id, self.streams)
+        {
+            return Err(Error::CommandFailed {
+                code: -1,
+                message: format!("transmit list of channel {} is full", channel.id.0),
+            });
+        }
+        self.hands.pop_front();
+        self.speaker = next;
+        Ok(next)
+    }
+
+    /// Revokes the current speaker without granting the next user.
+    pub fn finish(&mut self, client: &Client) -> Result<Option<UserId>> {
+        let Some(user_id) = self.speaker else {
+            return Ok(None);
+        };
+        client.revoke_transmit(self.channel_id, user_id, self.streams)?;
+        self.speaker = None;
+        Ok(Some(user_id))
+    }
+
+    /// Drops a user who left the channel; returns true if they were the speaker.
+    pub fn remove_user(&mut self, user_id: UserId) -> bool {
+        self.lower(user_id);
+        if self.speaker == Some(user_id) {
+            self.speaker = None;
+            return true;
+        }
+        false
+    }
+}
@@ -164,6 +164,15 @@ impl Client {
         self.auto_reconnect.borrow().enabled
     }
 
+    /// Returns the number of reconnect attempts made by auto-reconnect.
+    pub fn reconnect_attempts(&self) -> u32 {
+        self.auto_reconnect
+            .borrow()
+            .handler
+            .as_ref()
+            .map_or(0, |handler| handler.attempts())
+    }
+
     /// Stores connection parameters for automatic reconnection.
     pub fn set_reconnect_params(&self, params: ConnectParamsOwned) {
         self.auto_reconnect.borrow_mut().params = Some(params);
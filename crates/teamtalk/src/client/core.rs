@@ -78,6 +78,48 @@ impl Client {
             .map(|(_, msg)| msg)
     }
 
+    /// Polls until the command completes and returns its success message.
+    pub fn wait_for_command(&self, cmd_id: i32, timeout_ms: i32) -> crate::events::Result<Message> {
+        self.collect_command(cmd_id, timeout_ms, |_, _| None::<()>)
+            .map(|(msg, _)| msg)
+    }
+
+    pub(crate) fn collect_command<T>(
+        &self,
+        cmd_id: i32,
+        timeout_ms: i32,
+        mut extract: impl FnMut(Event, &Message) -> Option<T>,
+    ) -> crate::events::Result<(Message, Vec<T>)> {
+        if cmd_id <= 0 {
+            return Err(crate::events::Error::CommandFailed {
+                code: -1,
+                message: "Command rejected".to_string(),
+            });
+        }
+        let mut items = Vec::new();
+        let reply = self.poll_until(timeout_ms, |event, msg| {
+            if matches!(event, Event::CmdSuccess | Event::CmdError) {
+                return msg.source() == cmd_id;
+            }
+            items.extend(extract(event, msg));
+            false
+        });
+        match reply {
+            Some((Event::CmdSuccess, msg)) => Ok((msg, items)),
+            Some((_, msg)) => {
+                let err = msg.error().unwrap_or_default();
+                Err(crate::events::Error::CommandFailed {
+                    code: err.code,
+                    message: err.message,
+                })
+            }
+            None => Err(crate::events::Error::CommandFailed {
+                code: -1,
+                message: format!("Timed out waiting for command {cmd_id}"),
+            }),
+        }
+    }
+
     fn update_state_for_event(&self, event: Event, msg: &Message) {
         match event {
             Event::ConnectSuccess => {
@@ -92,6 +134,15 @@ impl Client {
                 self.handle_auto_join();
             }
             Event::MySelfLoggedOut => self.set_connection_state(ConnectionState::Connected),
+            Event::CmdSuccess => {
+                self.resolve_pending_temp(msg.source(), true);
+            }
+            Event::CmdError
+                if self.resolve_pending_temp(msg.source(), false)
+                    && self.connection_state() == ConnectionState::Creating =>
+            {
+                self.set_connection_state(ConnectionState::LoggedIn);
+            }
             Event::UserJoined => {
                 if let Some(user) = msg.user()
                     && user.id == self.my_id()
@@ -1,6 +1,6 @@
 use super::{Client, Message};
 use crate::events::Event;
-use crate::types::{ChannelId, TextMessage, User};
+use crate::types::{ChannelId, TextMessage, User, UserId};
 
 type EventHook = Box<dyn FnMut(&Client, Event, &Message) + Send>;
 type ClientHook = Box<dyn FnMut(&Client) + Send>;
@@ -8,6 +8,8 @@ type ChannelHook = Box<dyn FnMut(&Client, ChannelId) + Send>;
 type UserHook = Box<dyn FnMut(&Client, User) + Send>;
 type TextHook = Box<dyn FnMut(&Client, TextMessage) + Send>;
 type MessageHook = Box<dyn FnMut(&Client, &Message) + Send>;
+type QualityHook = Box<dyn FnMut(&Client, UserId, u8) + Send>;
+type UserIdHook = Box<dyn FnMut(&Client, UserId) + Send>;
 
 /// Event hooks for reacting to client activity.
 #[derive(Default)]
@@ -68,6 +70,10 @@ pub struct ClientHooks {
     on_sound_device_new_default_input_com_device: Option<MessageHook>,
     on_sound_device_new_default_output_com_device: Option<MessageHook>,
     on_reconnecting: Option<MessageHook>,
+    on_quality_degraded: Option<QualityHook>,
+    on_quality_recovered: Option<UserIdHook>,
+    on_transmit_queue_changed: Option<ChannelHook>,
+    on_transmit_users_changed: Option<ChannelHook>,
 }
 
 impl ClientHooks {
@@ -503,6 +509,42 @@ impl ClientHooks {
         self
     }
 
+    /// Registers a handler for degraded user audio quality.
+    pub fn on_quality_degraded(
+        mut self,
+        hook: impl FnMut(&Client, UserId, u8) + Send + 'static,
+    ) -> Self {
+        self.on_quality_degraded = Some(Box::new(hook));
+        self
+    }
+
+    /// Registers a handler for recovered user audio quality.
+    pub fn on_quality_recovered(
+        mut self,
+        hook: impl FnMut(&Client, UserId) + Send + 'static,
+    ) -> Self {
+        self.on_quality_recovered = Some(Box::new(hook));
+        self
+    }
+
+    /// Registers a handler for changes to a channel's transmit queue.
+    pub fn on_transmit_queue_changed(
+        mut self,
+        hook: impl FnMut(&Client, ChannelId) + Send + 'static,
+    ) -> Self {
+        self.on_transmit_queue_changed = Some(Box::new(hook));
+        self
+    }
+
+    /// Registers a handler for changes to who may transmit in a channel.
+    pub fn on_transmit_users_changed(
+        mut self,
+        hook: impl FnMut(&Client, ChannelId) + Send + 'static,
+    ) -> Self {
+        self.on_transmit_users_changed = Some(Box::new(hook));
+        self
+    }
+
     pub(crate) fn fire(&mut self, client: &Client, event: Event, msg: &Message) {
         match event {
             Event::ConnectSuccess => {
@@ -787,6 +829,26 @@ impl ClientHooks {
                     hook(client, msg);
                 }
             }
+            Event::QualityDegraded { user, loss } => {
+                if let Some(hook) = self.on_quality_degraded.as_mut() {
+                    hook(client, user, loss);
+                }
+            }
+            Event::QualityRecovered { user } => {
+                if let Some(hook) = self.on_quality_recovered.as_mut() {
+                    hook(client, user);
+                }
+            }
+            Event::TransmitQueueChanged { channel } => {
+                if let Some(hook) = self.on_transmit_queue_changed.as_mut() {
+                    hook(client, channel);
+                }
+            }
+            Event::TransmitUsersChanged { channel } => {
+                if let Some(hook) = self.on_transmit_users_changed.as_mut() {
+                    hook(client, channel);
+                }
+            }
             _ => {}
         }
 
@@ -0,0 +1,412 @@
+//! Streaming PCM audio into the current channel.
+use super::{Client, Message};
+use crate::events::{Error, Event, Result};
+use crate::types::{AudioCodec, AudioInputProgress, ChannelId};
+use crate::utils::{audio, wav};
+use std::collections::VecDeque;
+use std::io::Read;
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+use teamtalk_sys as ffi;
+
+/// Interleaved 16-bit PCM source for `AudioInjector`.
+pub struct PcmSource {
+    samples: Box<dyn Iterator<Item = i16> + Send>,
+    sample_rate: u32,
+    channels: u16,
+}
+
+impl PcmSource {
+    /// Wraps an iterator of interleaved samples.
+    pub fn from_iter<I>(samples: I, sample_rate: u32, channels: u16) -> Self
+    where
+        I: IntoIterator<Item = i16>,
+        I::IntoIter: Send + 'static,
+    {
+        Self {
+            samples: Box::new(samples.into_iter()),
+            sample_rate,
+            channels: channels.max(1),
+        }
+    }
+
+    /// Wraps a buffer of interleaved samples.
+    pub fn from_samples(samples: Vec<i16>, sample_rate: u32, channels: u16) -> Self {
+        Self::from_iter(samples, sample_rate, channels)
+    }
+
+    /// Reads little-endian 16-bit samples until the reader ends or fails.
+    pub fn from_reader<R: Read + Send + 'static>(
+        reader: R,
+        sample_rate: u32,
+        channels: u16,
+    ) -> Self {
+        let mut reader = std::io::BufReader::new(reader);
+        let samples = std::iter::from_fn(move || {
+            let mut pair = [0u8; 2];
+            reader.read_exact(&mut pair).ok()?;
+            Some(i16::from_le_bytes(pair))
+        });
+        Self::from_iter(samples, sample_rate, channels)
+    }
+
+    /// Receives sample buffers until the sending side is dropped.
+    pub fn from_receiver(receiver: Receiver<Vec<i16>>, sample_rate: u32, channels: u16) -> Self {
+        Self::from_iter(receiver.into_iter().flatten(), sample_rate, channels)
+    }
+
+    /// Loads a 16-bit PCM WAV file.
+    pub fn from_wav(path: impl AsRef<Path>) -> Result<Self> {
+        let (spec, samples) = wav::read(path)?;
+        Ok(Self::from_samples(samples, spec.sample_rate, spec.channels))
+    }
+
+    /// Returns the source sample rate.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Returns the source channel count.
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn read_frame(&mut self, channels: usize) -> Option<Vec<f32>> {
+        let mut frame = Vec::with_capacity(self.channels as usize);
+        for _ in 0..self.channels {
+            frame.push(self.samples.next()? as f32);
+        }
+        Some(audio::remix_frame(&frame, channels))
+    }
+}
+
+/// Converts a source to the target format with linear interpolation.
+struct Converter {
+    source: PcmSource,
+    channels: usize,
+    step: f64,
+    pos: f64,
+    current: Option<Vec<f32>>,
+    next: Option<Vec<f32>>,
+    started: bool,
+}
+
+impl Converter {
+    fn new(source: PcmSource, sample_rate: u32, channels: usize) -> Self {
+        let step = source.sample_rate as f64 / sample_rate.max(1) as f64;
+        Self {
+            source,
+            channels,
+            step,
+            pos: 0.0,
+            current: None,
+            next: None,
+            started: false,
+        }
+    }
+
+    fn next_frame(&mut self, out: &mut Vec<i16>) -> bool {
+        if !self.started {
+            self.started = true;
+            self.current = self.source.read_frame(self.channels);
+            self.next = self.source.read_frame(self.channels);
+        }
+        let Some(current) = self.current.as_ref() else {
+            return false;
+        };
+        let t = self.pos as f32;
+        for (c, a) in current.iter().enumerate() {
+            let b = self.next.as_ref().map_or(*a, |next| next[c]);
+            out.push(
+                (a + (b - a) * t)
+                    .round()
+                    .clamp(i16::MIN as f32, i16::MAX as f32) as i16,
+            );
+        }
+        self.pos += self.step;
+        while self.pos >= 1.0 {
+            self.pos -= 1.0;
+            self.current = self.next.take();
+            if self.current.is_none() {
+                break;
+            }
+            self.next = self.source.read_frame(self.channels);
+        }
+        true
+    }
+}
+
+/// Identifier of a queued injection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct InjectionId(pub u64);
+
+/// Notification raised by `AudioInjector`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InjectorEvent {
+    Started(InjectionId),
+    Finished(InjectionId),
+    Cancelled(InjectionId),
+}
+
+/// Frame of converted audio ready for `Client::insert_audio_block`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InjectFrame {
+    pub stream_id: i32,
+    pub sample_index: u32,
+    pub samples: Vec<i16>,
+}
+
+struct Active {
+    id: InjectionId,
+    stream_id: i32,
+    converter: Option<Converter>,
+    sample_index: u32,
+}
+
+/// Queues PCM sources and inserts them into the channel at the codec's frame size.
+///
+/// Feed `Event::AudioInput` to `handle` so insertion is paced by the SDK queue.
+pub struct AudioInjector {
+    sample_rate: u32,
+    channels: u16,
+    frame_samples: usize,
+    buffer_ms: u32,
+    queued_ms: u32,
+    queue: VecDeque<(InjectionId, PcmSource)>,
+    active: Option<Active>,
+    pending: Option<InjectFrame>,
+    next_id: u64,
+    next_stream: i32,
+    events: Vec<InjectorEvent>,
+}
+
+impl AudioInjector {
+    /// Creates an injector producing frames of `frame_samples` samples per channel.
+    pub fn new(sample_rate: u32, channels: u16, frame_samples: usize) -> Self {
+        Self {
+            sample_rate,
+            channels: channels.max(1),
+            frame_samples: frame_samples.max(1),
+            buffer_ms: 200,
+            queued_ms: 0,
+            queue: VecDeque::new(),
+            active: None,
+            pending: None,
+            next_id: 1,
+            next_stream: 1,
+            events: Vec::new(),
+        }
+    }
+
+    /// Creates an injector matching an audio codec.
+    pub fn for_codec(codec: &AudioCodec) -> Result<Self> {
+        if codec.sample_rate() <= 0 || codec.channels() <= 0 || codec.frame_samples() == 0 {
+            return Err(Error::InvalidParam);
+        }
+        Ok(Self::new(
+            codec.sample_rate() as u32,
+            codec.channels() as u16,
+            codec.frame_samples(),
+        ))
+    }
+
+    /// Creates an injector matching the audio codec of a channel.
+    pub fn for_channel(client: &Client, channel_id: ChannelId) -> Result<Self> {
+        let channel = client
+            .get_channel(channel_id)
+            .ok_or_else(|| Error::ChannelNotFound(format!("#{}", channel_id.0)))?;
+        Self::for_codec(&channel.audio_codec)
+    }
+
+    /// Sets how much audio may wait in the SDK queue, in milliseconds; 0 disables pacing.
+    pub fn buffer_ms(mut self, ms: u32) -> Self {
+        self.buffer_ms = ms;
+        self
+    }
+
+    /// Returns the output sample rate.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Returns the output channel count.
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Returns the samples per channel in each frame.
+    pub fn frame_samples(&self) -> usize {
+        self.frame_samples
+    }
+
+    fn frame_ms(&self) -> u32 {
+        (self.frame_samples as u64 * 1000 / self.sample_rate.max(1) as u64) as u32
+    }
+
+    /// Queues a source and returns its id.
+    pub fn enqueue(&mut self, source: PcmSource) -> InjectionId {
+        let id = InjectionId(self.next_id);
+        self.next_id += 1;
+        self.queue.push_back((id, source));
+        id
+    }
+
+    /// Returns the injection being sent.
+    pub fn current(&self) -> Option<InjectionId> {
+        self.active.as_ref().map(|active| active.id)
+    }
+
+    /// Returns the queued injections in order, excluding the current one.
+    pub fn queued(&self) -> Vec<InjectionId> {
+        self.queue.iter().map(|(id, _)| *id).collect()
+    }
+
+    /// Returns true if nothing is playing or queued.
+    pub fn is_idle(&self) -> bool {
+        self.active.is_none() && self.queue.is_empty()
+    }
+
+    /// Returns the next frame, or `None` while the SDK queue is full or nothing is left.
+    pub fn next_frame(&mut self) -> Option<InjectFrame> {
+        if let Some(frame) = self.pending.take() {
+            return Some(frame);
+        }
+        if self.queued_ms >= self.buffer_ms && self.buffer_ms > 0 {
+            return None;
+        }
+        if self.active.is_none() {
+            let (id, source) = self.queue.pop_front()?;
+            let stream_id = self.next_stream;
+            self.next_stream = self.next_stream % i32::MAX + 1;
+            self.active = Some(Active {
+                id,
+                stream_id,
+                converter: Some(Converter::new(
+                    source,
+                    self.sample_rate,
+                    self.channels as usize,
+                )),
+                sample_index: 0,
+            });
+            self.events.push(InjectorEvent::Started(id));
+        }
+        let frame_ms = self.frame_ms();
+        let len = self.frame_samples * self.channels as usize;
+        let active = self.active.as_mut()?;
+        let converter = active.converter.as_mut()?;
+        let mut samples = Vec::with_capacity(len);
+        while samples.len() < len && converter.next_frame(&mut samples) {}
+        if samples.len() < len {
+            active.converter = None;
+            if samples.is_empty() {
+                if active.sample_index == 0 {
+                    let id = active.id;
+                    self.active = None;
+                    self.events.push(InjectorEvent::Finished(id));
+                    return self.next_frame();
+                }
+                return None;
+            }
+            samples.resize(len, 0);
+        }
+        let frame = InjectFrame {
+            stream_id: active.stream_id,
+            sample_index: active.sample_index,
+            samples,
+        };
+        active.sample_index = active.sample_index.wrapping_add(self.frame_samples as u32);
+        self.queued_ms += frame_ms;
+        Some(frame)
+    }
+
+    /// Updates pacing from SDK progress and detects finished injections.
+    pub fn on_progress(&mut self, progress: &AudioInputProgress) {
+        self.queued_ms = progress.queue_ms;
+        let Some(active) = self.active.as_ref() else {
+            return;
+        };
+        if active.stream_id == progress.stream_id
+            && active.converter.is_none()
+            && self.pending.is_none()
+            && progress.queue_ms == 0
+        {
+            self.events.push(InjectorEvent::Finished(active.id));
+            self.active = None;
+        }
+    }
+
+    /// Inserts frames until the SDK queue is full and returns new notifications.
+    pub fn pump(&mut self, client: &Client) -> Vec<InjectorEvent> {
+        while let Some(mut frame) = self.next_frame() {
+            let block = ffi::AudioBlock {
+                nStreamID: frame.stream_id,
+                nSampleRate: self.sample_rate as i32,
+                nChannels: self.channels as i32,
+                lpRawAudio: frame.samples.as_mut_ptr() as *mut std::ffi::c_void,
+                nSamples: self.frame_samples as i32,
+                uSampleIndex: frame.sample_index,
+                uStreamTypes: ffi::StreamType::STREAMTYPE_VOICE as u32,
+            };
+            if !client.insert_audio_block(&block) {
+                self.queued_ms = self.queued_ms.saturating_sub(self.frame_ms());
+                self.pending = Some(frame);
+                break;
+            }
+        }
+        self.take_events()
+    }
+
+    /// Processes `Event::AudioInput` and inserts more audio.
+    pub fn handle(&mut self, client: &Client, event: Event, msg: &Message) -> Vec<InjectorEvent> {
+        if event != Event::AudioInput {
+            return Vec::new();
+        }
+        if let Some(progress) = msg.audio_input_progress() {
+            self.on_progress(&progress);
+        }
+        self.pump(client)
+    }
+
+    /// Cancels a queued or current injection; returns true if it was found.
+    ///
+    /// Cancelling the current injection discards audio already queued in the SDK.
+    pub fn cancel(&mut self, client: &Client, id: InjectionId) -> bool {
+        if self.current() == Some(id) {
+            client.flush_audio_input();
+        }
+        self.cancel_local(id)
+    }
+
+    /// Cancels an injection without touching the SDK queue.
+    pub fn cancel_local(&mut self, id: InjectionId) -> bool {
+        if self.current() == Some(id) {
+            self.active = None;
+            self.pending = None;
+            self.queued_ms = 0;
+            self.events.push(InjectorEvent::Cancelled(id));
+            return true;
+        }
+        let Some(pos) = self.queue.iter().position(|(queued, _)| *queued == id) else {
+            return false;
+        };
+        self.queue.remove(pos);
+        self.events.push(InjectorEvent::Cancelled(id));
+        true
+    }
+
+    /// Cancels the current and all queued injections.
+    pub fn cancel_all(&mut self, client: &Client) -> Vec<InjectorEvent> {
+        if let Some(id) = self.current() {
+            self.cancel(client, id);
+        }
+        while let Some((id, _)) = self.queue.pop_front() {
+            self.events.push(InjectorEvent::Cancelled(id));
+        }
+        self.take_events()
+    }
+
+    /// Returns and clears notifications not yet returned by `pump` or `handle`.
+    pub fn take_events(&mut self) -> Vec<InjectorEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
@@ -1,5 +1,5 @@
-use crate::events::Event;
-use crate::types::ClientId;
+use crate::events::{ConnectionState, Event};
+use crate::types::{ClientId, ClientKeepAlive, ClientStatistics};
 use std::collections::{HashMap, VecDeque};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::time::{Duration, Instant, SystemTime};
@@ -12,11 +12,159 @@ pub struct ClientEvent {
     pub at: SystemTime,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HealthStatus {
+    #[default]
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ClientHealth {
     pub last_event: Option<Event>,
     pub last_event_at: Option<SystemTime>,
     pub last_poll_at: Option<SystemTime>,
+    pub status: HealthStatus,
+    pub last_check_at: Option<SystemTime>,
+    pub tcp_ping_ms: Option<i32>,
+    pub udp_ping_ms: Option<i32>,
+    pub failed_pings: u32,
+    pub forced_reconnects: u32,
+    pub last_reconnect_at: Option<SystemTime>,
+    pub registered_at: Option<SystemTime>,
+}
+
+impl ClientHealth {
+    pub fn new(registered_at: SystemTime) -> Self {
+        Self {
+            registered_at: Some(registered_at),
+            ..Self::default()
+        }
+    }
+
+    pub fn silence(&self, now: SystemTime) -> Duration {
+        [
+            self.last_event_at,
+            self.last_reconnect_at,
+            self.registered_at,
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .and_then(|at| now.duration_since(at).ok())
+        .unwrap_or(Duration::ZERO)
+    }
+
+    pub fn record_forced_reconnect(&mut self, now: SystemTime) {
+        self.forced_reconnects += 1;
+        self.failed_pings = 0;
+        self.last_reconnect_at = Some(now);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchdogConfig {
+    pub check_interval: Duration,
+    pub degraded_after: Duration,
+    pub unhealthy_after: Duration,
+    pub max_ping_ms: i32,
+    pub unhealthy_failed_pings: u32,
+    pub force_reconnect: bool,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            check_interval: Duration::from_secs(5),
+            degraded_after: Duration::from_secs(30),
+            unhealthy_after: Duration::from_secs(90),
+            max_ping_ms: 1000,
+            unhealthy_failed_pings: 3,
+            force_reconnect: false,
+        }
+    }
+}
+
+impl WatchdogConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_keep_alive(keep_alive: &ClientKeepAlive) -> Self {
+        let defaults = Self::default();
+        let interval = Duration::from_millis(keep_alive.tcp_interval_ms.max(0) as u64);
+        let lost = Duration::from_millis(keep_alive.lost_ms.max(0) as u64);
+        Self {
+            degraded_after: if interval.is_zero() {
+                defaults.degraded_after
+            } else {
+                interval * 2
+            },
+            unhealthy_after: if lost.is_zero() {
+                defaults.unhealthy_after
+            } else {
+                lost
+            },
+            ..defaults
+        }
+    }
+
+    pub fn check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    pub fn degraded_after(mut self, silence: Duration) -> Self {
+        self.degraded_after = silence;
+        self
+    }
+
+    pub fn unhealthy_after(mut self, silence: Duration) -> Self {
+        self.unhealthy_after = silence;
+        self
+    }
+
+    pub fn max_ping_ms(mut self, ping_ms: i32) -> Self {
+        self.max_ping_ms = ping_ms;
+        self
+    }
+
+    pub fn unhealthy_failed_pings(mut self, count: u32) -> Self {
+        self.unhealthy_failed_pings = count;
+        self
+    }
+
+    pub fn force_reconnect(mut self, enabled: bool) -> Self {
+        self.force_reconnect = enabled;
+        self
+    }
+
+    pub fn ping_failed(&self, stats: Option<&ClientStatistics>) -> bool {
+        match stats {
+            Some(stats) => {
+                let pings = [stats.tcp_ping, stats.udp_ping];
+                if pings.iter().all(|&ping| ping < 0) {
+                    return true;
+                }
+                pings.iter().any(|&ping| ping > self.max_ping_ms)
+            }
+            None => true,
+        }
+    }
+
+    pub fn classify(&self, health: &ClientHealth, now: SystemTime) -> HealthStatus {
+        let silence = health.silence(now);
+        if health.failed_pings >= self.unhealthy_failed_pings.max(1)
+            || silence >= self.unhealthy_after
+        {
+            HealthStatus::Unhealthy
+        } else if health.failed_pings > 0 || silence >= self.degraded_after {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        }
+    }
 }
 
 pub struct ClientManager {
@@ -25,6 +173,7 @@ pub struct ClientManager {
     queue: VecDeque<usize>,
     poll_timeout_ms: i32,
     tick_sleep: Duration,
+    watchdog: Option<WatchdogConfig>,
     tx: Sender<ClientEvent>,
     rx: Receiver<ClientEvent>,
 }
@@ -44,6 +193,7 @@ impl ClientManager {
             queue: VecDeque::new(),
             poll_timeout_ms: 0,
             tick_sleep: Duration::from_millis(1),
+            watchdog: None,
             tx,
             rx,
         }
@@ -51,7 +201,7 @@ impl ClientManager {
 
     pub fn add_client(&mut self, client: crate::client::Client) {
         let id = client.id();
-        self.health.insert(id, ClientHealth::default());
+        self.health.insert(id, ClientHealth::new(SystemTime::now()));
         self.queue.push_back(self.clients.len());
         self.clients.push(client);
     }
@@ -76,6 +226,14 @@ impl ClientManager {
         }
     }
 
+    pub fn clients(&self) -> &[crate::client::Client] {
+        &self.clients
+    }
+
+    pub fn client(&self, id: ClientId) -> Option<&crate::client::Client> {
+        self.clients.iter().find(|c| c.id() == id)
+    }
+
     pub fn set_poll_timeout(&mut self, timeout_ms: i32) {
         self.poll_timeout_ms = timeout_ms;
     }
@@ -84,6 +242,18 @@ impl ClientManager {
         self.tick_sleep = sleep;
     }
 
+    pub fn set_watchdog(&mut self, config: WatchdogConfig) {
+        self.watchdog = Some(config);
+    }
+
+    pub fn disable_watchdog(&mut self) {
+        self.watchdog = None;
+    }
+
+    pub fn watchdog(&self) -> Option<&WatchdogConfig> {
+        self.watchdog.as_ref()
+    }
+
     pub fn events(&self) -> &Receiver<ClientEvent> {
         &self.rx
     }
@@ -118,11 +288,67 @@ impl ClientManager {
                 processed += 1;
             }
         }
+        self.run_watchdog(now);
         if processed == 0 {
             std::thread::sleep(self.tick_sleep);
         }
     }
 
+    fn run_watchdog(&mut self, now: SystemTime) {
+        let Some(config) = self.watchdog.as_ref() else {
+            return;
+        };
+        for client in &self.clients {
+            if client.connection_state() == ConnectionState::Idle {
+                continue;
+            }
+            let entry = self.health.entry(client.id()).or_default();
+            if let Some(last) = entry.last_check_at
+                && now.duration_since(last).unwrap_or(Duration::ZERO) < config.check_interval
+            {
+                continue;
+            }
+            entry.last_check_at = Some(now);
+
+            let stats = client.get_client_statistics();
+            entry.tcp_ping_ms = stats.map(|s| s.tcp_ping);
+            entry.udp_ping_ms = stats.map(|s| s.udp_ping);
+            if config.ping_failed(stats.as_ref()) {
+                entry.failed_pings += 1;
+            } else {
+                entry.failed_pings = 0;
+            }
+
+            let previous = entry.status;
+            let status = config.classify(entry, now);
+            entry.status = status;
+            if status == previous {
+                continue;
+            }
+
+            let event = match status {
+                HealthStatus::Healthy => Event::HealthRecovered,
+                HealthStatus::Degraded => Event::HealthDegraded,
+                HealthStatus::Unhealthy => Event::HealthUnhealthy,
+            };
+            let _ = self.tx.send(ClientEvent {
+                client_id: client.id(),
+                label: client.label(),
+                event,
+                at: now,
+            });
+
+            if status == HealthStatus::Unhealthy
+                && config.force_reconnect
+                && let Some(params) = client.reconnect_params()
+            {
+                let _ = client.disconnect();
+                let _ = client.connect_with_params(&params);
+                entry.record_forced_reconnect(now);
+            }
+        }
+    }
+
     pub fn run(&mut self) -> ! {
         loop {
             let start = Instant::now();
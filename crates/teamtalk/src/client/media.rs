@@ -1,6 +1,9 @@
 //! Media file playback and streaming APIs.
-use super::Client;
-use crate::types::{AudioPreprocessor, MediaFileInfo, UserId, VideoCodec};
+use super::{Client, Message};
+use crate::events::{Error, Event, Result};
+use crate::types::{
+    AudioPreprocessor, MEDIA_OFFSET_IGNORE, MediaFileInfo, TeamTalkPreprocessor, UserId, VideoCodec,
+};
 use crate::utils::ToTT;
 use teamtalk_sys as ffi;
 
@@ -78,6 +81,28 @@ impl Client {
         }
     }
 
+    /// Starts local playback and returns a handle that stops it when dropped.
+    pub fn play_local(
+        &self,
+        file_path: &str,
+        playback: &MediaPlayback,
+    ) -> Result<LocalPlayback<'_>> {
+        let session_id = self.init_local_playback(file_path, playback);
+        if session_id <= 0 {
+            return Err(Error::CommandFailed {
+                code: -1,
+                message: format!("failed to play {file_path}"),
+            });
+        }
+        Ok(LocalPlayback {
+            client: self,
+            session_id,
+            playback: playback.clone(),
+            info: None,
+            done: false,
+        })
+    }
+
     /// Updates local playback parameters.
     pub fn update_local_playback(&self, session_id: i32, playback: &MediaPlayback) -> bool {
         unsafe { ffi::api().TT_UpdateLocalPlayback(self.ptr, session_id, &playback.to_ffi()) == 1 }
@@ -118,3 +143,130 @@ impl Client {
         unsafe { ffi::api().TT_ReleaseUserMediaVideoFrame(self.ptr, frame) == 1 }
     }
 }
+
+/// Local playback session started by `Client::play_local`, stopped when dropped.
+pub struct LocalPlayback<'a> {
+    client: &'a Client,
+    session_id: i32,
+    playback: MediaPlayback,
+    info: Option<MediaFileInfo>,
+    done: bool,
+}
+
+impl LocalPlayback<'_> {
+    /// Returns the SDK session id.
+    pub fn session_id(&self) -> i32 {
+        self.session_id
+    }
+
+    /// Returns the latest progress reported for this session.
+    pub fn info(&self) -> Option<&MediaFileInfo> {
+        self.info.as_ref()
+    }
+
+    /// Returns the elapsed playback time in milliseconds.
+    pub fn elapsed_ms(&self) -> u32 {
+        self.info.as_ref().map_or(0, |info| info.elapsed_ms)
+    }
+
+    /// Returns the file duration in milliseconds, or 0 before the first update.
+    pub fn duration_ms(&self) -> u32 {
+        self.info.as_ref().map_or(0, |info| info.duration_ms)
+    }
+
+    /// Returns playback progress from 0.0 to 1.0, if the duration is known.
+    pub fn progress(&self) -> Option<f64> {
+        self.info.as_ref().and_then(MediaFileInfo::progress)
+    }
+
+    /// Returns true if playback is paused.
+    pub fn is_paused(&self) -> bool {
+        self.playback.paused
+    }
+
+    /// Returns true once playback has finished, failed or been stopped.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Pauses playback.
+    pub fn pause(&mut self) -> bool {
+        self.update(MEDIA_OFFSET_IGNORE, true)
+    }
+
+    /// Resumes playback.
+    pub fn resume(&mut self) -> bool {
+        self.update(MEDIA_OFFSET_IGNORE, false)
+    }
+
+    /// Seeks to `offset_ms`.
+    pub fn seek(&mut self, offset_ms: u32) -> bool {
+        self.update(offset_ms, self.playback.paused)
+    }
+
+    /// Sets the playback gain level (1000 is unity gain).
+    pub fn set_volume(&mut self, gain_level: i32) -> bool {
+        self.playback.preprocessor =
+            AudioPreprocessor::TeamTalk(TeamTalkPreprocessor::gain(gain_level));
+        self.update(MEDIA_OFFSET_IGNORE, self.playback.paused)
+    }
+
+    /// Stops playback.
+    pub fn stop(&mut self) -> bool {
+        if self.done {
+            return false;
+        }
+        self.done = true;
+        self.client.stop_local_playback(self.session_id)
+    }
+
+    /// Processes `Event::LocalMediaFile` updates for this session.
+    ///
+    /// Returns the progress if the event belongs to this session.
+    pub fn handle(&mut self, event: Event, msg: &Message) -> Option<&MediaFileInfo> {
+        if event != Event::LocalMediaFile || msg.source() != self.session_id {
+            return None;
+        }
+        let info = msg.media_file_info()?;
+        match info.status {
+            ffi::MediaFileStatus::MFS_PAUSED => self.playback.paused = true,
+            ffi::MediaFileStatus::MFS_PLAYING | ffi::MediaFileStatus::MFS_STARTED => {
+                self.playback.paused = false
+            }
+            _ => {}
+        }
+        self.done |= info.is_done();
+        self.info = Some(info);
+        self.info.as_ref()
+    }
+
+    fn update(&mut self, offset_ms: u32, paused: bool) -> bool {
+        if self.done {
+            return false;
+        }
+        let playback = MediaPlayback {
+            offset_ms,
+            paused,
+            preprocessor: self.playback.preprocessor,
+        };
+        if !self
+            .client
+            .update_local_playback(self.session_id, &playback)
+        {
+            return false;
+        }
+        self.playback.paused = paused;
+        if offset_ms != MEDIA_OFFSET_IGNORE
+            && let Some(info) = self.info.as_mut()
+        {
+            info.elapsed_ms = offset_ms;
+        }
+        true
+    }
+}
+
+impl Drop for LocalPlayback<'_> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -1,12 +1,18 @@
 //! Core client type and message wrapper.
 use crate::events::{ConnectionState, Error, Event, Result};
-use crate::types::ClientId;
+use crate::types::{ClientId, StreamTypes};
 use std::cell::{Cell, RefCell};
 use std::sync::atomic::{AtomicU64, Ordering};
 pub use teamtalk_sys as ffi;
 
+pub mod accounts;
+pub mod activity;
 pub mod audio;
+pub mod audio_stream;
+pub mod bans;
+pub mod channel_tree;
 pub mod channels;
+pub mod classroom;
 pub mod connection;
 pub mod core;
 pub mod desktop;
@@ -14,19 +20,49 @@ pub mod encryption;
 pub mod files;
 pub mod hooks;
 pub mod hotkeys;
+pub mod injector;
 pub mod manager;
 pub mod media;
+pub mod playlist;
+pub mod quality;
 pub mod recording;
 pub mod registry;
+pub mod segmenter;
 pub mod server;
+pub mod sound_devices;
 pub mod system;
+pub mod transcribe;
 pub mod users;
 pub mod video;
 
+pub use accounts::{AccountPlan, AccountReport, AccountResult, AccountStep};
+pub use activity::{
+    ActivityConfig, ActivitySummary, ActivityTracker, ChannelActivity, ExportFormat, UserActivity,
+};
+pub use audio::{AudioBlockGuard, AudioBlocks};
+pub use audio_stream::{AudioChunk, AudioStreamConfig, AudioStreams, UserAudioStream};
+pub use bans::{BanReport, BanResult, TempBan, TempBanScheduler};
+pub use channel_tree::{
+    ApplyOptions, ApplyReport, ChannelPlan, ChannelSpec, ChannelTree, PlanAction, PlanStep,
+    StepOutcome, StepResult,
+};
+pub use classroom::{RaiseHandQueue, TransmitMonitor};
 pub use connection::{ConnectParams, ConnectParamsOwned, ReconnectConfig, ReconnectHandler};
 pub use hooks::ClientHooks;
-pub use manager::{ClientEvent, ClientHealth, ClientManager};
-pub use registry::{ClientInfo, ClientRegistry};
+pub use injector::{AudioInjector, InjectFrame, InjectionId, InjectorEvent, PcmSource};
+pub use manager::{ClientEvent, ClientHealth, ClientManager, HealthStatus, WatchdogConfig};
+pub use media::LocalPlayback;
+pub use playlist::{NowPlaying, Playlist, PlaylistEvent, RepeatMode, Track};
+pub use quality::{QualityConfig, QualityMonitor, QualityReport};
+pub use recording::{
+    Participant, RecordTarget, RecordedFile, Recorder, RecorderConfig, RecorderEvent,
+};
+pub use registry::{ClientInfo, ClientRegistry, RegistryEvent};
+pub use segmenter::{SegmenterConfig, UtteranceFinished, UtteranceSegmenter};
+pub use sound_devices::{AudioDeviceManager, DeviceDirection, DeviceEvent, SoundDeviceSelector};
+#[cfg(feature = "json")]
+pub use transcribe::CommandTranscriber;
+pub use transcribe::{StubTranscriber, Transcriber, Transcript, TranscriptBot, Transcription};
 
 static NEXT_CLIENT_ID: AtomicU64 = AtomicU64::new(1);
 
@@ -214,17 +250,30 @@ impl Client {
             return;
         }
 
+        if let Some(temp) = auto.temp_channel.as_ref() {
+            let mut channel = temp.channel.clone();
+            if let Some(parent) = temp.parent.as_ref()
+                && let Ok(id) = self.channel_id_by_path(parent)
+            {
+                channel.parent_id = id;
+            }
+            drop(auto);
+            self.rejoin_temp_channel(channel);
+            return;
+        }
+
         let channel = match auto.last_channel {
             Some(channel) => channel,
             None => return,
         };
+        drop(auto);
 
         let _ = self.join_channel(channel, "");
     }
 
     /// Sends a debug input tone to the SDK.
-    pub fn dbg_set_input_tone(&self, stream_types: u32, freq: i32) -> bool {
-        unsafe { ffi::api().TT_DBG_SetSoundInputTone(self.ptr, stream_types, freq) == 1 }
+    pub fn dbg_set_input_tone(&self, stream_types: StreamTypes, freq: i32) -> bool {
+        unsafe { ffi::api().TT_DBG_SetSoundInputTone(self.ptr, stream_types.raw(), freq) == 1 }
     }
 
     /// Writes a debug tone into an audio file.
@@ -258,9 +307,17 @@ pub(crate) struct AutoReconnectState {
     handler: Option<ReconnectHandler>,
     params: Option<ConnectParamsOwned>,
     last_channel: Option<crate::types::ChannelId>,
+    temp_channel: Option<TempChannel>,
+    pending_temp: Option<(i32, TempChannel)>,
     login: Option<crate::client::users::LoginParams>,
 }
 
+/// Temporary channel created by `join_new_channel`, with its parent path for rejoining.
+pub(crate) struct TempChannel {
+    channel: crate::types::Channel,
+    parent: Option<crate::types::ChannelPath>,
+}
+
 /// Wrapper around a raw TeamTalk message.
 pub struct Message(ffi::TTMessage);
 
@@ -275,6 +332,42 @@ impl Message {
         self.0.nSource
     }
 
+    /// Returns the stream type of an audio block or stream event.
+    pub fn stream_type(&self) -> Option<crate::types::StreamTypes> {
+        if self.0.ttType != ffi::TTType::__STREAMTYPE {
+            return None;
+        }
+        let stream_type = unsafe { self.0.__bindgen_anon_1.nStreamType };
+        Some(crate::types::StreamTypes::from_raw(stream_type as u32))
+    }
+
+    /// Returns the sound device payload if present.
+    pub fn sound_device(&self) -> Option<crate::types::SoundDevice> {
+        if self.0.ttType != ffi::TTType::__SOUNDDEVICE {
+            return None;
+        }
+        let device = unsafe { self.0.__bindgen_anon_1.sounddevice };
+        Some(crate::types::SoundDevice::from(device))
+    }
+
+    /// Returns the audio input progress payload if present.
+    pub fn audio_input_progress(&self) -> Option<crate::types::AudioInputProgress> {
+        if self.0.ttType != ffi::TTType::__AUDIOINPUTPROGRESS {
+            return None;
+        }
+        let progress = unsafe { self.0.__bindgen_anon_1.audioinputprogress };
+        Some(crate::types::AudioInputProgress::from(progress))
+    }
+
+    /// Returns the media file payload if present.
+    pub fn media_file_info(&self) -> Option<crate::types::MediaFileInfo> {
+        if self.0.ttType != ffi::TTType::__MEDIAFILEINFO {
+            return None;
+        }
+        let info = unsafe { self.0.__bindgen_anon_1.mediafileinfo };
+        Some(crate::types::MediaFileInfo::from(info))
+    }
+
     /// Returns the text message payload if present.
     pub fn text(&self) -> Option<crate::types::TextMessage> {
         unsafe {
@@ -298,6 +391,50 @@ impl Message {
         }
     }
 
+    /// Returns the channel payload if present.
+    pub fn channel(&self) -> Option<crate::types::Channel> {
+        if self.0.ttType != ffi::TTType::__CHANNEL {
+            return None;
+        }
+        unsafe { Some(crate::types::Channel::from(self.0.__bindgen_anon_1.channel)) }
+    }
+
+    /// Returns the banned user payload if present.
+    pub fn banned_user(&self) -> Option<crate::types::BannedUser> {
+        if self.0.ttType != ffi::TTType::__BANNEDUSER {
+            return None;
+        }
+        unsafe {
+            Some(crate::types::BannedUser::from(
+                self.0.__bindgen_anon_1.banneduser,
+            ))
+        }
+    }
+
+    /// Returns the server statistics payload if present.
+    pub fn server_statistics(&self) -> Option<crate::types::ServerStatistics> {
+        if self.0.ttType != ffi::TTType::__SERVERSTATISTICS {
+            return None;
+        }
+        unsafe {
+            Some(crate::types::ServerStatistics::from(
+                self.0.__bindgen_anon_1.serverstatistics,
+            ))
+        }
+    }
+
+    /// Returns the SDK error payload if present.
+    pub fn error(&self) -> Option<crate::types::ErrorMessage> {
+        if self.0.ttType != ffi::TTType::__CLIENTERRORMSG {
+            return None;
+        }
+        unsafe {
+            Some(crate::types::ErrorMessage::from(
+                self.0.__bindgen_anon_1.clienterrormsg,
+            ))
+        }
+    }
+
     /// Returns the raw TeamTalk message.
     pub fn raw(&self) -> &ffi::TTMessage {
         &self.0
@@ -0,0 +1,570 @@
+//! Queued media streaming with shuffle, repeat and now-playing tracking.
+use super::media::MediaPlayback;
+use super::{Client, Message};
+use crate::events::{Error, Event, Result};
+use crate::types::{
+    AudioPreprocessor, MEDIA_OFFSET_IGNORE, MediaFileInfo, SOUND_GAIN_DEFAULT, SOUND_GAIN_MAX,
+    TeamTalkPreprocessor, VideoCodec,
+};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use std::path::Path;
+use teamtalk_sys as ffi;
+
+/// Media file or URL queued in a `Playlist`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Track {
+    pub source: String,
+    pub title: Option<String>,
+    pub volume: Option<i32>,
+}
+
+impl Track {
+    /// Creates a track from a file path or URL.
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            title: None,
+            volume: None,
+        }
+    }
+
+    /// Sets the display title.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the track gain level (1000 is unity gain).
+    pub fn volume(mut self, gain_level: i32) -> Self {
+        self.volume = Some(gain_level);
+        self
+    }
+
+    /// Returns true if the source is a URL rather than a local file.
+    pub fn is_url(&self) -> bool {
+        self.source.contains("://")
+    }
+
+    /// Returns the title, falling back to the file or URL name.
+    pub fn display_name(&self) -> String {
+        if let Some(title) = &self.title {
+            return title.clone();
+        }
+        let trimmed = self.source.trim_end_matches('/');
+        if self.is_url() {
+            return trimmed.rsplit('/').next().unwrap_or(trimmed).to_string();
+        }
+        Path::new(trimmed)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| trimmed.to_string())
+    }
+}
+
+/// Behaviour when a track or the playlist ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RepeatMode {
+    #[default]
+    Off,
+    One,
+    All,
+}
+
+/// Track currently streamed by a `Playlist`.
+#[derive(Debug, Clone)]
+pub struct NowPlaying {
+    /// Index of the track in the playlist, or `None` once it has been removed.
+    pub index: Option<usize>,
+    pub track: Track,
+    pub info: Option<MediaFileInfo>,
+    pub elapsed_ms: u32,
+    pub duration_ms: u32,
+    pub paused: bool,
+}
+
+impl NowPlaying {
+    /// Returns playback progress from 0.0 to 1.0, if the duration is known.
+    pub fn progress(&self) -> Option<f64> {
+        crate::types::playback_progress(self.elapsed_ms, self.duration_ms)
+    }
+}
+
+/// Playlist state change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistEvent {
+    TrackStarted(usize),
+    TrackFinished(usize),
+    TrackFailed(usize),
+    Ended,
+}
+
+/// Streams queued tracks to the current channel one after another.
+///
+/// Feed `Event::StreamMediaFile` to `handle` so the next track starts when the
+/// current one finishes.
+#[derive(Debug, Clone)]
+pub struct Playlist {
+    tracks: Vec<Track>,
+    order: Vec<usize>,
+    position: Option<usize>,
+    current: Option<usize>,
+    now_playing: Option<NowPlaying>,
+    shuffle: bool,
+    repeat: RepeatMode,
+    volume: i32,
+    codec: Option<VideoCodec>,
+}
+
+impl Default for Playlist {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Playlist {
+    /// Creates an empty playlist.
+    pub fn new() -> Self {
+        Self {
+            tracks: Vec::new(),
+            order: Vec::new(),
+            position: None,
+            current: None,
+            now_playing: None,
+            shuffle: false,
+            repeat: RepeatMode::Off,
+            volume: SOUND_GAIN_DEFAULT,
+            codec: None,
+        }
+    }
+
+    /// Sets the video codec used for files with video.
+    pub fn video_codec(mut self, codec: VideoCodec) -> Self {
+        self.codec = Some(codec);
+        self
+    }
+
+    /// Returns the queued tracks in insertion order.
+    pub fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    /// Returns track indices in play order.
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// Returns the number of queued tracks.
+    pub fn len(&self) -> usize {
+        self.tracks.len()
+    }
+
+    /// Returns true if no tracks are queued.
+    pub fn is_empty(&self) -> bool {
+        self.tracks.is_empty()
+    }
+
+    /// Returns the index of the track being streamed.
+    pub fn current_index(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// Returns the track being streamed and its progress.
+    pub fn now_playing(&self) -> Option<&NowPlaying> {
+        self.now_playing.as_ref()
+    }
+
+    /// Returns true if a track is being streamed.
+    pub fn is_playing(&self) -> bool {
+        self.now_playing.is_some()
+    }
+
+    /// Appends a track and returns its index.
+    pub fn push(&mut self, track: Track) -> usize {
+        let index = self.tracks.len();
+        self.tracks.push(track);
+        self.order.push(index);
+        index
+    }
+
+    /// Appends several tracks.
+    pub fn extend(&mut self, tracks: impl IntoIterator<Item = Track>) {
+        for track in tracks {
+            self.push(track);
+        }
+    }
+
+    /// Removes a track from the queue.
+    ///
+    /// Removing the playing track does not stop it; the following track plays next
+    /// and no `PlaylistEvent` is raised for the removed one.
+    pub fn remove(&mut self, index: usize) -> Option<Track> {
+        if index >= self.tracks.len() {
+            return None;
+        }
+        let track = self.tracks.remove(index);
+        if let Some(slot) = self.order.iter().position(|&i| i == index) {
+            self.order.remove(slot);
+            self.position = match self.position {
+                Some(pos) if slot <= pos => pos.checked_sub(1),
+                other => other,
+            };
+        }
+        for i in &mut self.order {
+            if *i > index {
+                *i -= 1;
+            }
+        }
+        self.current = match self.current {
+            Some(i) if i == index => None,
+            Some(i) if i > index => Some(i - 1),
+            other => other,
+        };
+        if let Some(now) = self.now_playing.as_mut() {
+            now.index = match now.index {
+                Some(i) if i == index => None,
+                Some(i) if i > index => Some(i - 1),
+                other => other,
+            };
+        }
+        Some(track)
+    }
+
+    /// Removes all tracks without stopping playback.
+    pub fn clear(&mut self) {
+        self.tracks.clear();
+        self.order.clear();
+        self.position = None;
+        self.current = None;
+        if let Some(now) = self.now_playing.as_mut() {
+            now.index = None;
+        }
+    }
+
+    /// Returns true if shuffle is enabled.
+    pub fn shuffle(&self) -> bool {
+        self.shuffle
+    }
+
+    /// Enables or disables shuffled play order.
+    pub fn set_shuffle(&mut self, enabled: bool) {
+        self.set_shuffle_with(enabled, &mut rand::thread_rng());
+    }
+
+    /// Enables or disables shuffle using the given random source.
+    ///
+    /// The current track stays in place so playback continues with a shuffled remainder.
+    pub fn set_shuffle_with(&mut self, enabled: bool, rng: &mut impl Rng) {
+        self.shuffle = enabled;
+        if enabled {
+            self.reshuffle(rng);
+        } else {
+            self.order = (0..self.tracks.len()).collect();
+            self.position = self.current;
+        }
+    }
+
+    /// Returns the repeat mode.
+    pub fn repeat(&self) -> RepeatMode {
+        self.repeat
+    }
+
+    /// Sets the repeat mode.
+    pub fn set_repeat(&mut self, repeat: RepeatMode) {
+        self.repeat = repeat;
+    }
+
+    /// Returns the master gain level applied on top of per-track volume.
+    pub fn volume(&self) -> i32 {
+        self.volume
+    }
+
+    /// Returns the gain level used when streaming a track.
+    pub fn volume_for(&self, index: usize) -> i32 {
+        self.track_gain(self.tracks.get(index).and_then(|track| track.volume))
+    }
+
+    fn track_gain(&self, volume: Option<i32>) -> i32 {
+        let track = volume.unwrap_or(SOUND_GAIN_DEFAULT);
+        (track as i64 * self.volume as i64 / SOUND_GAIN_DEFAULT as i64)
+            .clamp(0, SOUND_GAIN_MAX as i64) as i32
+    }
+
+    /// Moves to the next track in play order and returns its index.
+    ///
+    /// `manual` skips ignore `RepeatMode::One`. Returns `None` at the end of the list.
+    pub fn advance(&mut self, manual: bool) -> Option<usize> {
+        if self.order.is_empty() {
+            return None;
+        }
+        if !manual
+            && self.repeat == RepeatMode::One
+            && let Some(pos) = self.position
+        {
+            return Some(self.order[pos]);
+        }
+        let next = self.position.map_or(0, |pos| pos + 1);
+        if next < self.order.len() {
+            self.position = Some(next);
+        } else if self.repeat == RepeatMode::Off {
+            self.position = None;
+            return None;
+        } else {
+            if self.shuffle {
+                self.current = None;
+                self.reshuffle(&mut rand::thread_rng());
+            }
+            self.position = Some(0);
+        }
+        self.position.map(|pos| self.order[pos])
+    }
+
+    /// Moves to the previous track in play order and returns its index.
+    pub fn retreat(&mut self) -> Option<usize> {
+        if self.order.is_empty() {
+            return None;
+        }
+        let pos = match self.position {
+            Some(0) | None if self.repeat == RepeatMode::All => self.order.len() - 1,
+            Some(pos) => pos.saturating_sub(1),
+            None => 0,
+        };
+        self.position = Some(pos);
+        Some(self.order[pos])
+    }
+
+    /// Moves to a track by index.
+    pub fn select(&mut self, index: usize) -> Option<usize> {
+        let pos = self.order.iter().position(|&i| i == index)?;
+        self.position = Some(pos);
+        Some(index)
+    }
+
+    /// Applies a `MediaFileInfo` update for the streamed file.
+    ///
+    /// Updates for other files, such as a track that was just replaced, are ignored.
+    pub fn update(&mut self, info: &MediaFileInfo) -> Option<PlaylistEvent> {
+        let now = self.now_playing.as_mut()?;
+        if !info.name.is_empty() && info.name != now.track.source {
+            return None;
+        }
+        now.elapsed_ms = info.elapsed_ms;
+        if info.duration_ms > 0 {
+            now.duration_ms = info.duration_ms;
+        }
+        let index = now.index;
+        match info.status {
+            ffi::MediaFileStatus::MFS_STARTED => {
+                now.paused = false;
+                index.map(PlaylistEvent::TrackStarted)
+            }
+            ffi::MediaFileStatus::MFS_PLAYING => {
+                now.paused = false;
+                None
+            }
+            ffi::MediaFileStatus::MFS_PAUSED => {
+                now.paused = true;
+                None
+            }
+            ffi::MediaFileStatus::MFS_FINISHED => {
+                self.now_playing = None;
+                index.map(PlaylistEvent::TrackFinished)
+            }
+            ffi::MediaFileStatus::MFS_ERROR => {
+                self.now_playing = None;
+                index.map(PlaylistEvent::TrackFailed)
+            }
+            ffi::MediaFileStatus::MFS_ABORTED | ffi::MediaFileStatus::MFS_CLOSED => {
+                self.now_playing = None;
+                None
+            }
+        }
+    }
+
+    /// Starts streaming the selected track, or the first one.
+    pub fn play(&mut self, client: &Client) -> Result<()> {
+        if self.position.is_none() && self.advance(true).is_none() {
+            return Err(Error::CommandFailed {
+                code: -1,
+                message: "playlist is empty".to_string(),
+            });
+        }
+        self.start(client)
+    }
+
+    /// Starts streaming a track by index.
+    pub fn play_index(&mut self, client: &Client, index: usize) -> Result<()> {
+        if self.select(index).is_none() {
+            return Err(Error::CommandFailed {
+                code: -1,
+                message: format!("track {index} is not queued"),
+            });
+        }
+        self.start(client)
+    }
+
+    /// Skips to the next track; returns false at the end of the list.
+    pub fn next(&mut self, client: &Client) -> Result<bool> {
+        if self.advance(true).is_none() {
+            self.stop(client);
+            return Ok(false);
+        }
+        self.start(client)?;
+        Ok(true)
+    }
+
+    /// Returns to the previous track.
+    pub fn previous(&mut self, client: &Client) -> Result<()> {
+        if self.retreat().is_some() {
+            self.start(client)?;
+        }
+        Ok(())
+    }
+
+    /// Pauses the streamed track.
+    pub fn pause(&mut self, client: &Client) -> bool {
+        self.update_playback(client, MEDIA_OFFSET_IGNORE, true)
+    }
+
+    /// Resumes the streamed track.
+    pub fn resume(&mut self, client: &Client) -> bool {
+        self.update_playback(client, MEDIA_OFFSET_IGNORE, false)
+    }
+
+    /// Seeks the streamed track to `offset_ms`.
+    pub fn seek(&mut self, client: &Client, offset_ms: u32) -> bool {
+        let paused = self.now_playing.as_ref().is_some_and(|now| now.paused);
+        if !self.update_playback(client, offset_ms, paused) {
+            return false;
+        }
+        if let Some(now) = self.now_playing.as_mut() {
+            now.elapsed_ms = offset_ms;
+        }
+        true
+    }
+
+    /// Sets the master gain level and applies it to the streamed track.
+    pub fn set_volume(&mut self, client: &Client, gain_level: i32) -> bool {
+        self.volume = gain_level.clamp(0, SOUND_GAIN_MAX);
+        if self.now_playing.is_none() {
+            return true;
+        }
+        let paused = self.now_playing.as_ref().is_some_and(|now| now.paused);
+        self.update_playback(client, MEDIA_OFFSET_IGNORE, paused)
+    }
+
+    /// Stops streaming and rewinds to the start of the list.
+    pub fn stop(&mut self, client: &Client) -> bool {
+        self.position = None;
+        self.current = None;
+        if self.now_playing.take().is_some() {
+            client.stop_streaming()
+        } else {
+            true
+        }
+    }
+
+    /// Processes media streaming events, starting the next track when one ends.
+    pub fn handle(
+        &mut self,
+        client: &Client,
+        event: Event,
+        msg: &Message,
+    ) -> Result<Vec<PlaylistEvent>> {
+        if event != Event::StreamMediaFile {
+            return Ok(Vec::new());
+        }
+        let Some(info) = msg.media_file_info() else {
+            return Ok(Vec::new());
+        };
+        let was_playing = self.now_playing.is_some();
+        let mut events: Vec<_> = self.update(&info).into_iter().collect();
+        let ended = matches!(
+            info.status,
+            ffi::MediaFileStatus::MFS_FINISHED | ffi::MediaFileStatus::MFS_ERROR
+        );
+        if !(ended && was_playing && self.now_playing.is_none()) {
+            return Ok(events);
+        }
+        for _ in 0..self.order.len() {
+            let Some(index) = self.advance(false) else {
+                break;
+            };
+            if self.start(client).is_ok() {
+                return Ok(events);
+            }
+            events.push(PlaylistEvent::TrackFailed(index));
+            if self.repeat == RepeatMode::One {
+                break;
+            }
+        }
+        self.position = None;
+        self.current = None;
+        events.push(PlaylistEvent::Ended);
+        Ok(events)
+    }
+
+    fn start(&mut self, client: &Client) -> Result<()> {
+        let Some(index) = self.position.map(|pos| self.order[pos]) else {
+            return Ok(());
+        };
+        let track = self.tracks[index].clone();
+        if self.now_playing.take().is_some() {
+            client.stop_streaming();
+        }
+        let playback = MediaPlayback {
+            offset_ms: 0,
+            paused: false,
+            preprocessor: self.preprocessor(track.volume),
+        };
+        if !client.start_streaming_ex(&track.source, &playback, self.codec.as_ref()) {
+            self.current = None;
+            return Err(Error::CommandFailed {
+                code: -1,
+                message: format!("failed to stream {}", track.source),
+            });
+        }
+        let info = Client::get_media_file_info(&track.source);
+        self.current = Some(index);
+        self.now_playing = Some(NowPlaying {
+            index: Some(index),
+            duration_ms: info.as_ref().map_or(0, |info| info.duration_ms),
+            info,
+            track,
+            elapsed_ms: 0,
+            paused: false,
+        });
+        Ok(())
+    }
+
+    fn update_playback(&mut self, client: &Client, offset_ms: u32, paused: bool) -> bool {
+        let Some(now) = self.now_playing.as_ref() else {
+            return false;
+        };
+        let playback = MediaPlayback {
+            offset_ms,
+            paused,
+            preprocessor: self.preprocessor(now.track.volume),
+        };
+        if !client.update_streaming(&playback, self.codec.as_ref()) {
+            return false;
+        }
+        if let Some(now) = self.now_playing.as_mut() {
+            now.paused = paused;
+        }
+        true
+    }
+
+    fn preprocessor(&self, volume: Option<i32>) -> AudioPreprocessor {
+        AudioPreprocessor::TeamTalk(TeamTalkPreprocessor::gain(self.track_gain(volume)))
+    }
+
+    fn reshuffle(&mut self, rng: &mut impl Rng) {
+        let mut rest: Vec<usize> = (0..self.tracks.len())
+            .filter(|&i| Some(i) != self.current)
+            .collect();
+        rest.shuffle(rng);
+        self.order = self.current.into_iter().chain(rest).collect();
+        self.position = self.current.map(|_| 0);
+    }
+}
@@ -0,0 +1,276 @@
+//! Per-user voice quality monitoring.
+use super::{Client, Message};
+use crate::events::Event;
+use crate::types::{UserId, UserStatistics};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use teamtalk_sys as ffi;
+
+/// Thresholds and sampling settings for `QualityMonitor`.
+#[derive(Debug, Clone)]
+pub struct QualityConfig {
+    pub interval: Duration,
+    pub degraded_loss_percent: f32,
+    pub recovered_loss_percent: f32,
+    pub degraded_mos: f32,
+    pub min_packets: i64,
+    pub ping_window: usize,
+}
+
+impl Default for QualityConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5),
+            degraded_loss_percent: 5.0,
+            recovered_loss_percent: 2.0,
+            degraded_mos: 3.0,
+            min_packets: 20,
+            ping_window: 12,
+        }
+    }
+}
+
+impl QualityConfig {
+    /// Creates a configuration with defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the sampling interval.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the loss percentage which marks a user as degraded.
+    pub fn degraded_loss_percent(mut self, percent: f32) -> Self {
+        self.degraded_loss_percent = percent;
+        self
+    }
+
+    /// Sets the loss percentage at or below which a user recovers.
+    pub fn recovered_loss_percent(mut self, percent: f32) -> Self {
+        self.recovered_loss_percent = percent;
+        self
+    }
+
+    /// Sets the MOS score below which a user is degraded.
+    pub fn degraded_mos(mut self, mos: f32) -> Self {
+        self.degraded_mos = mos;
+        self
+    }
+
+    /// Sets the minimum packets per sample required to evaluate loss.
+    pub fn min_packets(mut self, packets: i64) -> Self {
+        self.min_packets = packets;
+        self
+    }
+
+    /// Sets the number of ping samples used for trend and jitter.
+    pub fn ping_window(mut self, samples: usize) -> Self {
+        self.ping_window = samples;
+        self
+    }
+}
+
+/// Latest quality figures for a user.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct QualityReport {
+    pub user: UserId,
+    pub loss_percent: f32,
+    pub ping_ms: Option<i32>,
+    pub ping_trend: f32,
+    pub mos: f32,
+    pub degraded: bool,
+}
+
+#[derive(Default)]
+struct UserQuality {
+    recv: i64,
+    lost: i64,
+    sampled: bool,
+    report: QualityReport,
+}
+
+/// Returns a MOS-like score (1.0-4.5) from latency, jitter and loss.
+pub fn mos_score(ping_ms: f32, jitter_ms: f32, loss_percent: f32) -> f32 {
+    let latency = ping_ms.max(0.0) + jitter_ms.max(0.0) * 2.0 + 10.0;
+    let mut r = if latency < 160.0 {
+        93.2 - latency / 40.0
+    } else {
+        93.2 - (latency - 120.0) / 10.0
+    };
+    r -= loss_percent.clamp(0.0, 100.0) * 2.5;
+    let r = r.clamp(0.0, 100.0);
+    let mos = 1.0 + 0.035 * r + 0.000007 * r * (r - 60.0) * (100.0 - r);
+    mos.clamp(1.0, 4.5)
+}
+
+/// Returns the least-squares slope of the samples in units per sample.
+pub fn trend(samples: &[f32]) -> f32 {
+    let n = samples.len();
+    if n < 2 {
+        return 0.0;
+    }
+    let mean_x = (n - 1) as f32 / 2.0;
+    let mean_y = samples.iter().sum::<f32>() / n as f32;
+    let mut num = 0.0;
+    let mut den = 0.0;
+    for (i, y) in samples.iter().enumerate() {
+        let dx = i as f32 - mean_x;
+        num += dx * (y - mean_y);
+        den += dx * dx;
+    }
+    if den == 0.0 { 0.0 } else { num / den }
+}
+
+/// Samples user statistics and raises quality events.
+pub struct QualityMonitor {
+    config: QualityConfig,
+    last_sample: Option<Instant>,
+    pings: VecDeque<i32>,
+    users: HashMap<UserId, UserQuality>,
+}
+
+impl Default for QualityMonitor {
+    fn default() -> Self {
+        Self::new(QualityConfig::default())
+    }
+}
+
+impl QualityMonitor {
+    /// Creates a monitor with the provided configuration.
+    pub fn new(config: QualityConfig) -> Self {
+        Self {
+            config,
+            last_sample: None,
+            pings: VecDeque::new(),
+            users: HashMap::new(),
+        }
+    }
+
+    /// Returns the active configuration.
+    pub fn config(&self) -> &QualityConfig {
+        &self.config
+    }
+
+    /// Samples all users when the interval has elapsed and fires hooks.
+    pub fn tick(&mut self, client: &Client) -> Vec<(Event, Message)> {
+        if self
+            .last_sample
+            .is_some_and(|last| last.elapsed() < self.config.interval)
+        {
+            return Vec::new();
+        }
+        self.last_sample = Some(Instant::now());
+        self.sample(client)
+    }
+
+    /// Samples all users immediately and fires hooks.
+    pub fn sample(&mut self, client: &Client) -> Vec<(Event, Message)> {
+        let ping = client
+            .get_client_statistics()
+            .map(|stats| stats.udp_ping.max(stats.tcp_ping))
+            .filter(|&ping| ping >= 0);
+        self.record_ping(ping);
+
+        let users = client.get_server_users();
+        self.users
+            .retain(|id, _| users.iter().any(|user| user.id == *id));
+
+        let mut out = Vec::new();
+        for user in users {
+            if user.id == client.my_id() {
+                continue;
+            }
+            let Some(stats) = client.get_user_statistics(user.id) else {
+                continue;
+            };
+            if let Some(event) = self.record_user(user.id, &stats) {
+                let mut raw = unsafe { std::mem::zeroed::<ffi::TTMessage>() };
+                raw.nSource = user.id.0;
+                let msg = Message::from_raw(raw);
+                client.invoke_hooks(event, &msg);
+                out.push((event, msg));
+            }
+        }
+        out
+    }
+
+    /// Records the latest connection ping used for all users.
+    pub fn record_ping(&mut self, ping_ms: Option<i32>) {
+        if let Some(ping) = ping_ms {
+            self.pings.push_back(ping);
+            while self.pings.len() > self.config.ping_window.max(1) {
+                self.pings.pop_front();
+            }
+        }
+    }
+
+    /// Records a statistics sample for a user and returns a transition event.
+    pub fn record_user(&mut self, user: UserId, stats: &UserStatistics) -> Option<Event> {
+        let pings: Vec<f32> = self.pings.iter().map(|&p| p as f32).collect();
+        let ping_ms = self.pings.back().copied();
+        let jitter = if pings.len() > 1 {
+            pings.windows(2).map(|w| (w[1] - w[0]).abs()).sum::<f32>() / (pings.len() - 1) as f32
+        } else {
+            0.0
+        };
+        let ping_trend = trend(&pings);
+        let config = &self.config;
+        let entry = self.users.entry(user).or_default();
+        let recv = stats.voice_recv - entry.recv;
+        let lost = stats.voice_lost - entry.lost;
+        let first = !entry.sampled;
+        entry.recv = stats.voice_recv;
+        entry.lost = stats.voice_lost;
+        entry.sampled = true;
+
+        let mut report = entry.report;
+        report.user = user;
+        report.ping_ms = ping_ms;
+        report.ping_trend = ping_trend;
+        if !first && recv >= 0 && lost >= 0 && recv + lost >= config.min_packets.max(1) {
+            report.loss_percent = lost as f32 * 100.0 / (recv + lost) as f32;
+        }
+        report.mos = mos_score(ping_ms.unwrap_or(0) as f32, jitter, report.loss_percent);
+
+        let was_degraded = report.degraded;
+        if !was_degraded
+            && (report.loss_percent >= config.degraded_loss_percent
+                || report.mos < config.degraded_mos)
+        {
+            report.degraded = true;
+        } else if was_degraded
+            && report.loss_percent <= config.recovered_loss_percent
+            && report.mos >= config.degraded_mos
+        {
+            report.degraded = false;
+        }
+        entry.report = report;
+
+        match (was_degraded, report.degraded) {
+            (false, true) => Some(Event::QualityDegraded {
+                user,
+                loss: report.loss_percent.round().clamp(0.0, 100.0) as u8,
+            }),
+            (true, false) => Some(Event::QualityRecovered { user }),
+            _ => None,
+        }
+    }
+
+    /// Returns the latest report for a user.
+    pub fn report(&self, user: UserId) -> Option<QualityReport> {
+        self.users.get(&user).map(|q| q.report)
+    }
+
+    /// Returns the latest reports for all tracked users.
+    pub fn reports(&self) -> Vec<QualityReport> {
+        self.users.values().map(|q| q.report).collect()
+    }
+
+    /// Stops tracking a user.
+    pub fn remove_user(&mut self, user: UserId) {
+        self.users.remove(&user);
+    }
+}
@@ -1,8 +1,12 @@
 //! Recording APIs for channels and streams.
-use super::Client;
-use crate::events::{Error, Result};
-use crate::types::{AudioCodec, ChannelId};
+use super::{Client, Message};
+use crate::events::{Error, Event, Result};
+use crate::types::{AudioCodec, ChannelId, MediaFileInfo, StreamTypes, User, UserId};
 use crate::utils::ToTT;
+use crate::utils::wav::Rotation;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
 use teamtalk_sys as ffi;
 
 impl Client {
@@ -38,7 +42,7 @@ impl Client {
     /// Starts recording a set of stream types.
     pub fn start_recording_streams(
         &self,
-        stream_types: u32,
+        stream_types: StreamTypes,
         codec: &AudioCodec,
         file_path: &str,
         format: ffi::AudioFileFormat,
@@ -48,7 +52,7 @@ impl Client {
         unsafe {
             ffi::api().TT_StartRecordingMuxedStreams(
                 self.ptr,
-                stream_types,
+                stream_types.raw(),
                 &raw_codec,
                 p.as_ptr(),
                 format,
@@ -65,6 +69,52 @@ impl Client {
     pub fn stop_recording_channel(&self, channel_id: i32) -> bool {
         unsafe { ffi::api().TT_StopRecordingMuxedAudioFileEx(self.ptr, channel_id) == 1 }
     }
+
+    /// Stores a user's audio streams as files in a folder; an empty folder stops storing.
+    ///
+    /// `file_name_vars` uses SDK variables such as `%username%`, `%userid%` and `%starttime%`.
+    pub fn set_user_media_storage_dir(
+        &self,
+        user_id: UserId,
+        folder: &str,
+        file_name_vars: &str,
+        format: ffi::AudioFileFormat,
+    ) -> bool {
+        let folder = folder.tt();
+        let vars = file_name_vars.tt();
+        unsafe {
+            ffi::api().TT_SetUserMediaStorageDir(
+                self.ptr,
+                user_id.0,
+                folder.as_ptr(),
+                vars.as_ptr(),
+                format,
+            ) == 1
+        }
+    }
+
+    /// Stores a user's audio streams, waiting `stop_delay_ms` before closing a file.
+    pub fn set_user_media_storage_dir_ex(
+        &self,
+        user_id: UserId,
+        folder: &str,
+        file_name_vars: &str,
+        format: ffi::AudioFileFormat,
+        stop_delay_ms: i32,
+    ) -> bool {
+        let folder = folder.tt();
+        let vars = file_name_vars.tt();
+        unsafe {
+            ffi::api().TT_SetUserMediaStorageDirEx(
+                self.ptr,
+                user_id.0,
+                folder.as_ptr(),
+                vars.as_ptr(),
+                format,
+                stop_delay_ms,
+            ) == 1
+        }
+    }
 }
 
 /// Guard that stops a channel recording when dropped.
@@ -111,3 +161,588 @@ impl Drop for RecordSession<'_> {
         }
     }
 }
+
+/// Audio captured by a `Recorder`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordTarget {
+    /// Muxed audio of a channel.
+    Channel(ChannelId),
+    /// Muxed stream types of the current channel, encoded with a codec.
+    Streams(StreamTypes, AudioCodec),
+    /// One file per transmission for each listed user.
+    Users(Vec<UserId>),
+    /// One file per transmission for everyone in a channel, including later joiners.
+    ChannelUsers(ChannelId),
+}
+
+impl RecordTarget {
+    fn name(&self) -> String {
+        match self {
+            Self::Channel(id) => format!("channel{}", id.0),
+            Self::Streams(..) => "streams".to_string(),
+            Self::Users(_) => "users".to_string(),
+            Self::ChannelUsers(id) => format!("users{}", id.0),
+        }
+    }
+
+    fn is_muxed(&self) -> bool {
+        matches!(self, Self::Channel(_) | Self::Streams(..))
+    }
+}
+
+/// Settings for `Recorder`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecorderConfig {
+    pub dir: PathBuf,
+    pub template: String,
+    pub format: ffi::AudioFileFormat,
+    pub rotation: Rotation,
+    pub user_file_vars: String,
+    pub stop_delay_ms: Option<i32>,
+}
+
+impl RecorderConfig {
+    /// Creates a configuration writing WAV files to `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            template: "{target}_{timestamp}_{index}".to_string(),
+            format: ffi::AudioFileFormat::AFF_WAVE_FORMAT,
+            rotation: Rotation::default(),
+            user_file_vars: "%starttime%_%userid%_%username%".to_string(),
+            stop_delay_ms: None,
+        }
+    }
+
+    /// Sets the muxed file name template.
+    ///
+    /// Supports `{target}`, `{timestamp}` (unix seconds of the file start), `{session}`
+    /// (unix seconds of the recording start) and `{index}` (file number from 1).
+    pub fn template(mut self, template: &str) -> Self {
+        self.template = template.to_string();
+        self
+    }
+
+    /// Sets the audio file format.
+    pub fn format(mut self, format: ffi::AudioFileFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets when muxed files are rotated.
+    pub fn rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Sets the SDK file name variables for per-user files.
+    pub fn user_file_vars(mut self, vars: &str) -> Self {
+        self.user_file_vars = vars.to_string();
+        self
+    }
+
+    /// Keeps per-user files open for `ms` after a transmission ends.
+    pub fn stop_delay_ms(mut self, ms: i32) -> Self {
+        self.stop_delay_ms = Some(ms);
+        self
+    }
+
+    /// Returns the muxed file name for a target, file number and start times.
+    pub fn file_name(
+        &self,
+        target: &RecordTarget,
+        index: u32,
+        session: SystemTime,
+        started: SystemTime,
+    ) -> String {
+        let secs = |time: SystemTime| {
+            time.duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+                .to_string()
+        };
+        let name = self
+            .template
+            .replace("{target}", &target.name())
+            .replace("{timestamp}", &secs(started))
+            .replace("{session}", &secs(session))
+            .replace("{index}", &index.to_string());
+        format!("{name}.{}", extension(self.format))
+    }
+}
+
+fn extension(format: ffi::AudioFileFormat) -> &'static str {
+    match format {
+        ffi::AudioFileFormat::AFF_WAVE_FORMAT => "wav",
+        ffi::AudioFileFormat::AFF_CHANNELCODEC_FORMAT => "ogg",
+        ffi::AudioFileFormat::AFF_NONE => "raw",
+        _ => "mp3",
+    }
+}
+
+/// File written during a recording.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RecordedFile {
+    pub path: PathBuf,
+    #[cfg_attr(feature = "serde", serde(rename = "user_id"))]
+    pub user: Option<UserId>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::unix_millis"))]
+    pub started: SystemTime,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_support::unix_millis_opt")
+    )]
+    pub finished: Option<SystemTime>,
+    pub failed: bool,
+}
+
+/// User present during a recording.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Participant {
+    pub user_id: UserId,
+    pub username: String,
+    pub nickname: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::unix_millis"))]
+    pub joined: SystemTime,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_support::unix_millis_opt")
+    )]
+    pub left: Option<SystemTime>,
+}
+
+#[cfg(feature = "json")]
+#[derive(serde::Serialize)]
+struct Sidecar<'a> {
+    target: String,
+    #[serde(with = "crate::serde_support::unix_millis_opt")]
+    started: Option<SystemTime>,
+    #[serde(with = "crate::serde_support::unix_millis_opt")]
+    stopped: Option<SystemTime>,
+    files: &'a [RecordedFile],
+    participants: &'a [Participant],
+}
+
+/// File notification raised by `Recorder`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecorderEvent {
+    FileStarted { user: Option<UserId>, path: PathBuf },
+    FileFinished { user: Option<UserId>, path: PathBuf },
+    FileFailed { user: Option<UserId>, path: PathBuf },
+}
+
+/// Recording session covering muxed and per-user targets with rotation and a JSON sidecar.
+///
+/// Call `stop` to end the recording; with the `json` feature the sidecar is written
+/// next to the audio files. Dropping a recorder that is still recording stops it
+/// without writing the sidecar.
+pub struct Recorder<'a> {
+    client: Option<&'a Client>,
+    target: RecordTarget,
+    config: RecorderConfig,
+    session: Option<SystemTime>,
+    stopped: Option<SystemTime>,
+    index: u32,
+    current: Option<usize>,
+    files: Vec<RecordedFile>,
+    participants: Vec<Participant>,
+    storing: HashSet<UserId>,
+}
+
+impl<'a> Recorder<'a> {
+    /// Creates a recorder; nothing is recorded until `start`.
+    pub fn new(target: RecordTarget, config: RecorderConfig) -> Self {
+        Self {
+            client: None,
+            target,
+            config,
+            session: None,
+            stopped: None,
+            index: 0,
+            current: None,
+            files: Vec::new(),
+            participants: Vec::new(),
+            storing: HashSet::new(),
+        }
+    }
+
+    /// Returns the recorded target.
+    pub fn target(&self) -> &RecordTarget {
+        &self.target
+    }
+
+    /// Returns true between `start` and `stop`.
+    pub fn is_recording(&self) -> bool {
+        self.session.is_some() && self.stopped.is_none()
+    }
+
+    /// Returns the files written so far.
+    pub fn files(&self) -> &[RecordedFile] {
+        &self.files
+    }
+
+    /// Returns the users seen during the recording.
+    pub fn participants(&self) -> &[Participant] {
+        &self.participants
+    }
+
+    fn channel(&self, client: &Client) -> Option<ChannelId> {
+        match &self.target {
+            RecordTarget::Channel(id) | RecordTarget::ChannelUsers(id) => Some(*id),
+            RecordTarget::Streams(..) => Some(client.my_channel_id()),
+            RecordTarget::Users(_) => None,
+        }
+    }
+
+    /// Starts recording with `client`, which is used until the recorder is stopped or dropped.
+    pub fn start(&mut self, client: &'a Client, now: SystemTime) -> Result<Vec<RecorderEvent>> {
+        if self.is_recording() {
+            return Ok(Vec::new());
+        }
+        std::fs::create_dir_all(&self.config.dir)?;
+        self.client = Some(client);
+        self.session = Some(now);
+        self.stopped = None;
+        let users = match (&self.target, self.channel(client)) {
+            (RecordTarget::Users(ids), _) => {
+                ids.iter().filter_map(|id| client.get_user(*id)).collect()
+            }
+            (_, Some(channel)) => client.get_channel_users(channel),
+            (_, None) => Vec::new(),
+        };
+        for user in &users {
+            self.participant_joined(user, now);
+        }
+        if self.target.is_muxed() {
+            return match self.start_file(client, now) {
+                Ok(event) => Ok(vec![event]),
+                Err(err) => {
+                    self.stopped = Some(now);
+                    Err(err)
+                }
+            };
+        }
+        for user in &users {
+            self.store_user(client, user.id)?;
+        }
+        Ok(Vec::new())
+    }
+
+    /// Stops recording and, with the `json` feature, writes the sidecar and returns its path.
+    ///
+    /// Returns `Ok(None)` if the recording was never started.
+    pub fn stop(&mut self, now: SystemTime) -> Result<Option<PathBuf>> {
+        if self.session.is_none() {
+            return Ok(None);
+        }
+        self.finish(now);
+        #[cfg(feature = "json")]
+        return self.write_sidecar().map(Some);
+        #[cfg(not(feature = "json"))]
+        Ok(None)
+    }
+
+    fn finish(&mut self, now: SystemTime) {
+        let Some(client) = self.client else {
+            return;
+        };
+        if self.is_recording() {
+            self.stop_file(client, now);
+            for user_id in std::mem::take(&mut self.storing) {
+                client.set_user_media_storage_dir(user_id, "", "", ffi::AudioFileFormat::AFF_NONE);
+            }
+            self.stopped = Some(now);
+        }
+    }
+
+    /// Writes the sidecar next to the audio files and returns its path.
+    #[cfg(feature = "json")]
+    pub fn write_sidecar(&self) -> Result<PathBuf> {
+        let path = self.sidecar_path();
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, self.sidecar_json()?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(path)
+    }
+
+    /// Rotates the muxed file when its duration or size limit is reached.
+    ///
+    /// If the next file cannot be started the recording stops and a
+    /// `FileFailed` event is returned.
+    pub fn tick(&mut self, now: SystemTime) -> Result<Vec<RecorderEvent>> {
+        let (Some(client), Some(current)) =
+            (self.client, self.current.map(|index| &self.files[index]))
+        else {
+            return Ok(Vec::new());
+        };
+        let rotation = self.config.rotation;
+        let too_long = rotation
+            .max_duration
+            .is_some_and(|max| now.duration_since(current.started).unwrap_or_default() >= max);
+        let too_big = rotation.max_bytes.is_some_and(|max| {
+            std::fs::metadata(&current.path).is_ok_and(|meta| meta.len() >= max)
+        });
+        if !too_long && !too_big {
+            return Ok(Vec::new());
+        }
+        let mut events = self.stop_file(client, now).into_iter().collect::<Vec<_>>();
+        if let Ok(event) = self.start_file(client, now) {
+            events.push(event);
+        } else if let Some(file) = self.files.last() {
+            self.stopped = Some(now);
+            events.push(RecorderEvent::FileFailed {
+                user: None,
+                path: file.path.clone(),
+            });
+        }
+        Ok(events)
+    }
+
+    /// Processes user and media file events.
+    pub fn handle(&mut self, event: Event, msg: &Message) -> Result<Vec<RecorderEvent>> {
+        let Some(client) = self.client.filter(|_| self.is_recording()) else {
+            return Ok(Vec::new());
+        };
+        let now = SystemTime::now();
+        let mut events = Vec::new();
+        match event {
+            Event::UserRecordMediaFile => {
+                if let Some(info) = msg.media_file_info() {
+                    events.extend(self.user_file(UserId(msg.source()), &info, now));
+                }
+            }
+            Event::UserJoined => {
+                if let Some(user) = msg.user()
+                    && self.channel(client) == Some(user.channel_id)
+                {
+                    self.participant_joined(&user, now);
+                    if matches!(self.target, RecordTarget::ChannelUsers(_)) {
+                        self.store_user(client, user.id)?;
+                    }
+                }
+            }
+            Event::UserLoggedIn => {
+                if let Some(user) = msg.user()
+                    && let RecordTarget::Users(ids) = &self.target
+                    && ids.contains(&user.id)
+                {
+                    self.participant_joined(&user, now);
+                    self.store_user(client, user.id)?;
+                }
+            }
+            Event::UserLeft | Event::UserLoggedOut => {
+                // Listed users are followed across channels and only leave by logging out;
+                // for `UserLeft` the message source is the channel that was left.
+                let departed = match &self.target {
+                    RecordTarget::Users(_) => event == Event::UserLoggedOut,
+                    _ => {
+                        event == Event::UserLoggedOut
+                            || self.channel(client) == Some(ChannelId(msg.source()))
+                    }
+                };
+                if departed && let Some(user) = msg.user() {
+                    self.participant_left(user.id, now);
+                    if self.storing.remove(&user.id) {
+                        client.set_user_media_storage_dir(
+                            user.id,
+                            "",
+                            "",
+                            ffi::AudioFileFormat::AFF_NONE,
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+        events.extend(self.tick(now)?);
+        Ok(events)
+    }
+
+    /// Records a per-user file update reported by the SDK.
+    pub fn user_file(
+        &mut self,
+        user_id: UserId,
+        info: &MediaFileInfo,
+        now: SystemTime,
+    ) -> Option<RecorderEvent> {
+        let path = PathBuf::from(&info.name);
+        let existing = self
+            .files
+            .iter()
+            .rposition(|file| file.path == path && file.finished.is_none());
+        match info.status {
+            ffi::MediaFileStatus::MFS_STARTED => {
+                if existing.is_none() {
+                    self.files.push(RecordedFile {
+                        path: path.clone(),
+                        user: Some(user_id),
+                        started: now,
+                        finished: None,
+                        failed: false,
+                    });
+                }
+                Some(RecorderEvent::FileStarted {
+                    user: Some(user_id),
+                    path,
+                })
+            }
+            ffi::MediaFileStatus::MFS_FINISHED => {
+                let file = &mut self.files[existing?];
+                file.finished = Some(now);
+                Some(RecorderEvent::FileFinished {
+                    user: Some(user_id),
+                    path,
+                })
+            }
+            ffi::MediaFileStatus::MFS_ERROR | ffi::MediaFileStatus::MFS_ABORTED => {
+                match existing {
+                    Some(index) => {
+                        self.files[index].finished = Some(now);
+                        self.files[index].failed = true;
+                    }
+                    None => self.files.push(RecordedFile {
+                        path: path.clone(),
+                        user: Some(user_id),
+                        started: now,
+                        finished: Some(now),
+                        failed: true,
+                    }),
+                }
+                Some(RecorderEvent::FileFailed {
+                    user: Some(user_id),
+                    path,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Adds a participant, or marks a returning one as present again.
+    pub fn participant_joined(&mut self, user: &User, now: SystemTime) {
+        let present = self
+            .participants
+            .iter()
+            .any(|p| p.user_id == user.id && p.left.is_none());
+        if !present {
+            self.participants.push(Participant {
+                user_id: user.id,
+                username: user.username.clone(),
+                nickname: user.nickname.clone(),
+                joined: now,
+                left: None,
+            });
+        }
+    }
+
+    /// Marks a participant as gone.
+    pub fn participant_left(&mut self, user_id: UserId, now: SystemTime) {
+        if let Some(participant) = self
+            .participants
+            .iter_mut()
+            .rev()
+            .find(|p| p.user_id == user_id && p.left.is_none())
+        {
+            participant.left = Some(now);
+        }
+    }
+
+    /// Returns the sidecar file path.
+    pub fn sidecar_path(&self) -> PathBuf {
+        let session = self.session.unwrap_or(UNIX_EPOCH);
+        let secs = session
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.config
+            .dir
+            .join(format!("{}_{}.json", self.target.name(), secs))
+    }
+
+    /// Returns the sidecar JSON with times in unix milliseconds.
+    #[cfg(feature = "json")]
+    pub fn sidecar_json(&self) -> Result<String> {
+        let sidecar = Sidecar {
+            target: self.target.name(),
+            started: self.session,
+            stopped: self.stopped,
+            files: &self.files,
+            participants: &self.participants,
+        };
+        serde_json::to_string_pretty(&sidecar).map_err(|e| Error::Format(e.to_string()))
+    }
+
+    fn store_user(&mut self, client: &Client, user_id: UserId) -> Result<()> {
+        let dir = self.config.dir.to_string_lossy();
+        let vars = &self.config.user_file_vars;
+        let ok = match self.config.stop_delay_ms {
+            Some(delay) => {
+                client.set_user_media_storage_dir_ex(user_id, &dir, vars, self.config.format, delay)
+            }
+            None => client.set_user_media_storage_dir(user_id, &dir, vars, self.config.format),
+        };
+        if !ok {
+            return Err(Error::CommandFailed {
+                code: -1,
+                message: format!("storing audio of user {} failed", user_id.0),
+            });
+        }
+        self.storing.insert(user_id);
+        Ok(())
+    }
+
+    fn start_file(&mut self, client: &Client, now: SystemTime) -> Result<RecorderEvent> {
+        self.index += 1;
+        let session = self.session.unwrap_or(now);
+        let name = self
+            .config
+            .file_name(&self.target, self.index, session, now);
+        let path = self.config.dir.join(name);
+        let path_str = path.to_string_lossy();
+        let ok = match &self.target {
+            RecordTarget::Channel(id) => {
+                client.start_recording_channel(id.0, &path_str, self.config.format)
+            }
+            RecordTarget::Streams(types, codec) => {
+                client.start_recording_streams(*types, codec, &path_str, self.config.format)
+            }
+            _ => false,
+        };
+        self.files.push(RecordedFile {
+            path: path.clone(),
+            user: None,
+            started: now,
+            finished: (!ok).then_some(now),
+            failed: !ok,
+        });
+        if !ok {
+            return Err(Error::CommandFailed {
+                code: -1,
+                message: "Recording start failed".to_string(),
+            });
+        }
+        self.current = Some(self.files.len() - 1);
+        Ok(RecorderEvent::FileStarted { user: None, path })
+    }
+
+    fn stop_file(&mut self, client: &Client, now: SystemTime) -> Option<RecorderEvent> {
+        let index = self.current.take()?;
+        match &self.target {
+            RecordTarget::Channel(id) => client.stop_recording_channel(id.0),
+            _ => client.stop_recording(),
+        };
+        let file = &mut self.files[index];
+        file.finished = Some(now);
+        Some(RecorderEvent::FileFinished {
+            user: None,
+            path: file.path.clone(),
+        })
+    }
+}
+
+impl Drop for Recorder<'_> {
+    fn drop(&mut self) {
+        self.finish(SystemTime::now());
+    }
+}
@@ -1,21 +1,132 @@
-use crate::events::Event;
+use crate::client::Message;
+use crate::events::{ConnectionState, Event};
 use crate::types::ClientId;
 use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ClientInfo {
     pub id: ClientId,
     pub label: Option<String>,
-    pub state: crate::events::ConnectionState,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub state: ConnectionState,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_event"))]
     pub last_event: Option<Event>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "last_event_at_ms",
+            with = "crate::serde_support::unix_millis_opt"
+        )
+    )]
     pub last_event_at: Option<SystemTime>,
+    pub server_host: Option<String>,
+    pub nickname: Option<String>,
+    pub channel_path: Option<String>,
+    pub reconnect_attempts: u32,
+    pub last_error: Option<String>,
+}
+
+/// Client values read over FFI before the registry lock is taken.
+struct ClientFields {
+    label: Option<String>,
+    state: ConnectionState,
+    server_host: Option<String>,
+    nickname: Option<String>,
+    channel_path: Option<String>,
+    reconnect_attempts: u32,
+}
+
+impl ClientFields {
+    fn read(client: &crate::client::Client) -> Self {
+        let state = client.connection_state();
+        let logged_in = matches!(
+            state,
+            ConnectionState::LoggedIn
+                | ConnectionState::Creating
+                | ConnectionState::Joining(_)
+                | ConnectionState::Joined(_)
+        );
+        let nickname = logged_in
+            .then(|| client.get_user(client.my_id()).map(|user| user.nickname))
+            .flatten()
+            .or_else(|| client.login_params().map(|params| params.nickname));
+        let channel_path = match state {
+            ConnectionState::Joined(channel) => {
+                Some(client.get_channel_path(channel)).filter(|path| !path.is_empty())
+            }
+            _ => None,
+        };
+        Self {
+            label: client.label(),
+            state,
+            server_host: client.reconnect_params().map(|params| params.host),
+            nickname,
+            channel_path,
+            reconnect_attempts: client.reconnect_attempts(),
+        }
+    }
+}
+
+impl ClientInfo {
+    fn new(id: ClientId, fields: ClientFields) -> Self {
+        let mut info = Self {
+            id,
+            label: None,
+            state: ConnectionState::Idle,
+            last_event: None,
+            last_event_at: None,
+            server_host: None,
+            nickname: None,
+            channel_path: None,
+            reconnect_attempts: 0,
+            last_error: None,
+        };
+        info.apply(fields);
+        info
+    }
+
+    fn apply(&mut self, fields: ClientFields) {
+        self.label = fields.label;
+        self.state = fields.state;
+        self.server_host = fields.server_host;
+        self.nickname = fields.nickname;
+        self.channel_path = fields.channel_path;
+        self.reconnect_attempts = fields.reconnect_attempts;
+    }
+
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> crate::events::Result<String> {
+        serde_json::to_string(self).map_err(|e| crate::events::Error::Format(e.to_string()))
+    }
+}
+
+#[cfg(feature = "serde")]
+fn serialize_event<S: serde::Serializer>(event: &Option<Event>, s: S) -> Result<S::Ok, S::Error> {
+    match event {
+        Some(event) => s.collect_str(&format_args!("{event:?}")),
+        None => s.serialize_none(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum RegistryEvent {
+    Registered(ClientInfo),
+    Unregistered(ClientId),
+    StateChanged {
+        id: ClientId,
+        from: ConnectionState,
+        to: ConnectionState,
+    },
 }
 
 #[derive(Clone, Default)]
 pub struct ClientRegistry {
     inner: Arc<Mutex<HashMap<ClientId, ClientInfo>>>,
+    subscribers: Arc<Mutex<Vec<Sender<RegistryEvent>>>>,
 }
 
 impl ClientRegistry {
@@ -23,49 +134,84 @@ impl ClientRegistry {
         Self::default()
     }
 
+    pub fn subscribe(&self) -> Receiver<RegistryEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
+    fn notify(&self, event: RegistryEvent) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+    }
+
     pub fn register(&self, client: &crate::client::Client) {
-        let info = ClientInfo {
-            id: client.id(),
-            label: client.label(),
-            state: client.connection_state(),
-            last_event: None,
-            last_event_at: None,
-        };
-        let mut map = self.inner.lock().unwrap();
-        map.insert(info.id, info);
+        let info = ClientInfo::new(client.id(), ClientFields::read(client));
+        {
+            let mut map = self.inner.lock().unwrap();
+            map.insert(info.id, info.clone());
+        }
+        self.notify(RegistryEvent::Registered(info));
     }
 
     pub fn unregister(&self, id: ClientId) {
-        let mut map = self.inner.lock().unwrap();
-        map.remove(&id);
+        let removed = {
+            let mut map = self.inner.lock().unwrap();
+            map.remove(&id).is_some()
+        };
+        if removed {
+            self.notify(RegistryEvent::Unregistered(id));
+        }
     }
 
     pub fn update_event(&self, client: &crate::client::Client, event: Event) {
-        let mut map = self.inner.lock().unwrap();
-        let entry = map.entry(client.id()).or_insert(ClientInfo {
-            id: client.id(),
-            label: client.label(),
-            state: client.connection_state(),
-            last_event: None,
-            last_event_at: None,
+        self.update(client, |entry| {
+            entry.last_event = Some(event);
+            entry.last_event_at = Some(SystemTime::now());
         });
-        entry.label = client.label();
-        entry.state = client.connection_state();
-        entry.last_event = Some(event);
-        entry.last_event_at = Some(SystemTime::now());
     }
 
-    pub fn update_snapshot(&self, client: &crate::client::Client) {
-        let mut map = self.inner.lock().unwrap();
-        let entry = map.entry(client.id()).or_insert(ClientInfo {
-            id: client.id(),
-            label: client.label(),
-            state: client.connection_state(),
-            last_event: None,
-            last_event_at: None,
+    pub fn update_message(&self, client: &crate::client::Client, event: Event, msg: &Message) {
+        let error = msg.error().map(|err| err.message);
+        self.update(client, |entry| {
+            entry.last_event = Some(event);
+            entry.last_event_at = Some(SystemTime::now());
+            if error.is_some() {
+                entry.last_error = error;
+            }
         });
-        entry.label = client.label();
-        entry.state = client.connection_state();
+    }
+
+    pub fn record_error(&self, id: ClientId, error: impl Into<String>) {
+        let mut map = self.inner.lock().unwrap();
+        if let Some(entry) = map.get_mut(&id) {
+            entry.last_error = Some(error.into());
+        }
+    }
+
+    pub fn update_snapshot(&self, client: &crate::client::Client) {
+        self.update(client, |_| {});
+    }
+
+    fn update(&self, client: &crate::client::Client, apply: impl FnOnce(&mut ClientInfo)) {
+        let fields = ClientFields::read(client);
+        let change = {
+            let mut map = self.inner.lock().unwrap();
+            let Some(entry) = map.get_mut(&client.id()) else {
+                return;
+            };
+            let from = entry.state;
+            entry.apply(fields);
+            apply(entry);
+            (from != entry.state).then_some((from, entry.state))
+        };
+        if let Some((from, to)) = change {
+            self.notify(RegistryEvent::StateChanged {
+                id: client.id(),
+                from,
+                to,
+            });
+        }
     }
 
     pub fn list(&self) -> Vec<ClientInfo> {
@@ -77,4 +223,11 @@ impl ClientRegistry {
         let map = self.inner.lock().unwrap();
         map.get(&id).cloned()
     }
+
+    #[cfg(feature = "json")]
+    pub fn snapshot_json(&self) -> crate::events::Result<String> {
+        let mut infos = self.list();
+        infos.sort_by_key(|info| info.id.0);
+        serde_json::to_string(&infos).map_err(|e| crate::events::Error::Format(e.to_string()))
+    }
 }
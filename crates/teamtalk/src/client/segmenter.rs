@@ -0,0 +1,328 @@
+//! Talk-spurt segmentation writing one WAV file per utterance.
+use super::audio_stream::{AudioChunk, stream_name};
+use super::{Client, Message};
+use crate::events::{Event, Result};
+use crate::types::{ChannelId, StreamTypes, User, UserId};
+use crate::utils::audio;
+use crate::utils::wav::{WavSpec, WavWriter};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Finished utterance clip.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UtteranceFinished {
+    pub user: UserId,
+    pub channel: ChannelId,
+    pub path: PathBuf,
+    pub started: SystemTime,
+    pub duration: Duration,
+}
+
+/// Settings for `UtteranceSegmenter`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmenterConfig {
+    pub dir: PathBuf,
+    pub hangover: Duration,
+    pub min_duration: Duration,
+    pub max_duration: Option<Duration>,
+    pub use_talking_flag: bool,
+    pub energy_threshold_dbfs: Option<f64>,
+}
+
+impl SegmenterConfig {
+    /// Creates a configuration writing clips to `dir`, detecting speech from talking flags.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            hangover: Duration::from_millis(500),
+            min_duration: Duration::from_millis(200),
+            max_duration: None,
+            use_talking_flag: true,
+            energy_threshold_dbfs: None,
+        }
+    }
+
+    /// Sets how long silence must last before a clip is closed.
+    pub fn hangover(mut self, duration: Duration) -> Self {
+        self.hangover = duration;
+        self
+    }
+
+    /// Discards clips shorter than `duration`.
+    pub fn min_duration(mut self, duration: Duration) -> Self {
+        self.min_duration = duration;
+        self
+    }
+
+    /// Splits clips longer than `duration`.
+    pub fn max_duration(mut self, duration: Duration) -> Self {
+        self.max_duration = Some(duration);
+        self
+    }
+
+    /// Enables or disables speech detection from `UserStateChange` talking flags.
+    pub fn use_talking_flag(mut self, enabled: bool) -> Self {
+        self.use_talking_flag = enabled;
+        self
+    }
+
+    /// Treats audio blocks at or above `dbfs` RMS as speech.
+    pub fn energy_threshold_dbfs(mut self, dbfs: f64) -> Self {
+        self.energy_threshold_dbfs = Some(dbfs);
+        self
+    }
+}
+
+struct Clip {
+    writer: WavWriter,
+    channel: ChannelId,
+    started: SystemTime,
+}
+
+#[derive(Default)]
+struct Speaker {
+    name: Option<String>,
+    channel: ChannelId,
+    talking: bool,
+    last_voice: Option<SystemTime>,
+    clip: Option<Clip>,
+}
+
+type UtteranceHook = Box<dyn FnMut(&UtteranceFinished) + Send>;
+
+/// Splits each user's audio into clips from speech start to the end of the hangover.
+///
+/// Files are named `<user>_ch<channel>_<unix ms>.wav`.
+pub struct UtteranceSegmenter {
+    config: SegmenterConfig,
+    speakers: HashMap<UserId, Speaker>,
+    on_finished: Option<UtteranceHook>,
+}
+
+impl UtteranceSegmenter {
+    /// Creates a segmenter.
+    pub fn new(config: SegmenterConfig) -> Self {
+        Self {
+            config,
+            speakers: HashMap::new(),
+            on_finished: None,
+        }
+    }
+
+    /// Sets a callback invoked for every finished utterance.
+    pub fn on_utterance_finished(
+        mut self,
+        hook: impl FnMut(&UtteranceFinished) + Send + 'static,
+    ) -> Self {
+        self.on_finished = Some(Box::new(hook));
+        self
+    }
+
+    /// Returns true if a clip is being recorded for a user.
+    pub fn is_recording(&self, user_id: UserId) -> bool {
+        self.speakers
+            .get(&user_id)
+            .is_some_and(|speaker| speaker.clip.is_some())
+    }
+
+    /// Processes user state and audio block events.
+    pub fn handle(
+        &mut self,
+        client: &Client,
+        event: Event,
+        msg: &Message,
+    ) -> Result<Vec<UtteranceFinished>> {
+        let now = SystemTime::now();
+        match event {
+            Event::UserStateChange | Event::UserJoined => match msg.user() {
+                Some(user) => self.update_user(&user, now),
+                None => Ok(Vec::new()),
+            },
+            Event::UserLeft | Event::UserLoggedOut => match msg.user() {
+                Some(user) => Ok(self.remove_user(user.id)?.into_iter().collect()),
+                None => Ok(Vec::new()),
+            },
+            Event::AudioBlock => {
+                let user_id = UserId(msg.source());
+                let types = msg.stream_type().unwrap_or(StreamTypes::VOICE);
+                let mut finished = Vec::new();
+                for block in client.audio_blocks(user_id, types) {
+                    finished.extend(self.push_audio(user_id, &AudioChunk::from(&block), now)?);
+                }
+                Ok(finished)
+            }
+            _ => self.tick(now),
+        }
+    }
+
+    /// Records a user's name, channel and talking flag.
+    pub fn update_user(&mut self, user: &User, now: SystemTime) -> Result<Vec<UtteranceFinished>> {
+        let speaker = self.speakers.entry(user.id).or_default();
+        speaker.name = Some(user.username.clone()).filter(|name| !name.is_empty());
+        let moved = speaker.channel != user.channel_id;
+        speaker.channel = user.channel_id;
+        let talking = user.state.is_talking();
+        if talking || speaker.talking {
+            speaker.last_voice = Some(now);
+        }
+        speaker.talking = talking;
+        let mut finished = Vec::new();
+        if moved {
+            finished.extend(self.close_user(user.id)?);
+        }
+        finished.extend(self.tick(now)?);
+        Ok(finished)
+    }
+
+    /// Appends audio for a user, starting or closing clips as speech is detected.
+    pub fn push_audio(
+        &mut self,
+        user_id: UserId,
+        chunk: &AudioChunk,
+        now: SystemTime,
+    ) -> Result<Vec<UtteranceFinished>> {
+        if chunk.channels <= 0 || chunk.sample_rate <= 0 {
+            return Ok(Vec::new());
+        }
+        let energetic = self
+            .config
+            .energy_threshold_dbfs
+            .is_some_and(|threshold| audio::rms_dbfs(&chunk.samples) >= threshold);
+        let speaker = self.speakers.entry(user_id).or_default();
+        let voice = (self.config.use_talking_flag && speaker.talking) || energetic;
+        if voice {
+            speaker.last_voice = Some(now);
+        }
+        let spec = WavSpec::new(chunk.sample_rate as u32, chunk.channels as u16);
+        let mut finished = Vec::new();
+        let format_changed = speaker
+            .clip
+            .as_ref()
+            .is_some_and(|clip| clip.writer.spec() != spec);
+        if format_changed {
+            finished.extend(self.close_user(user_id)?);
+        }
+        let speaker = self
+            .speakers
+            .get_mut(&user_id)
+            .expect("speaker inserted above");
+        if speaker.clip.is_none() && voice {
+            let name = speaker.name.clone().unwrap_or_else(|| stream_name(user_id));
+            let millis = now
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis();
+            let path = self.config.dir.join(format!(
+                "{}_ch{}_{}.wav",
+                sanitize(&name),
+                speaker.channel.0,
+                millis
+            ));
+            speaker.clip = Some(Clip {
+                writer: WavWriter::create(path, spec)?,
+                channel: speaker.channel,
+                started: now,
+            });
+        }
+        let Some(clip) = speaker.clip.as_mut() else {
+            return Ok(finished);
+        };
+        clip.writer.write_samples(&chunk.samples)?;
+        let too_long = self
+            .config
+            .max_duration
+            .is_some_and(|max| clip.writer.duration() >= max);
+        if too_long {
+            finished.extend(self.close_user(user_id)?);
+        }
+        finished.extend(self.tick(now)?);
+        Ok(finished)
+    }
+
+    /// Closes clips whose hangover has elapsed.
+    pub fn tick(&mut self, now: SystemTime) -> Result<Vec<UtteranceFinished>> {
+        let hangover = self.config.hangover;
+        let expired: Vec<UserId> = self
+            .speakers
+            .iter()
+            .filter(|(_, speaker)| {
+                speaker.clip.is_some() && !speaker.talking_now(self.config.use_talking_flag)
+            })
+            .filter(|(_, speaker)| {
+                speaker
+                    .last_voice
+                    .is_none_or(|last| now.duration_since(last).unwrap_or_default() >= hangover)
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        let mut finished = Vec::new();
+        for user_id in expired {
+            finished.extend(self.close_user(user_id)?);
+        }
+        Ok(finished)
+    }
+
+    /// Closes a user's clip immediately.
+    pub fn close_user(&mut self, user_id: UserId) -> Result<Option<UtteranceFinished>> {
+        let Some(clip) = self
+            .speakers
+            .get_mut(&user_id)
+            .and_then(|speaker| speaker.clip.take())
+        else {
+            return Ok(None);
+        };
+        let duration = clip.writer.duration();
+        let path = clip.writer.finish()?;
+        if duration < self.config.min_duration {
+            std::fs::remove_file(&path)?;
+            return Ok(None);
+        }
+        let finished = UtteranceFinished {
+            user: user_id,
+            channel: clip.channel,
+            path,
+            started: clip.started,
+            duration,
+        };
+        if let Some(hook) = self.on_finished.as_mut() {
+            hook(&finished);
+        }
+        Ok(Some(finished))
+    }
+
+    /// Closes a user's clip and forgets their name, channel and talking state.
+    pub fn remove_user(&mut self, user_id: UserId) -> Result<Option<UtteranceFinished>> {
+        let finished = self.close_user(user_id)?;
+        self.speakers.remove(&user_id);
+        Ok(finished)
+    }
+
+    /// Closes all clips.
+    pub fn finish(&mut self) -> Result<Vec<UtteranceFinished>> {
+        let users: Vec<UserId> = self.speakers.keys().copied().collect();
+        let mut finished = Vec::new();
+        for user_id in users {
+            finished.extend(self.close_user(user_id)?);
+        }
+        Ok(finished)
+    }
+}
+
+impl Speaker {
+    fn talking_now(&self, use_talking_flag: bool) -> bool {
+        use_talking_flag && self.talking
+    }
+}
+
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
@@ -1,6 +1,10 @@
 //! Server administration APIs.
 use super::Client;
-use crate::types::{ChannelId, ClientStatistics, ServerProperties, User, UserId};
+use crate::events::{Error, Result};
+use crate::types::{
+    ChannelId, ClientStatistics, PropertyChange, ServerProperties, ServerPropertiesPatch, User,
+    UserId,
+};
 use crate::utils::ToTT;
 use teamtalk_sys as ffi;
 
@@ -54,6 +58,28 @@ impl Client {
         unsafe { ffi::api().TT_DoUpdateServer(self.ptr, &props.to_ffi()) }
     }
 
+    /// Applies a patch on top of the current properties and returns the changes.
+    pub fn patch_server_properties(
+        &self,
+        patch: &ServerPropertiesPatch,
+        timeout_ms: i32,
+    ) -> Result<Vec<PropertyChange>> {
+        let current = self.get_server_properties().ok_or(Error::CommandFailed {
+            code: -1,
+            message: "Server properties unavailable".to_string(),
+        })?;
+        let mut updated = current.clone();
+        patch.apply_to(&mut updated);
+        let changes = current.diff(&updated);
+        if !changes.is_empty() {
+            self.wait_for_command(self.update_server_properties(&updated), timeout_ms)?;
+        }
+        if patch.save_config {
+            self.wait_for_command(self.save_config(), timeout_ms)?;
+        }
+        Ok(changes)
+    }
+
     /// Saves server configuration.
     pub fn save_config(&self) -> i32 {
         unsafe { ffi::api().TT_DoSaveConfig(self.ptr) }
@@ -0,0 +1,440 @@
+//! Sound device selection by name or UID and automatic reopening on hotplug.
+use super::{Client, Message};
+use crate::events::{Error, Event, Result};
+use crate::types::SoundDevice;
+use crate::utils::strings::glob_match;
+use teamtalk_sys as ffi;
+
+/// Input or output side of the sound system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeviceDirection {
+    Input,
+    Output,
+}
+
+impl DeviceDirection {
+    fn supports(self, device: &SoundDevice) -> bool {
+        match self {
+            Self::Input => device.max_input_channels > 0,
+            Self::Output => device.max_output_channels > 0,
+        }
+    }
+
+    fn sample_rates(self, device: &SoundDevice) -> &[i32] {
+        match self {
+            Self::Input => &device.input_sample_rates,
+            Self::Output => &device.output_sample_rates,
+        }
+    }
+}
+
+/// Criteria for picking a sound device without relying on numeric ids.
+///
+/// A device matching `device_uid` wins over one matching `name`. Without either,
+/// or when nothing matches and `fallback_to_default` is set, the system default
+/// device is chosen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundDeviceSelector {
+    pub name: Option<String>,
+    pub device_uid: Option<String>,
+    pub sound_system: Option<ffi::SoundSystem>,
+    pub sample_rate: Option<i32>,
+    pub fallback_to_default: bool,
+}
+
+impl Default for SoundDeviceSelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SoundDeviceSelector {
+    /// Creates a selector that picks the default device.
+    pub fn new() -> Self {
+        Self {
+            name: None,
+            device_uid: None,
+            sound_system: None,
+            sample_rate: None,
+            fallback_to_default: true,
+        }
+    }
+
+    /// Matches device names against a case-insensitive glob such as `*USB*`.
+    pub fn name(mut self, pattern: impl Into<String>) -> Self {
+        self.name = Some(pattern.into());
+        self
+    }
+
+    /// Matches the device's stable `device_uid`.
+    pub fn device_uid(mut self, uid: impl Into<String>) -> Self {
+        self.device_uid = Some(uid.into());
+        self
+    }
+
+    /// Prefers devices of a sound system.
+    pub fn sound_system(mut self, system: ffi::SoundSystem) -> Self {
+        self.sound_system = Some(system);
+        self
+    }
+
+    /// Requires devices to support a sample rate.
+    pub fn sample_rate(mut self, rate: i32) -> Self {
+        self.sample_rate = Some(rate);
+        self
+    }
+
+    /// Chooses whether to use the default device when nothing matches.
+    pub fn fallback_to_default(mut self, enabled: bool) -> Self {
+        self.fallback_to_default = enabled;
+        self
+    }
+
+    /// Returns true if the selector names a specific device.
+    pub fn is_specific(&self) -> bool {
+        self.name.is_some() || self.device_uid.is_some()
+    }
+
+    /// Returns true if a device satisfies the name or UID criteria.
+    pub fn matches(&self, device: &SoundDevice) -> bool {
+        if let Some(uid) = &self.device_uid
+            && !device.device_uid.is_empty()
+            && device.device_uid == *uid
+        {
+            return true;
+        }
+        self.name
+            .as_ref()
+            .is_some_and(|pattern| glob_match(&pattern.to_lowercase(), &device.name.to_lowercase()))
+    }
+
+    /// Picks a device from `devices`, using `default_id` as the system default.
+    pub fn select<'a>(
+        &self,
+        devices: &'a [SoundDevice],
+        direction: DeviceDirection,
+        default_id: Option<i32>,
+    ) -> Option<&'a SoundDevice> {
+        let usable: Vec<&SoundDevice> = devices
+            .iter()
+            .filter(|device| direction.supports(device))
+            .filter(|device| {
+                self.sample_rate.is_none_or(|rate| {
+                    let rates = direction.sample_rates(device);
+                    rates.contains(&rate)
+                        || (rates.is_empty() && device.default_sample_rate == rate)
+                })
+            })
+            .collect();
+        let by_uid = self.device_uid.as_ref().and_then(|uid| {
+            self.best(
+                usable
+                    .iter()
+                    .copied()
+                    .filter(|device| device.device_uid == *uid),
+                default_id,
+            )
+        });
+        let by_name = || {
+            self.name.as_ref().and_then(|pattern| {
+                let pattern = pattern.to_lowercase();
+                self.best(
+                    usable
+                        .iter()
+                        .copied()
+                        .filter(|device| glob_match(&pattern, &device.name.to_lowercase())),
+                    default_id,
+                )
+            })
+        };
+        by_uid.or_else(by_name).or_else(|| {
+            if self.is_specific() && !self.fallback_to_default {
+                return None;
+            }
+            self.best(usable.iter().copied(), default_id)
+        })
+    }
+
+    /// Picks a device from the client's current device list.
+    pub fn resolve(&self, client: &Client, direction: DeviceDirection) -> Option<SoundDevice> {
+        let devices = client.get_sound_devices();
+        self.select(
+            &devices,
+            direction,
+            Some(self.default_id(client, direction)),
+        )
+        .cloned()
+    }
+
+    fn default_id(&self, client: &Client, direction: DeviceDirection) -> i32 {
+        let (input, output) = match self.sound_system {
+            Some(system) => client.get_default_sound_devices_ex(system),
+            None => client.get_default_sound_devices(),
+        };
+        match direction {
+            DeviceDirection::Input => input,
+            DeviceDirection::Output => output,
+        }
+    }
+
+    fn best<'a>(
+        &self,
+        candidates: impl Iterator<Item = &'a SoundDevice>,
+        default_id: Option<i32>,
+    ) -> Option<&'a SoundDevice> {
+        candidates
+            .enumerate()
+            .min_by_key(|(position, device)| {
+                let other_system = self
+                    .sound_system
+                    .is_some_and(|system| device.system != system);
+                let not_default = default_id != Some(device.id);
+                (other_system, not_default, *position)
+            })
+            .map(|(_, device)| device)
+    }
+}
+
+/// Device change reported by `AudioDeviceManager`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceEvent {
+    Opened {
+        direction: DeviceDirection,
+        device: SoundDevice,
+    },
+    Lost {
+        direction: DeviceDirection,
+        device: SoundDevice,
+    },
+    Unavailable {
+        direction: DeviceDirection,
+    },
+}
+
+/// Opens input and output devices from selectors and reopens them when devices change.
+///
+/// Feed the `SoundDevice*` events to `handle`. A removed or unplugged device is
+/// replaced by the next best match, and a new system default is followed when
+/// the selector does not name a specific device.
+#[derive(Debug, Clone)]
+pub struct AudioDeviceManager {
+    input: SoundDeviceSelector,
+    output: SoundDeviceSelector,
+    follow_default: bool,
+    current_input: Option<SoundDevice>,
+    current_output: Option<SoundDevice>,
+}
+
+impl AudioDeviceManager {
+    /// Creates a manager for the given input and output selectors.
+    pub fn new(input: SoundDeviceSelector, output: SoundDeviceSelector) -> Self {
+        Self {
+            input,
+            output,
+            follow_default: true,
+            current_input: None,
+            current_output: None,
+        }
+    }
+
+    /// Chooses whether to switch to new default devices.
+    pub fn follow_default(mut self, enabled: bool) -> Self {
+        self.follow_default = enabled;
+        self
+    }
+
+    /// Returns the selector for a direction.
+    pub fn selector(&self, direction: DeviceDirection) -> &SoundDeviceSelector {
+        match direction {
+            DeviceDirection::Input => &self.input,
+            DeviceDirection::Output => &self.output,
+        }
+    }
+
+    /// Returns the open device for a direction.
+    pub fn device(&self, direction: DeviceDirection) -> Option<&SoundDevice> {
+        match direction {
+            DeviceDirection::Input => self.current_input.as_ref(),
+            DeviceDirection::Output => self.current_output.as_ref(),
+        }
+    }
+
+    /// Opens both devices.
+    pub fn open(&mut self, client: &Client) -> Result<Vec<DeviceEvent>> {
+        let mut events = Vec::new();
+        for direction in [DeviceDirection::Input, DeviceDirection::Output] {
+            let event = self.reopen(client, direction, None);
+            if let Some(DeviceEvent::Unavailable { .. }) = event {
+                return Err(Error::CommandFailed {
+                    code: -1,
+                    message: format!("no matching sound {} device", direction_name(direction)),
+                });
+            }
+            events.extend(event);
+        }
+        Ok(events)
+    }
+
+    /// Replaces the selector for a direction and reopens the device.
+    pub fn set_selector(
+        &mut self,
+        client: &Client,
+        direction: DeviceDirection,
+        selector: SoundDeviceSelector,
+    ) -> Option<DeviceEvent> {
+        match direction {
+            DeviceDirection::Input => self.input = selector,
+            DeviceDirection::Output => self.output = selector,
+        }
+        self.reopen(client, direction, None)
+    }
+
+    /// Closes both devices.
+    pub fn close(&mut self, client: &Client) {
+        if self.current_input.take().is_some() {
+            client.close_sound_input_device();
+        }
+        if self.current_output.take().is_some() {
+            client.close_sound_output_device();
+        }
+    }
+
+    /// Processes sound device hotplug and default-device events.
+    pub fn handle(&mut self, client: &Client, event: Event, msg: &Message) -> Vec<DeviceEvent> {
+        let mut events = Vec::new();
+        match event {
+            Event::SoundDeviceRemoved | Event::SoundDeviceUnplugged => {
+                let gone = msg.sound_device();
+                for direction in [DeviceDirection::Input, DeviceDirection::Output] {
+                    let Some(current) = self.device(direction).cloned() else {
+                        continue;
+                    };
+                    if gone
+                        .as_ref()
+                        .is_some_and(|gone| !same_device(gone, &current))
+                    {
+                        continue;
+                    }
+                    events.push(DeviceEvent::Lost {
+                        direction,
+                        device: current.clone(),
+                    });
+                    events.extend(self.reopen(client, direction, Some(&current)));
+                }
+            }
+            Event::SoundDeviceAdded => {
+                for direction in [DeviceDirection::Input, DeviceDirection::Output] {
+                    if self.selector(direction).is_specific() {
+                        events.extend(self.reopen_if_changed(client, direction));
+                    }
+                }
+            }
+            Event::SoundDeviceNewDefaultInput | Event::SoundDeviceNewDefaultInputComDevice
+                if self.follows_default(DeviceDirection::Input) =>
+            {
+                events.extend(self.reopen_if_changed(client, DeviceDirection::Input));
+            }
+            Event::SoundDeviceNewDefaultOutput | Event::SoundDeviceNewDefaultOutputComDevice
+                if self.follows_default(DeviceDirection::Output) =>
+            {
+                events.extend(self.reopen_if_changed(client, DeviceDirection::Output));
+            }
+            _ => {}
+        }
+        events
+    }
+
+    fn follows_default(&self, direction: DeviceDirection) -> bool {
+        self.follow_default && !self.selector(direction).is_specific()
+    }
+
+    fn reopen_if_changed(
+        &mut self,
+        client: &Client,
+        direction: DeviceDirection,
+    ) -> Option<DeviceEvent> {
+        let best = self.selector(direction).resolve(client, direction)?;
+        if self
+            .device(direction)
+            .is_some_and(|current| same_device(current, &best))
+        {
+            return None;
+        }
+        self.open_device(client, direction, best)
+    }
+
+    fn reopen(
+        &mut self,
+        client: &Client,
+        direction: DeviceDirection,
+        exclude: Option<&SoundDevice>,
+    ) -> Option<DeviceEvent> {
+        let selector = self.selector(direction);
+        let default_id = selector.default_id(client, direction);
+        let devices: Vec<SoundDevice> = client
+            .get_sound_devices()
+            .into_iter()
+            .filter(|device| exclude.is_none_or(|gone| !same_device(gone, device)))
+            .collect();
+        match selector
+            .select(&devices, direction, Some(default_id))
+            .cloned()
+        {
+            Some(device) => self.open_device(client, direction, device),
+            None => {
+                self.close_device(client, direction);
+                Some(DeviceEvent::Unavailable { direction })
+            }
+        }
+    }
+
+    fn open_device(
+        &mut self,
+        client: &Client,
+        direction: DeviceDirection,
+        device: SoundDevice,
+    ) -> Option<DeviceEvent> {
+        self.close_device(client, direction);
+        let opened = match direction {
+            DeviceDirection::Input => client.init_sound_input_device(device.id),
+            DeviceDirection::Output => client.init_sound_output_device(device.id),
+        };
+        if !opened {
+            return Some(DeviceEvent::Unavailable { direction });
+        }
+        match direction {
+            DeviceDirection::Input => self.current_input = Some(device.clone()),
+            DeviceDirection::Output => self.current_output = Some(device.clone()),
+        }
+        Some(DeviceEvent::Opened { direction, device })
+    }
+
+    fn close_device(&mut self, client: &Client, direction: DeviceDirection) {
+        match direction {
+            DeviceDirection::Input => {
+                if self.current_input.take().is_some() {
+                    client.close_sound_input_device();
+                }
+            }
+            DeviceDirection::Output => {
+                if self.current_output.take().is_some() {
+                    client.close_sound_output_device();
+                }
+            }
+        }
+    }
+}
+
+fn same_device(a: &SoundDevice, b: &SoundDevice) -> bool {
+    if !a.device_uid.is_empty() && !b.device_uid.is_empty() {
+        return a.device_uid == b.device_uid && a.system == b.system;
+    }
+    a.id == b.id
+}
+
+fn direction_name(direction: DeviceDirection) -> &'static str {
+    match direction {
+        DeviceDirection::Input => "input",
+        DeviceDirection::Output => "output",
+    }
+}
@@ -0,0 +1,479 @@
+//! Speech-to-text hooks for per-user audio.
+use super::audio_stream::AudioChunk;
+use super::{Client, Message};
+use crate::events::{Event, Result};
+use crate::types::{ChannelId, StreamTypes, UserId};
+use crate::utils::wav::WavSpec;
+use std::collections::{HashMap, VecDeque};
+
+/// Text recognised from a user's audio.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript {
+    pub text: String,
+    pub is_final: bool,
+}
+
+impl Transcript {
+    /// Creates an interim result.
+    pub fn partial(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            is_final: false,
+        }
+    }
+
+    /// Creates a final result.
+    pub fn final_text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            is_final: true,
+        }
+    }
+}
+
+/// Speech recognition engine fed with PCM audio.
+pub trait Transcriber: Send {
+    /// Feeds interleaved 16-bit samples.
+    fn push(&mut self, samples: &[i16], format: WavSpec) -> Result<()>;
+
+    /// Returns results produced since the last call without blocking.
+    fn poll(&mut self) -> Result<Vec<Transcript>>;
+
+    /// Ends the current utterance and returns the results available so far.
+    ///
+    /// Engines finishing in the background deliver the rest through `poll`.
+    fn finish(&mut self) -> Result<Vec<Transcript>>;
+}
+
+/// Transcriber returning scripted text, for tests and dry runs.
+#[derive(Debug, Clone, Default)]
+pub struct StubTranscriber {
+    responses: VecDeque<String>,
+    samples: usize,
+    total_samples: usize,
+}
+
+impl StubTranscriber {
+    /// Creates a stub returning `responses` in order, one per finished utterance.
+    pub fn new<I, S>(responses: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            responses: responses.into_iter().map(Into::into).collect(),
+            samples: 0,
+            total_samples: 0,
+        }
+    }
+
+    /// Returns the number of samples received.
+    pub fn samples_received(&self) -> usize {
+        self.total_samples
+    }
+}
+
+impl Transcriber for StubTranscriber {
+    fn push(&mut self, samples: &[i16], _format: WavSpec) -> Result<()> {
+        self.samples += samples.len();
+        self.total_samples += samples.len();
+        Ok(())
+    }
+
+    fn poll(&mut self) -> Result<Vec<Transcript>> {
+        Ok(Vec::new())
+    }
+
+    fn finish(&mut self) -> Result<Vec<Transcript>> {
+        if std::mem::take(&mut self.samples) == 0 {
+            return Ok(Vec::new());
+        }
+        Ok(self
+            .responses
+            .pop_front()
+            .map(Transcript::final_text)
+            .into_iter()
+            .collect())
+    }
+}
+
+#[cfg(feature = "json")]
+pub use command::CommandTranscriber;
+
+#[cfg(feature = "json")]
+mod command {
+    use super::{Transcriber, Transcript};
+    use crate::events::{Error, Result};
+    use crate::utils::audio;
+    use crate::utils::wav::WavSpec;
+    use std::io::{BufRead, BufReader, Write};
+    use std::process::{Child, Command, Stdio};
+    use std::sync::mpsc::{self, Receiver, Sender};
+    use std::thread::JoinHandle;
+
+    #[derive(serde::Deserialize)]
+    struct Line {
+        text: String,
+        #[serde(default, rename = "final")]
+        is_final: bool,
+    }
+
+    struct Running {
+        child: Child,
+        input: Option<Sender<Vec<u8>>>,
+        results: Receiver<Result<Transcript>>,
+        reader: JoinHandle<()>,
+    }
+
+    impl Running {
+        /// Returns the exit status once the process and its output reader are done.
+        fn try_reap(&mut self) -> Result<Option<std::process::ExitStatus>> {
+            if !self.reader.is_finished() {
+                return Ok(None);
+            }
+            Ok(self.child.try_wait()?)
+        }
+    }
+
+    /// Transcriber running a local command once per utterance.
+    ///
+    /// The command receives raw little-endian 16-bit PCM in the configured format on stdin
+    /// and prints one JSON object per line, e.g. `{"text": "hello", "final": true}`.
+    /// Audio is written from a background thread, and finished commands are reaped by
+    /// `poll`, so neither call waits for the command. A non-zero exit is reported as an
+    /// error from `poll`.
+    pub struct CommandTranscriber {
+        program: String,
+        args: Vec<String>,
+        format: WavSpec,
+        running: Option<Running>,
+        finishing: Vec<Running>,
+    }
+
+    impl CommandTranscriber {
+        /// Creates an adapter for a command, sending 16 kHz mono audio.
+        pub fn new(program: &str) -> Self {
+            Self {
+                program: program.to_string(),
+                args: Vec::new(),
+                format: WavSpec::new(16000, 1),
+                running: None,
+                finishing: Vec::new(),
+            }
+        }
+
+        /// Appends a command argument.
+        pub fn arg(mut self, arg: &str) -> Self {
+            self.args.push(arg.to_string());
+            self
+        }
+
+        /// Sets the PCM format written to the command.
+        pub fn format(mut self, format: WavSpec) -> Self {
+            self.format = format;
+            self
+        }
+
+        /// Returns the number of finished commands still producing output.
+        pub fn pending(&self) -> usize {
+            self.finishing.len()
+        }
+
+        fn start(&mut self) -> Result<&mut Running> {
+            if self.running.is_none() {
+                let mut child = Command::new(&self.program)
+                    .args(&self.args)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .spawn()?;
+                let unavailable = |stream: &str| Error::CommandFailed {
+                    code: -1,
+                    message: format!("transcriber {stream} unavailable"),
+                };
+                let mut stdin = child.stdin.take().ok_or_else(|| unavailable("stdin"))?;
+                let stdout = child.stdout.take().ok_or_else(|| unavailable("stdout"))?;
+                let (tx, rx) = mpsc::channel();
+                let (input, audio) = mpsc::channel::<Vec<u8>>();
+                let write_errors = tx.clone();
+                std::thread::spawn(move || {
+                    for bytes in audio {
+                        if let Err(err) = stdin.write_all(&bytes) {
+                            let _ = write_errors.send(Err(err.into()));
+                            break;
+                        }
+                    }
+                });
+                let reader = std::thread::spawn(move || {
+                    for line in BufReader::new(stdout).lines() {
+                        let result = match line {
+                            Ok(line) if line.trim().is_empty() => continue,
+                            Ok(line) => serde_json::from_str::<Line>(&line)
+                                .map(|line| Transcript {
+                                    text: line.text,
+                                    is_final: line.is_final,
+                                })
+                                .map_err(|e| Error::Format(e.to_string())),
+                            Err(err) => Err(err.into()),
+                        };
+                        if tx.send(result).is_err() {
+                            break;
+                        }
+                    }
+                });
+                self.running = Some(Running {
+                    child,
+                    input: Some(input),
+                    results: rx,
+                    reader,
+                });
+            }
+            Ok(self.running.as_mut().expect("process started above"))
+        }
+    }
+
+    impl Transcriber for CommandTranscriber {
+        fn push(&mut self, samples: &[i16], format: WavSpec) -> Result<()> {
+            let target = self.format;
+            let converted = audio::convert(samples, &format_of(format), &format_of(target));
+            let bytes: Vec<u8> = converted.iter().flat_map(|s| s.to_le_bytes()).collect();
+            let running = self.start()?;
+            if let Some(input) = running.input.as_ref() {
+                // A failed write is reported through the results by the writer thread.
+                let _ = input.send(bytes);
+            }
+            Ok(())
+        }
+
+        fn poll(&mut self) -> Result<Vec<Transcript>> {
+            let mut results: Vec<Result<Transcript>> = Vec::new();
+            if let Some(running) = self.running.as_mut() {
+                results.extend(running.results.try_iter());
+            }
+            let mut index = 0;
+            while index < self.finishing.len() {
+                let status = self.finishing[index].try_reap()?;
+                results.extend(self.finishing[index].results.try_iter());
+                let Some(status) = status else {
+                    index += 1;
+                    continue;
+                };
+                self.finishing.remove(index);
+                if !status.success() {
+                    results.push(Err(Error::CommandFailed {
+                        code: status.code().unwrap_or(-1),
+                        message: format!("transcriber exited with {status}"),
+                    }));
+                }
+            }
+            results.into_iter().collect()
+        }
+
+        fn finish(&mut self) -> Result<Vec<Transcript>> {
+            if let Some(mut running) = self.running.take() {
+                running.input = None;
+                self.finishing.push(running);
+            }
+            self.poll()
+        }
+    }
+
+    impl Drop for CommandTranscriber {
+        fn drop(&mut self) {
+            for mut running in self
+                .running
+                .take()
+                .into_iter()
+                .chain(self.finishing.drain(..))
+            {
+                let _ = running.child.kill();
+                let _ = running.child.wait();
+            }
+        }
+    }
+
+    fn format_of(spec: WavSpec) -> crate::types::AudioFormat {
+        crate::types::AudioFormat {
+            sample_rate: spec.sample_rate as i32,
+            channels: spec.channels as i32,
+            ..Default::default()
+        }
+    }
+}
+
+type TranscriberFactory = Box<dyn FnMut(UserId) -> Box<dyn Transcriber> + Send>;
+
+/// Per-user transcribers fed from audio block and talking events.
+///
+/// A user's utterance is finished when their talking flag clears.
+pub struct Transcription {
+    factory: TranscriberFactory,
+    users: HashMap<UserId, Box<dyn Transcriber>>,
+}
+
+impl Transcription {
+    /// Creates a hub building a transcriber for each user on demand.
+    pub fn new(factory: impl FnMut(UserId) -> Box<dyn Transcriber> + Send + 'static) -> Self {
+        Self {
+            factory: Box::new(factory),
+            users: HashMap::new(),
+        }
+    }
+
+    /// Feeds a chunk of a user's audio.
+    pub fn push(&mut self, user_id: UserId, chunk: &AudioChunk) -> Result<()> {
+        if chunk.channels <= 0 || chunk.sample_rate <= 0 {
+            return Ok(());
+        }
+        let factory = &mut self.factory;
+        self.users
+            .entry(user_id)
+            .or_insert_with(|| factory(user_id))
+            .push(
+                &chunk.samples,
+                WavSpec::new(chunk.sample_rate as u32, chunk.channels as u16),
+            )
+    }
+
+    /// Ends a user's utterance and returns the results available so far.
+    pub fn finish_user(&mut self, user_id: UserId) -> Result<Vec<(UserId, Transcript)>> {
+        let Some(transcriber) = self.users.get_mut(&user_id) else {
+            return Ok(Vec::new());
+        };
+        Ok(transcriber
+            .finish()?
+            .into_iter()
+            .map(|t| (user_id, t))
+            .collect())
+    }
+
+    /// Collects pending results from all users.
+    pub fn poll(&mut self) -> Result<Vec<(UserId, Transcript)>> {
+        let mut results = Vec::new();
+        for (user_id, transcriber) in &mut self.users {
+            results.extend(transcriber.poll()?.into_iter().map(|t| (*user_id, t)));
+        }
+        Ok(results)
+    }
+
+    /// Ends a user's utterance and drops their transcriber.
+    pub fn remove_user(&mut self, user_id: UserId) -> Result<Vec<(UserId, Transcript)>> {
+        let results = self.finish_user(user_id)?;
+        self.users.remove(&user_id);
+        Ok(results)
+    }
+
+    /// Processes audio block and user events and returns new results.
+    pub fn handle(
+        &mut self,
+        client: &Client,
+        event: Event,
+        msg: &Message,
+    ) -> Result<Vec<(UserId, Transcript)>> {
+        let mut results = Vec::new();
+        match event {
+            Event::AudioBlock => {
+                let user_id = UserId(msg.source());
+                let types = msg.stream_type().unwrap_or(StreamTypes::VOICE);
+                for block in client.audio_blocks(user_id, types) {
+                    self.push(user_id, &AudioChunk::from(&block))?;
+                }
+            }
+            Event::UserStateChange => {
+                if let Some(user) = msg.user()
+                    && !user.state.is_talking()
+                {
+                    results.extend(self.finish_user(user.id)?);
+                }
+            }
+            Event::UserLeft | Event::UserLoggedOut => {
+                if let Some(user) = msg.user() {
+                    results.extend(self.remove_user(user.id)?);
+                }
+            }
+            _ => {}
+        }
+        results.extend(self.poll()?);
+        Ok(results)
+    }
+}
+
+/// Bot helper posting final transcripts to a channel as `nickname: text`.
+pub struct TranscriptBot {
+    transcription: Transcription,
+    channel: Option<ChannelId>,
+    post_partial: bool,
+}
+
+impl TranscriptBot {
+    /// Creates a bot posting to the client's current channel.
+    pub fn new(transcription: Transcription) -> Self {
+        Self {
+            transcription,
+            channel: None,
+            post_partial: false,
+        }
+    }
+
+    /// Posts to a fixed channel instead of the current one.
+    pub fn channel(mut self, channel_id: ChannelId) -> Self {
+        self.channel = Some(channel_id);
+        self
+    }
+
+    /// Also posts interim results.
+    pub fn post_partial(mut self, enabled: bool) -> Self {
+        self.post_partial = enabled;
+        self
+    }
+
+    /// Returns the underlying transcription hub.
+    pub fn transcription(&mut self) -> &mut Transcription {
+        &mut self.transcription
+    }
+
+    /// Processes an event and posts any new transcripts.
+    pub fn handle(
+        &mut self,
+        client: &Client,
+        event: Event,
+        msg: &Message,
+    ) -> Result<Vec<(UserId, Transcript)>> {
+        let results = self.transcription.handle(client, event, msg)?;
+        let channel_id = self.channel.unwrap_or_else(|| client.my_channel_id());
+        for (user_id, transcript) in &results {
+            if let Some(line) = self.format_line(client, *user_id, transcript) {
+                client.send_to_channel(channel_id, &line);
+            }
+        }
+        Ok(results)
+    }
+
+    fn format_line(
+        &self,
+        client: &Client,
+        user_id: UserId,
+        transcript: &Transcript,
+    ) -> Option<String> {
+        let text = transcript.text.trim();
+        if text.is_empty() || (!transcript.is_final && !self.post_partial) {
+            return None;
+        }
+        let name = client
+            .get_user(user_id)
+            .map(|user| user.nickname)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| format!("#{}", user_id.0));
+        Some(format_transcript(&name, transcript))
+    }
+}
+
+/// Formats a transcript line, marking interim results with an ellipsis.
+pub fn format_transcript(name: &str, transcript: &Transcript) -> String {
+    let text = transcript.text.trim();
+    if transcript.is_final {
+        format!("{name}: {text}")
+    } else {
+        format!("{name}: {text}…")
+    }
+}
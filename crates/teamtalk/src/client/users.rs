@@ -1,7 +1,8 @@
 //! User management APIs.
 use super::Client;
 use crate::types::{
-    ChannelId, MessageTarget, Subscriptions, User, UserAccount, UserId, UserStatistics, UserStatus,
+    ChannelId, MessageTarget, Subscriptions, User, UserAccount, UserId, UserRights, UserStatistics,
+    UserStatus, UserTypes,
 };
 use crate::utils::ToTT;
 use std::env;
@@ -130,13 +131,13 @@ impl Client {
     }
 
     /// Returns the user type of the current user.
-    pub fn get_my_user_type(&self) -> u32 {
-        unsafe { ffi::api().TT_GetMyUserType(self.ptr) }
+    pub fn get_my_user_type(&self) -> UserTypes {
+        UserTypes::from_raw(unsafe { ffi::api().TT_GetMyUserType(self.ptr) })
     }
 
     /// Returns the user rights of the current user.
-    pub fn get_my_user_rights(&self) -> u32 {
-        unsafe { ffi::api().TT_GetMyUserRights(self.ptr) }
+    pub fn get_my_user_rights(&self) -> UserRights {
+        UserRights::from_raw(unsafe { ffi::api().TT_GetMyUserRights(self.ptr) })
     }
 
     /// Requests user data for the current user.
@@ -179,8 +180,8 @@ impl Client {
     }
 
     /// Bans a user with custom ban types.
-    pub fn ban_user_ex(&self, user_id: UserId, ban_types: u32) -> i32 {
-        unsafe { ffi::api().TT_DoBanUserEx(self.ptr, user_id.0, ban_types) }
+    pub fn ban_user_ex(&self, user_id: UserId, ban_types: crate::types::BanTypes) -> i32 {
+        unsafe { ffi::api().TT_DoBanUserEx(self.ptr, user_id.0, ban_types.raw()) }
     }
 
     /// Removes a ban by IP address.
@@ -296,12 +297,12 @@ impl Client {
 
     /// Unsubscribes from all streams for a user.
     pub fn unsubscribe_all_from_user(&self, user_id: UserId) -> i32 {
-        unsafe { ffi::api().TT_DoUnsubscribe(self.ptr, user_id.0, Subscriptions::ALL) }
+        unsafe { ffi::api().TT_DoUnsubscribe(self.ptr, user_id.0, Subscriptions::ALL.raw()) }
     }
 
     /// Unsubscribes from all streams for all users.
     pub fn unsubscribe_all(&self) -> i32 {
-        unsafe { ffi::api().TT_DoUnsubscribe(self.ptr, 0, Subscriptions::ALL) }
+        unsafe { ffi::api().TT_DoUnsubscribe(self.ptr, 0, Subscriptions::ALL.raw()) }
     }
 
     pub fn channel_op_ex(
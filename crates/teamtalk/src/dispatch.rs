@@ -383,6 +383,11 @@ impl<S: EventSource> Dispatcher<S> {
         }
     }
 
+    /// Dispatches an externally produced event to the handlers.
+    pub fn dispatch(&mut self, event: Event, message: Message) -> DispatchFlow {
+        self.process_event(event, message)
+    }
+
     fn process_event(&mut self, event: Event, message: Message) -> DispatchFlow {
         let client = self.source.client();
         if let Some(reconnect) = self.reconnect.as_mut() {
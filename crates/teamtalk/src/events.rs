@@ -1,5 +1,5 @@
 //! Event and error types emitted by the TeamTalk client.
-use crate::types::ChannelId;
+use crate::types::{ChannelId, UserId};
 use std::time::Duration;
 use teamtalk_sys as ffi;
 
@@ -61,11 +61,23 @@ pub enum Event {
     SoundDeviceNewDefaultInputComDevice,
     SoundDeviceNewDefaultOutputComDevice,
     Reconnecting { attempt: u32, delay: Duration },
+    HealthDegraded,
+    HealthUnhealthy,
+    HealthRecovered,
+    QualityDegraded { user: UserId, loss: u8 },
+    QualityRecovered { user: UserId },
+    TransmitQueueChanged { channel: ChannelId },
+    TransmitUsersChanged { channel: ChannelId },
     Unknown(ffi::ClientEvent),
 }
 
 /// Client connection state derived from commands and events.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "state", content = "channel", rename_all = "snake_case")
+)]
 pub enum ConnectionState {
     #[default]
     Idle,
@@ -73,11 +85,37 @@ pub enum ConnectionState {
     Connected,
     LoggingIn,
     LoggedIn,
+    Creating,
     Joining(ChannelId),
     Joined(ChannelId),
     Disconnected,
 }
 
+impl ConnectionState {
+    /// Returns a stable lowercase name for the state.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConnectionState::Idle => "idle",
+            ConnectionState::Connecting => "connecting",
+            ConnectionState::Connected => "connected",
+            ConnectionState::LoggingIn => "logging_in",
+            ConnectionState::LoggedIn => "logged_in",
+            ConnectionState::Creating => "creating",
+            ConnectionState::Joining(_) => "joining",
+            ConnectionState::Joined(_) => "joined",
+            ConnectionState::Disconnected => "disconnected",
+        }
+    }
+
+    /// Returns the channel associated with joining or joined states.
+    pub fn channel(&self) -> Option<ChannelId> {
+        match self {
+            ConnectionState::Joining(id) | ConnectionState::Joined(id) => Some(*id),
+            _ => None,
+        }
+    }
+}
+
 impl From<ffi::ClientEvent> for Event {
     fn from(event: ffi::ClientEvent) -> Self {
         match event {
@@ -196,6 +234,12 @@ pub enum Error {
     MissingLoginParams,
     #[error("SDK Error: {code} ({message})")]
     ClientError { code: i32, message: String },
+    #[error("Format error: {0}")]
+    Format(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Channel not found: {0}")]
+    ChannelNotFound(String),
 }
 
 /// Convenience result type for TeamTalk operations.
@@ -0,0 +1,495 @@
+//! Persistent text message history with search and replay.
+use crate::client::{Client, Message};
+use crate::events::{Error, Event, Result};
+use crate::types::{ChannelId, ChannelPath, TextMessage, UserId};
+use crate::utils::csv;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use teamtalk_sys as ffi;
+
+/// Kind of a recorded message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum MessageKind {
+    User,
+    Channel,
+    Broadcast,
+    Custom,
+}
+
+impl MessageKind {
+    /// Returns the kind of a TeamTalk message type.
+    pub fn from_msg_type(msg_type: ffi::TextMsgType) -> Option<Self> {
+        match msg_type {
+            ffi::TextMsgType::MSGTYPE_USER => Some(Self::User),
+            ffi::TextMsgType::MSGTYPE_CHANNEL => Some(Self::Channel),
+            ffi::TextMsgType::MSGTYPE_BROADCAST => Some(Self::Broadcast),
+            ffi::TextMsgType::MSGTYPE_CUSTOM => Some(Self::Custom),
+            _ => None,
+        }
+    }
+
+    /// Returns the name used in the history file.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Channel => "channel",
+            Self::Broadcast => "broadcast",
+            Self::Custom => "custom",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "user" => Some(Self::User),
+            "channel" => Some(Self::Channel),
+            "broadcast" => Some(Self::Broadcast),
+            "custom" => Some(Self::Custom),
+            _ => None,
+        }
+    }
+}
+
+/// Stored text message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HistoryEntry {
+    pub id: u64,
+    pub time: SystemTime,
+    pub kind: MessageKind,
+    pub from_id: UserId,
+    pub from_username: String,
+    pub to_id: UserId,
+    pub channel_id: ChannelId,
+    pub channel_path: Option<ChannelPath>,
+    pub text: String,
+}
+
+/// Limits applied by `ChatHistory::prune`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Retention {
+    pub max_entries: Option<usize>,
+    pub max_age: Option<Duration>,
+}
+
+impl Retention {
+    /// Creates a policy that keeps everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps at most `count` of the newest entries.
+    pub fn max_entries(mut self, count: usize) -> Self {
+        self.max_entries = Some(count);
+        self
+    }
+
+    /// Drops entries older than `age`.
+    pub fn max_age(mut self, age: Duration) -> Self {
+        self.max_age = Some(age);
+        self
+    }
+}
+
+/// Filter for `ChatHistory::search`; unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryQuery {
+    pub user: Option<UserId>,
+    pub username: Option<String>,
+    pub channel_path: Option<ChannelPath>,
+    pub kind: Option<MessageKind>,
+    pub since: Option<SystemTime>,
+    pub until: Option<SystemTime>,
+    pub text: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl HistoryQuery {
+    /// Creates an empty query.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Matches messages sent by or to a user id.
+    pub fn user(mut self, user_id: UserId) -> Self {
+        self.user = Some(user_id);
+        self
+    }
+
+    /// Matches messages sent by a username.
+    pub fn username(mut self, username: &str) -> Self {
+        self.username = Some(username.to_string());
+        self
+    }
+
+    /// Matches messages in a channel path.
+    pub fn channel_path(mut self, path: ChannelPath) -> Self {
+        self.channel_path = Some(path);
+        self
+    }
+
+    /// Matches messages of a kind.
+    pub fn kind(mut self, kind: MessageKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Matches messages at or after a time.
+    pub fn since(mut self, time: SystemTime) -> Self {
+        self.since = Some(time);
+        self
+    }
+
+    /// Matches messages before a time.
+    pub fn until(mut self, time: SystemTime) -> Self {
+        self.until = Some(time);
+        self
+    }
+
+    /// Matches messages containing the text, ignoring case.
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = Some(text.to_lowercase());
+        self
+    }
+
+    /// Returns at most the newest `count` matches.
+    pub fn limit(mut self, count: usize) -> Self {
+        self.limit = Some(count);
+        self
+    }
+
+    /// Returns true if the entry matches the filter.
+    pub fn matches(&self, entry: &HistoryEntry) -> bool {
+        self.user
+            .is_none_or(|id| entry.from_id == id || entry.to_id == id)
+            && self
+                .username
+                .as_ref()
+                .is_none_or(|name| entry.from_username.eq_ignore_ascii_case(name))
+            && self
+                .channel_path
+                .as_ref()
+                .is_none_or(|path| entry.channel_path.as_ref() == Some(path))
+            && self.kind.is_none_or(|kind| entry.kind == kind)
+            && self.since.is_none_or(|since| entry.time >= since)
+            && self.until.is_none_or(|until| entry.time < until)
+            && self
+                .text
+                .as_ref()
+                .is_none_or(|text| entry.text.to_lowercase().contains(text))
+    }
+}
+
+const HEADER: [&str; 9] = [
+    "id",
+    "time_ms",
+    "kind",
+    "from_id",
+    "from_username",
+    "to_id",
+    "channel_id",
+    "channel_path",
+    "text",
+];
+
+type FragmentKey = (MessageKind, UserId, UserId, ChannelId);
+
+/// Append-only message history for one server, stored as CSV under a directory.
+///
+/// The file is loaded into memory on open and searched linearly, which suits
+/// per-server chat logs rather than large archives.
+pub struct ChatHistory {
+    path: PathBuf,
+    entries: Vec<HistoryEntry>,
+    next_id: u64,
+    retention: Retention,
+    fragments: HashMap<FragmentKey, String>,
+}
+
+impl ChatHistory {
+    /// Opens the history for `host` in `dir`, loading existing entries.
+    pub fn open(dir: impl AsRef<Path>, host: &str) -> Result<Self> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        Self::open_file(dir.join(format!("{}.csv", file_stem(host))))
+    }
+
+    /// Opens a history file directly.
+    ///
+    /// A partial last row left by an interrupted append is truncated away.
+    pub fn open_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match std::fs::read(&path) {
+            Ok(bytes) => {
+                let len = complete_len(&bytes);
+                if len < bytes.len() {
+                    std::fs::OpenOptions::new()
+                        .write(true)
+                        .open(&path)?
+                        .set_len(len as u64)?;
+                }
+                let text = std::str::from_utf8(&bytes[..len])
+                    .map_err(|err| Error::Format(format!("history file: {err}")))?;
+                parse_entries(text)?
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        let next_id = entries.iter().map(|e| e.id + 1).max().unwrap_or(1);
+        Ok(Self {
+            path,
+            entries,
+            next_id,
+            retention: Retention::default(),
+            fragments: HashMap::new(),
+        })
+    }
+
+    /// Returns the backing file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns all stored entries, oldest first.
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Sets the retention policy applied on `prune`.
+    pub fn set_retention(&mut self, retention: Retention) {
+        self.retention = retention;
+    }
+
+    /// Records a text message event, looking up the channel path.
+    pub fn handle(
+        &mut self,
+        client: &Client,
+        event: Event,
+        msg: &Message,
+    ) -> Result<Option<HistoryEntry>> {
+        if event != Event::TextMessage {
+            return Ok(None);
+        }
+        let Some(text) = msg.text() else {
+            return Ok(None);
+        };
+        let channel_path = if text.channel_id.0 > 0 {
+            client.channel_path(text.channel_id).ok()
+        } else {
+            None
+        };
+        self.record(&text, channel_path.as_ref(), SystemTime::now())
+    }
+
+    /// Records a message, joining fragments flagged with `more`.
+    ///
+    /// Returns the stored entry once the last fragment arrives.
+    pub fn record(
+        &mut self,
+        msg: &TextMessage,
+        channel_path: Option<&ChannelPath>,
+        now: SystemTime,
+    ) -> Result<Option<HistoryEntry>> {
+        let Some(kind) = MessageKind::from_msg_type(msg.msg_type) else {
+            return Ok(None);
+        };
+        let key = (kind, msg.from_id, msg.to_id, msg.channel_id);
+        if msg.more {
+            self.fragments.entry(key).or_default().push_str(&msg.text);
+            return Ok(None);
+        }
+        let mut text = self.fragments.remove(&key).unwrap_or_default();
+        text.push_str(&msg.text);
+        let entry = HistoryEntry {
+            id: self.next_id,
+            time: now,
+            kind,
+            from_id: msg.from_id,
+            from_username: msg.from_username.clone(),
+            to_id: msg.to_id,
+            channel_id: msg.channel_id,
+            channel_path: channel_path.cloned(),
+            text,
+        };
+        self.append(&entry)?;
+        self.next_id += 1;
+        self.entries.push(entry.clone());
+        Ok(Some(entry))
+    }
+
+    /// Returns matching entries, oldest first.
+    pub fn search(&self, query: &HistoryQuery) -> Vec<&HistoryEntry> {
+        let mut found: Vec<&HistoryEntry> =
+            self.entries.iter().filter(|e| query.matches(e)).collect();
+        if let Some(limit) = query.limit {
+            let skip = found.len().saturating_sub(limit);
+            found.drain(..skip);
+        }
+        found
+    }
+
+    /// Returns the newest channel messages for a channel path, oldest first.
+    pub fn recent(&self, channel_path: &ChannelPath, count: usize) -> Vec<&HistoryEntry> {
+        self.search(
+            &HistoryQuery::new()
+                .kind(MessageKind::Channel)
+                .channel_path(channel_path.clone())
+                .limit(count),
+        )
+    }
+
+    /// Sends the newest channel messages of a channel to a user as private messages.
+    ///
+    /// Returns the number of messages sent.
+    pub fn replay(
+        &self,
+        client: &Client,
+        user_id: UserId,
+        channel_path: &ChannelPath,
+        count: usize,
+    ) -> usize {
+        self.recent(channel_path, count)
+            .into_iter()
+            .filter(|entry| client.send_to_user(user_id, &replay_line(entry)) > 0)
+            .count()
+    }
+
+    /// Applies the retention policy and rewrites the file if entries were dropped.
+    pub fn prune(&mut self, now: SystemTime) -> Result<usize> {
+        let before = self.entries.len();
+        if let Some(age) = self.retention.max_age {
+            self.entries
+                .retain(|e| now.duration_since(e.time).unwrap_or_default() <= age);
+        }
+        if let Some(max) = self.retention.max_entries {
+            let skip = self.entries.len().saturating_sub(max);
+            self.entries.drain(..skip);
+        }
+        let removed = before - self.entries.len();
+        if removed > 0 {
+            self.rewrite()?;
+        }
+        Ok(removed)
+    }
+
+    fn append(&self, entry: &HistoryEntry) -> Result<()> {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        if file.metadata()?.len() == 0 {
+            file.write_all(csv::write_row(&HEADER).as_bytes())?;
+        }
+        file.write_all(entry_row(entry).as_bytes())?;
+        Ok(())
+    }
+
+    fn rewrite(&self) -> Result<()> {
+        let mut text = csv::write_row(&HEADER);
+        for entry in &self.entries {
+            text.push_str(&entry_row(entry));
+        }
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, text)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Formats an entry as `[HH:MM] name: text` using UTC time.
+pub fn replay_line(entry: &HistoryEntry) -> String {
+    let secs = entry
+        .time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let line = format!(
+        "[{:02}:{:02}] {}: {}",
+        secs / 3600 % 24,
+        secs / 60 % 60,
+        entry.from_username,
+        entry.text
+    );
+    match line.char_indices().nth(400) {
+        Some((idx, _)) => line[..idx].to_string(),
+        None => line,
+    }
+}
+
+fn file_stem(host: &str) -> String {
+    host.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn entry_row(entry: &HistoryEntry) -> String {
+    let millis = entry
+        .time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    csv::write_row(&[
+        entry.id.to_string(),
+        millis.to_string(),
+        entry.kind.as_str().to_string(),
+        entry.from_id.0.to_string(),
+        entry.from_username.clone(),
+        entry.to_id.0.to_string(),
+        entry.channel_id.0.to_string(),
+        entry
+            .channel_path
+            .as_ref()
+            .map(ChannelPath::to_string)
+            .unwrap_or_default(),
+        entry.text.clone(),
+    ])
+}
+
+/// Returns the length of the text up to the end of the last complete row.
+fn complete_len(bytes: &[u8]) -> usize {
+    let mut quoted = false;
+    let mut end = 0;
+    for (idx, byte) in bytes.iter().enumerate() {
+        match byte {
+            b'"' => quoted = !quoted,
+            b'\n' if !quoted => end = idx + 1,
+            _ => {}
+        }
+    }
+    end
+}
+
+fn parse_entries(text: &str) -> Result<Vec<HistoryEntry>> {
+    let mut entries = Vec::new();
+    for (line, row) in csv::parse(text)?.into_iter().enumerate().skip(1) {
+        let invalid = || Error::Format(format!("history row {}: invalid entry", line + 1));
+        if row.len() != HEADER.len() {
+            return Err(invalid());
+        }
+        let number = |value: &str| value.parse::<i64>().map_err(|_| invalid());
+        entries.push(HistoryEntry {
+            id: number(&row[0])? as u64,
+            time: UNIX_EPOCH + Duration::from_millis(number(&row[1])? as u64),
+            kind: MessageKind::parse(&row[2]).ok_or_else(invalid)?,
+            from_id: UserId(number(&row[3])? as i32),
+            from_username: row[4].clone(),
+            to_id: UserId(number(&row[5])? as i32),
+            channel_id: ChannelId(number(&row[6])? as i32),
+            channel_path: match row[7].as_str() {
+                "" => None,
+                path => Some(ChannelPath::parse(path).map_err(|_| invalid())?),
+            },
+            text: row[8].clone(),
+        });
+    }
+    Ok(entries)
+}
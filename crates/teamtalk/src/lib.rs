@@ -11,26 +11,38 @@ pub mod utils;
 pub mod async_api;
 #[cfg(feature = "dispatch")]
 pub mod dispatch;
+#[cfg(feature = "history")]
+pub mod history;
 #[cfg(feature = "logging")]
 pub mod logging;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 #[cfg(feature = "mock")]
 pub mod mock;
+#[cfg(feature = "serde")]
+pub mod serde_support;
 
 #[cfg(feature = "async")]
 pub use async_api::{AsyncClient, AsyncConfig};
 pub use client::recording::RecordSession;
 pub use client::users::LoginParams;
 pub use client::{
-    Client, ClientEvent, ClientHealth, ClientHooks, ClientInfo, ClientManager, ClientRegistry,
-    Message, ReconnectConfig,
+    ApplyOptions, ApplyReport, ChannelPlan, ChannelSpec, ChannelTree, Client, ClientEvent,
+    ClientHealth, ClientHooks, ClientInfo, ClientManager, ClientRegistry, HealthStatus, Message,
+    QualityConfig, QualityMonitor, QualityReport, RaiseHandQueue, ReconnectConfig, RegistryEvent,
+    TransmitMonitor, WatchdogConfig,
 };
 #[cfg(feature = "dispatch")]
 pub use dispatch::{
     ClientConfig, ConnectParamsOwned, DispatchFlow, Dispatcher, EventContext, ReconnectSettings,
 };
 pub use events::{ConnectionState, Error, Event, Result};
+#[cfg(feature = "history")]
+pub use history::{ChatHistory, HistoryEntry, HistoryQuery};
+#[cfg(feature = "metrics")]
+pub use metrics::{MetricsCollector, MetricsHandle, MetricsServer};
 #[cfg(feature = "mock")]
-pub use mock::{MockClient, MockMessage, MockUserBuilder};
+pub use mock::{MockAudioBlock, MockClient, MockMessage, MockUserBuilder};
 pub use types::ClientId;
 
 /// Initializes the TeamTalk SDK by loading the runtime DLL from the default location.
@@ -0,0 +1,585 @@
+//! Statistics sampling and OpenMetrics export.
+use crate::client::{Client, Message};
+use crate::events::Event;
+use crate::types::{ClientId, ClientStatistics, ServerStatistics, User, UserStatistics};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Returns the per-second rate between two cumulative counter values.
+pub fn counter_rate(previous: i64, current: i64, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs <= 0.0 || current < previous {
+        return 0.0;
+    }
+    (current - previous) as f64 / secs
+}
+
+/// Escapes a label value for the OpenMetrics text format.
+pub fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// User statistics sample with identifying labels.
+#[derive(Debug, Clone)]
+pub struct UserSample {
+    pub user: User,
+    pub channel_path: String,
+    pub stats: UserStatistics,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Counter,
+    Gauge,
+}
+
+struct Series {
+    name: &'static str,
+    help: &'static str,
+    kind: Kind,
+    labels: Vec<(&'static str, String)>,
+    value: f64,
+}
+
+impl Series {
+    fn key(&self) -> String {
+        let labels: Vec<String> = self
+            .labels
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect();
+        format!("{}|{}", self.name, labels.join(","))
+    }
+}
+
+type CounterValues = HashMap<String, (i64, Instant)>;
+
+#[derive(Default)]
+struct ClientMetrics {
+    client: Vec<Series>,
+    users: Vec<Series>,
+    server: Vec<Series>,
+    client_counters: CounterValues,
+    user_counters: CounterValues,
+    server_counters: CounterValues,
+}
+
+/// Collects the counter values of one sample and derives rates from the previous one.
+///
+/// Only the counters seen in the sample are kept, so departed users and channels
+/// do not accumulate.
+struct CounterSample<'a> {
+    previous: &'a CounterValues,
+    current: CounterValues,
+    now: Instant,
+}
+
+impl<'a> CounterSample<'a> {
+    fn new(previous: &'a CounterValues, now: Instant) -> Self {
+        Self {
+            previous,
+            current: HashMap::new(),
+            now,
+        }
+    }
+
+    fn push(
+        &mut self,
+        out: &mut Vec<Series>,
+        name: &'static str,
+        rate_name: &'static str,
+        help: &'static str,
+        labels: Vec<(&'static str, String)>,
+        value: i64,
+    ) {
+        let now = self.now;
+        let series = Series {
+            name,
+            help,
+            kind: Kind::Counter,
+            labels,
+            value: value as f64,
+        };
+        let key = series.key();
+        let rate = match self.previous.get(&key) {
+            Some(&(prev, at)) => counter_rate(prev, value, now.duration_since(at)),
+            None => 0.0,
+        };
+        self.current.insert(key, (value, now));
+        out.push(Series {
+            name: rate_name,
+            help: "Per-second rate of the matching counter.",
+            kind: Kind::Gauge,
+            labels: series.labels.clone(),
+            value: rate,
+        });
+        out.push(series);
+    }
+}
+
+/// Shared handle to the latest rendered metrics text.
+#[derive(Clone, Default)]
+pub struct MetricsHandle {
+    text: Arc<Mutex<String>>,
+}
+
+impl MetricsHandle {
+    /// Returns the latest rendered metrics text.
+    pub fn get(&self) -> String {
+        self.text.lock().map(|t| t.clone()).unwrap_or_default()
+    }
+
+    fn set(&self, text: String) {
+        if let Ok(mut guard) = self.text.lock() {
+            *guard = text;
+        }
+    }
+}
+
+/// Periodic statistics sampler with OpenMetrics rendering.
+pub struct MetricsCollector {
+    interval: Duration,
+    query_server: bool,
+    last_sample: Option<Instant>,
+    clients: BTreeMap<ClientId, (Option<String>, ClientMetrics)>,
+    handle: MetricsHandle,
+}
+
+impl Default for MetricsCollector {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(10))
+    }
+}
+
+impl MetricsCollector {
+    /// Creates a collector sampling at the provided interval.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            query_server: true,
+            last_sample: None,
+            clients: BTreeMap::new(),
+            handle: MetricsHandle::default(),
+        }
+    }
+
+    /// Enables or disables server statistics queries on each sample.
+    pub fn query_server(mut self, enabled: bool) -> Self {
+        self.query_server = enabled;
+        self
+    }
+
+    /// Returns the sampling interval.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Returns a handle to the latest rendered text.
+    pub fn handle(&self) -> MetricsHandle {
+        self.handle.clone()
+    }
+
+    /// Returns true when the next sample is due.
+    pub fn is_due(&self) -> bool {
+        self.last_sample
+            .is_none_or(|last| last.elapsed() >= self.interval)
+    }
+
+    /// Samples all clients when the interval has elapsed.
+    pub fn tick<'a>(&mut self, clients: impl IntoIterator<Item = &'a Client>) -> bool {
+        if !self.is_due() {
+            return false;
+        }
+        for client in clients {
+            self.sample(client);
+        }
+        self.last_sample = Some(Instant::now());
+        self.publish();
+        true
+    }
+
+    /// Samples client and user statistics for one client.
+    pub fn sample(&mut self, client: &Client) {
+        let now = Instant::now();
+        let id = client.id();
+        let label = client.label();
+        if let Some(stats) = client.get_client_statistics() {
+            self.record_client(id, label.clone(), &stats, now);
+        }
+        let users: Vec<UserSample> = client
+            .get_server_users()
+            .into_iter()
+            .filter_map(|user| {
+                let stats = client.get_user_statistics(user.id)?;
+                let channel_path = client.get_channel_path(user.channel_id);
+                Some(UserSample {
+                    user,
+                    channel_path,
+                    stats,
+                })
+            })
+            .collect();
+        self.record_users(id, label, &users, now);
+        if self.query_server && client.is_connected() {
+            client.query_server_stats();
+        }
+    }
+
+    /// Records server statistics delivered through `Event::ServerStatistics`.
+    pub fn observe(&mut self, client: &Client, event: Event, message: &Message) {
+        if event == Event::ServerStatistics
+            && let Some(stats) = message.server_statistics()
+        {
+            self.record_server(client.id(), client.label(), &stats, Instant::now());
+            self.publish();
+        }
+    }
+
+    /// Records a client statistics sample.
+    pub fn record_client(
+        &mut self,
+        id: ClientId,
+        label: Option<String>,
+        stats: &ClientStatistics,
+        now: Instant,
+    ) {
+        let base = base_labels(id, label.as_deref());
+        let entry = self.entry(id, label);
+        let mut out = Vec::new();
+        let mut counters = CounterSample::new(&entry.client_counters, now);
+        for (protocol, ping) in [("tcp", stats.tcp_ping), ("udp", stats.udp_ping)] {
+            out.push(Series {
+                name: "teamtalk_client_ping_milliseconds",
+                help: "Last measured ping time.",
+                kind: Kind::Gauge,
+                labels: with(&base, &[("protocol", protocol)]),
+                value: ping as f64,
+            });
+        }
+        let values = [
+            ("udp", "sent", stats.udp_sent),
+            ("udp", "recv", stats.udp_recv),
+            ("voice", "sent", stats.voice_sent),
+            ("voice", "recv", stats.voice_recv),
+            ("video", "sent", stats.video_sent),
+            ("video", "recv", stats.video_recv),
+            ("media_audio", "sent", stats.media_audio_sent),
+            ("media_audio", "recv", stats.media_audio_recv),
+            ("media_video", "sent", stats.media_video_sent),
+            ("media_video", "recv", stats.media_video_recv),
+            ("desktop", "sent", stats.desktop_sent),
+            ("desktop", "recv", stats.desktop_recv),
+        ];
+        for (stream, direction, value) in values {
+            counters.push(
+                &mut out,
+                "teamtalk_client_bytes",
+                "teamtalk_client_bytes_per_second",
+                "Bytes transferred by the client.",
+                with(&base, &[("stream", stream), ("direction", direction)]),
+                value,
+            );
+        }
+        entry.client_counters = counters.current;
+        entry.client = out;
+    }
+
+    /// Records user statistics samples for one client.
+    pub fn record_users(
+        &mut self,
+        id: ClientId,
+        label: Option<String>,
+        users: &[UserSample],
+        now: Instant,
+    ) {
+        let base = base_labels(id, label.as_deref());
+        let entry = self.entry(id, label);
+        let mut out = Vec::new();
+        let mut counters = CounterSample::new(&entry.user_counters, now);
+        for sample in users {
+            let user_id = sample.user.id.0.to_string();
+            let user_labels = with(
+                &base,
+                &[
+                    ("user", user_id.as_str()),
+                    ("nickname", sample.user.nickname.as_str()),
+                    ("channel", sample.channel_path.as_str()),
+                ],
+            );
+            let s = &sample.stats;
+            let streams = [
+                ("voice", s.voice_recv, s.voice_lost),
+                ("media_audio", s.media_audio_recv, s.media_audio_lost),
+                ("video", s.video_frames_recv, s.video_frames_lost),
+                (
+                    "media_video",
+                    s.media_video_frames_recv,
+                    s.media_video_frames_lost,
+                ),
+            ];
+            for (stream, recv, lost) in streams {
+                for (kind, value) in [("recv", recv), ("lost", lost)] {
+                    counters.push(
+                        &mut out,
+                        "teamtalk_user_packets",
+                        "teamtalk_user_packets_per_second",
+                        "Packets or frames received from a user.",
+                        with(&user_labels, &[("stream", stream), ("kind", kind)]),
+                        value,
+                    );
+                }
+                let total = recv + lost;
+                out.push(Series {
+                    name: "teamtalk_user_loss_ratio",
+                    help: "Cumulative loss ratio for a user stream.",
+                    kind: Kind::Gauge,
+                    labels: with(&user_labels, &[("stream", stream)]),
+                    value: if total > 0 {
+                        lost as f64 / total as f64
+                    } else {
+                        0.0
+                    },
+                });
+            }
+        }
+        entry.user_counters = counters.current;
+        entry.users = out;
+    }
+
+    /// Records a server statistics sample.
+    pub fn record_server(
+        &mut self,
+        id: ClientId,
+        label: Option<String>,
+        stats: &ServerStatistics,
+        now: Instant,
+    ) {
+        let base = base_labels(id, label.as_deref());
+        let entry = self.entry(id, label);
+        let mut out = Vec::new();
+        let mut counters = CounterSample::new(&entry.server_counters, now);
+        let values = [
+            ("total", "tx", stats.total_tx),
+            ("total", "rx", stats.total_rx),
+            ("voice", "tx", stats.voice_tx),
+            ("voice", "rx", stats.voice_rx),
+            ("video", "tx", stats.video_tx),
+            ("video", "rx", stats.video_rx),
+            ("media", "tx", stats.media_tx),
+            ("media", "rx", stats.media_rx),
+            ("desktop", "tx", stats.desktop_tx),
+            ("desktop", "rx", stats.desktop_rx),
+            ("files", "tx", stats.files_tx),
+            ("files", "rx", stats.files_rx),
+        ];
+        for (stream, direction, value) in values {
+            counters.push(
+                &mut out,
+                "teamtalk_server_bytes",
+                "teamtalk_server_bytes_per_second",
+                "Bytes transferred by the server.",
+                with(&base, &[("stream", stream), ("direction", direction)]),
+                value,
+            );
+        }
+        for (name, help, value) in [
+            (
+                "teamtalk_server_users_served",
+                "Users served since server start.",
+                stats.users_served as f64,
+            ),
+            (
+                "teamtalk_server_users_peak",
+                "Peak number of concurrent users.",
+                stats.users_peak as f64,
+            ),
+            (
+                "teamtalk_server_uptime_seconds",
+                "Server uptime.",
+                stats.uptime_ms as f64 / 1000.0,
+            ),
+        ] {
+            out.push(Series {
+                name,
+                help,
+                kind: Kind::Gauge,
+                labels: base.clone(),
+                value,
+            });
+        }
+        entry.server_counters = counters.current;
+        entry.server = out;
+    }
+
+    /// Removes all series for a client.
+    pub fn remove_client(&mut self, id: ClientId) {
+        self.clients.remove(&id);
+    }
+
+    /// Renders all series in the OpenMetrics text format.
+    pub fn render(&self) -> String {
+        let mut families: Vec<(&'static str, &'static str, Kind, Vec<String>)> = Vec::new();
+        let all = self
+            .clients
+            .values()
+            .flat_map(|(_, m)| m.client.iter().chain(&m.users).chain(&m.server));
+        for series in all {
+            let line = render_sample(series);
+            match families.iter_mut().find(|f| f.0 == series.name) {
+                Some(family) => family.3.push(line),
+                None => families.push((series.name, series.help, series.kind, vec![line])),
+            }
+        }
+        let mut out = String::new();
+        for (name, help, kind, lines) in families {
+            let kind = match kind {
+                Kind::Counter => "counter",
+                Kind::Gauge => "gauge",
+            };
+            out.push_str(&format!("# TYPE {name} {kind}\n# HELP {name} {help}\n"));
+            for line in lines {
+                out.push_str(&line);
+            }
+        }
+        out.push_str("# EOF\n");
+        out
+    }
+
+    /// Renders and stores the text in the shared handle.
+    pub fn publish(&self) {
+        self.handle.set(self.render());
+    }
+
+    fn entry(&mut self, id: ClientId, label: Option<String>) -> &mut ClientMetrics {
+        let entry = self.clients.entry(id).or_default();
+        entry.0 = label;
+        &mut entry.1
+    }
+}
+
+fn base_labels(id: ClientId, label: Option<&str>) -> Vec<(&'static str, String)> {
+    vec![
+        ("client_id", id.0.to_string()),
+        ("client_label", label.unwrap_or_default().to_string()),
+    ]
+}
+
+fn with(
+    base: &[(&'static str, String)],
+    extra: &[(&'static str, &str)],
+) -> Vec<(&'static str, String)> {
+    let mut labels = base.to_vec();
+    labels.extend(extra.iter().map(|(k, v)| (*k, v.to_string())));
+    labels
+}
+
+fn render_sample(series: &Series) -> String {
+    let labels: Vec<String> = series
+        .labels
+        .iter()
+        .map(|(k, v)| format!("{k}=\"{}\"", escape_label(v)))
+        .collect();
+    let suffix = if series.kind == Kind::Counter {
+        "_total"
+    } else {
+        ""
+    };
+    format!(
+        "{}{}{{{}}} {}\n",
+        series.name,
+        suffix,
+        labels.join(","),
+        series.value
+    )
+}
+
+/// Minimal HTTP endpoint serving the latest metrics text.
+pub struct MetricsServer {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+
+impl MetricsServer {
+    /// Binds to the address and serves `handle` on every GET request.
+    pub fn start(addr: impl ToSocketAddrs, metrics: MetricsHandle) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let metrics = metrics.clone();
+                        thread::spawn(move || {
+                            let _ = serve(stream, &metrics);
+                        });
+                    }
+                    Err(_) => thread::sleep(ACCEPT_POLL),
+                }
+            }
+        });
+        Ok(Self {
+            addr,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    /// Returns the bound address.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Stops the accept thread; connections already accepted finish on their own.
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn serve(stream: TcpStream, metrics: &MetricsHandle) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+    let mut stream = stream;
+    let (status, body) = if request.starts_with("GET ") {
+        ("200 OK", metrics.get())
+    } else {
+        ("405 Method Not Allowed", String::new())
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/openmetrics-text; version=1.0.0; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
@@ -1,9 +1,11 @@
 //! Mock event source for tests and offline simulations.
-use crate::client::{Client, Message};
+use crate::client::audio::BlockOwner;
+use crate::client::{AudioBlockGuard, Client, Message};
 use crate::dispatch::EventSource;
 use crate::events::Event;
-use crate::types::{ChannelId, UserId, UserState, UserStatus};
+use crate::types::{ChannelId, StreamTypes, UserId, UserState, UserStatus};
 use crate::utils::ToTT;
+use std::cell::Cell;
 use std::collections::VecDeque;
 use teamtalk_sys as ffi;
 
@@ -102,6 +104,79 @@ impl MockMessage {
     }
 }
 
+/// Owned audio block handing out `AudioBlockGuard`s and counting their releases.
+pub struct MockAudioBlock {
+    raw: ffi::AudioBlock,
+    samples: Vec<i16>,
+    null: bool,
+    released: Cell<usize>,
+}
+
+impl MockAudioBlock {
+    /// Creates a block holding interleaved samples.
+    pub fn new(sample_rate: i32, channels: i32, samples: Vec<i16>) -> Self {
+        let mut raw = ffi::AudioBlock {
+            nSampleRate: sample_rate,
+            nChannels: channels,
+            ..Default::default()
+        };
+        raw.nSamples = match channels {
+            1.. => (samples.len() / channels as usize) as i32,
+            _ => 0,
+        };
+        Self {
+            raw,
+            samples,
+            null: false,
+            released: Cell::new(0),
+        }
+    }
+
+    /// Creates a block standing in for an SDK call that returned no block.
+    pub fn null() -> Self {
+        Self {
+            null: true,
+            ..Self::new(0, 0, Vec::new())
+        }
+    }
+
+    /// Sets the stream id.
+    pub fn stream_id(mut self, stream_id: i32) -> Self {
+        self.raw.nStreamID = stream_id;
+        self
+    }
+
+    /// Sets the index of the first sample.
+    pub fn sample_index(mut self, index: u32) -> Self {
+        self.raw.uSampleIndex = index;
+        self
+    }
+
+    /// Sets the stream types mixed into the block.
+    pub fn stream_types(mut self, types: StreamTypes) -> Self {
+        self.raw.uStreamTypes = types.raw();
+        self
+    }
+
+    /// Returns a guard over the block, or `None` for a null block.
+    pub fn guard(&mut self) -> Option<AudioBlockGuard<'_>> {
+        if self.null {
+            return AudioBlockGuard::new(BlockOwner::Mock(&self.released), std::ptr::null_mut());
+        }
+        self.raw.lpRawAudio = if self.samples.is_empty() {
+            std::ptr::null_mut()
+        } else {
+            self.samples.as_mut_ptr().cast()
+        };
+        AudioBlockGuard::new(BlockOwner::Mock(&self.released), &mut self.raw)
+    }
+
+    /// Returns how many guards have released the block.
+    pub fn released(&self) -> usize {
+        self.released.get()
+    }
+}
+
 /// Builder for mock user messages.
 pub struct MockUserBuilder {
     user: ffi::User,
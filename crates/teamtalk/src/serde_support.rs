@@ -0,0 +1,186 @@
+//! Stable serde representations for raw TeamTalk enums and structs.
+//!
+//! Each module can be used with `#[serde(with = "...")]`.
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use teamtalk_sys as ffi;
+
+macro_rules! ffi_enum {
+    ($(#[$doc:meta])* $module:ident, $ty:ident, { $($variant:ident => $name:literal),* $(,)? }) => {
+        $(#[$doc])*
+        pub mod $module {
+            use super::*;
+
+            /// Names accepted by `from_name`.
+            pub const NAMES: &[&str] = &[$($name),*];
+
+            /// Returns the stable name for a value.
+            pub fn name(value: ffi::$ty) -> &'static str {
+                #[allow(unreachable_patterns)]
+                match value {
+                    $(ffi::$ty::$variant => $name,)*
+                    _ => "unknown",
+                }
+            }
+
+            /// Parses a stable name into a value.
+            pub fn from_name(name: &str) -> Option<ffi::$ty> {
+                match name {
+                    $($name => Some(ffi::$ty::$variant),)*
+                    _ => None,
+                }
+            }
+
+            /// Serializes the value as its stable name.
+            pub fn serialize<S: Serializer>(value: &ffi::$ty, s: S) -> Result<S::Ok, S::Error> {
+                s.serialize_str(name(*value))
+            }
+
+            /// Deserializes a value from its stable name.
+            pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<ffi::$ty, D::Error> {
+                let value = String::deserialize(d)?;
+                from_name(&value).ok_or_else(|| D::Error::unknown_variant(&value, NAMES))
+            }
+        }
+    };
+}
+
+ffi_enum!(
+    /// `TextMsgType` as `"user"`, `"channel"`, `"broadcast"`, `"custom"` or `"none"`.
+    text_msg_type, TextMsgType, {
+    MSGTYPE_NONE => "none",
+    MSGTYPE_USER => "user",
+    MSGTYPE_CHANNEL => "channel",
+    MSGTYPE_BROADCAST => "broadcast",
+    MSGTYPE_CUSTOM => "custom",
+});
+
+ffi_enum!(
+    /// `SoundSystem` as a lowercase backend name.
+    sound_system, SoundSystem, {
+    SOUNDSYSTEM_NONE => "none",
+    SOUNDSYSTEM_WINMM => "winmm",
+    SOUNDSYSTEM_DSOUND => "dsound",
+    SOUNDSYSTEM_ALSA => "alsa",
+    SOUNDSYSTEM_COREAUDIO => "coreaudio",
+    SOUNDSYSTEM_WASAPI => "wasapi",
+    SOUNDSYSTEM_OPENSLES_ANDROID => "opensles_android",
+    SOUNDSYSTEM_AUDIOUNIT => "audiounit",
+    SOUNDSYSTEM_PULSEAUDIO => "pulseaudio",
+});
+
+ffi_enum!(
+    /// `Codec` as `"none"`, `"speex"`, `"speex_vbr"`, `"opus"` or `"webm_vp8"`.
+    codec, Codec, {
+    NO_CODEC => "none",
+    SPEEX_CODEC => "speex",
+    SPEEX_VBR_CODEC => "speex_vbr",
+    OPUS_CODEC => "opus",
+    WEBM_VP8_CODEC => "webm_vp8",
+});
+
+ffi_enum!(
+    /// `AudioFileFormat` as a lowercase format name.
+    audio_file_format, AudioFileFormat, {
+    AFF_NONE => "none",
+    AFF_CHANNELCODEC_FORMAT => "channel_codec",
+    AFF_WAVE_FORMAT => "wave",
+    AFF_MP3_16KBIT_FORMAT => "mp3_16kbit",
+    AFF_MP3_32KBIT_FORMAT => "mp3_32kbit",
+    AFF_MP3_64KBIT_FORMAT => "mp3_64kbit",
+    AFF_MP3_128KBIT_FORMAT => "mp3_128kbit",
+    AFF_MP3_256KBIT_FORMAT => "mp3_256kbit",
+    AFF_MP3_320KBIT_FORMAT => "mp3_320kbit",
+});
+
+ffi_enum!(
+    /// `MediaFileStatus` as a lowercase status name.
+    media_file_status, MediaFileStatus, {
+    MFS_CLOSED => "closed",
+    MFS_ERROR => "error",
+    MFS_STARTED => "started",
+    MFS_FINISHED => "finished",
+    MFS_ABORTED => "aborted",
+    MFS_PAUSED => "paused",
+    MFS_PLAYING => "playing",
+});
+
+ffi_enum!(
+    /// `FourCC` as `"none"`, `"i420"`, `"yuy2"` or `"rgb32"`.
+    fourcc, FourCC, {
+    FOURCC_NONE => "none",
+    FOURCC_I420 => "i420",
+    FOURCC_YUY2 => "yuy2",
+    FOURCC_RGB32 => "rgb32",
+});
+
+/// Raw `AudioFormat` through `crate::types::AudioFormat`.
+pub mod audio_format {
+    use super::*;
+    use crate::types::AudioFormat;
+
+    /// Serializes the raw struct via its typed wrapper.
+    pub fn serialize<S: Serializer>(value: &ffi::AudioFormat, s: S) -> Result<S::Ok, S::Error> {
+        AudioFormat::from(*value).serialize(s)
+    }
+
+    /// Deserializes the raw struct via its typed wrapper.
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<ffi::AudioFormat, D::Error> {
+        AudioFormat::deserialize(d).map(|f| f.to_ffi())
+    }
+}
+
+/// Raw `VideoFormat` through `crate::types::VideoFormat`.
+pub mod video_format {
+    use super::*;
+    use crate::types::VideoFormat;
+
+    /// Serializes the raw struct via its typed wrapper.
+    pub fn serialize<S: Serializer>(value: &ffi::VideoFormat, s: S) -> Result<S::Ok, S::Error> {
+        VideoFormat::from(*value).serialize(s)
+    }
+
+    /// Deserializes the raw struct via its typed wrapper.
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<ffi::VideoFormat, D::Error> {
+        VideoFormat::deserialize(d).map(|f| f.to_ffi())
+    }
+}
+
+/// `SystemTime` as Unix milliseconds.
+pub mod unix_millis {
+    use super::*;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    /// Serializes the time as milliseconds since the Unix epoch.
+    pub fn serialize<S: Serializer>(value: &SystemTime, s: S) -> Result<S::Ok, S::Error> {
+        (value
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64)
+            .serialize(s)
+    }
+
+    /// Deserializes milliseconds since the Unix epoch.
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<SystemTime, D::Error> {
+        Ok(UNIX_EPOCH + Duration::from_millis(u64::deserialize(d)?))
+    }
+}
+
+/// `Option<SystemTime>` as Unix milliseconds or `null`.
+pub mod unix_millis_opt {
+    use super::*;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    /// Serializes the time as milliseconds since the Unix epoch.
+    pub fn serialize<S: Serializer>(value: &Option<SystemTime>, s: S) -> Result<S::Ok, S::Error> {
+        value
+            .and_then(|at| at.duration_since(UNIX_EPOCH).ok())
+            .map(|at| at.as_millis() as u64)
+            .serialize(s)
+    }
+
+    /// Deserializes milliseconds since the Unix epoch.
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<SystemTime>, D::Error> {
+        Ok(Option::<u64>::deserialize(d)?.map(|ms| UNIX_EPOCH + Duration::from_millis(ms)))
+    }
+}
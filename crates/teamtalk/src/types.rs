@@ -1,20 +1,43 @@
 //! Core data structures and constants used by the SDK.
 use teamtalk_sys as ffi;
 
+mod channel_path;
+mod flags;
+
+pub use channel_path::ChannelPath;
+pub use flags::{
+    BanTypes, ChannelTypes, ServerLogEvents, StreamTypes, SubscriptionFlags, UserRights, UserTypes,
+};
+
+/// Subscription mask for user streams.
+pub type Subscriptions = SubscriptionFlags;
+/// Channel type bitmask.
+pub type ChannelType = ChannelTypes;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 /// Strongly typed user id.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct UserId(pub i32);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 /// Strongly typed channel id.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct ChannelId(pub i32);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 /// Strongly typed remote file id.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct FileId(pub i32);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 /// Strongly typed transfer id.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct TransferId(pub i32);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 /// Strongly typed client id.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct ClientId(pub u64);
 
 /// Reserved local user id.
@@ -49,6 +72,14 @@ pub const OPUS_MAX_FRAMESIZE: i32 = 60;
 /// Maximum WebRTC gain value.
 pub const WEBRTC_GAIN_MAX: f32 = 49.9;
 
+/// Default TeamTalk preprocessor gain level.
+pub const SOUND_GAIN_DEFAULT: i32 = ffi::SoundLevel::SOUND_GAIN_DEFAULT as i32;
+/// Maximum TeamTalk preprocessor gain level.
+pub const SOUND_GAIN_MAX: i32 = ffi::SoundLevel::SOUND_GAIN_MAX as i32;
+
+/// Media playback offset that keeps the current position.
+pub const MEDIA_OFFSET_IGNORE: u32 = ffi::TT_MEDIAPLAYBACK_OFFSET_IGNORE as u32;
+
 /// Desktop input: left mouse button.
 pub const MOUSE_LEFT: u32 = 0x1000;
 /// Desktop input: right mouse button.
@@ -59,92 +90,13 @@ pub const MOUSE_MIDDLE: u32 = 0x1002;
 /// TeamTalk fixed string length.
 pub const TT_STRLEN: usize = 512;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-/// Subscription mask for user streams.
-pub struct Subscriptions(u32);
-
-impl Subscriptions {
-    /// No subscriptions.
-    pub const NONE: u32 = 0x00000000;
-    /// User messages subscription flag.
-    pub const USER_MSG: u32 = 0x00000001;
-    /// Channel messages subscription flag.
-    pub const CHANNEL_MSG: u32 = 0x00000002;
-    /// Broadcast messages subscription flag.
-    pub const BROADCAST_MSG: u32 = 0x00000004;
-    /// Custom messages subscription flag.
-    pub const CUSTOM_MSG: u32 = 0x00000008;
-    /// Voice subscription flag.
-    pub const VOICE: u32 = 0x00000010;
-    /// Video capture subscription flag.
-    pub const VIDEOCAPTURE: u32 = 0x00000020;
-    /// Desktop subscription flag.
-    pub const DESKTOP: u32 = 0x00000040;
-    /// Desktop input subscription flag.
-    pub const DESKTOPINPUT: u32 = 0x00000080;
-    /// Media file subscription flag.
-    pub const MEDIAFILE: u32 = 0x00000100;
-    /// Intercept user messages flag.
-    pub const INTERCEPT_USER_MSG: u32 = 0x00010000;
-    /// Intercept channel messages flag.
-    pub const INTERCEPT_CHANNEL_MSG: u32 = 0x00020000;
-    /// Intercept custom messages flag.
-    pub const INTERCEPT_CUSTOM_MSG: u32 = 0x00080000;
-    /// Intercept voice streams flag.
-    pub const INTERCEPT_VOICE: u32 = 0x00100000;
-    /// Intercept video capture streams flag.
-    pub const INTERCEPT_VIDEOCAPTURE: u32 = 0x00200000;
-    /// Intercept desktop streams flag.
-    pub const INTERCEPT_DESKTOP: u32 = 0x00400000;
-    /// Intercept media file streams flag.
-    pub const INTERCEPT_MEDIAFILE: u32 = 0x01000000;
-    /// All subscription flags.
-    pub const ALL: u32 = 0xFFFFFFFF;
-
-    /// Creates an empty subscription mask.
-    pub fn new() -> Self {
-        Self(Self::NONE)
-    }
-    /// Creates a subscription mask with all flags set.
-    pub fn all() -> Self {
-        Self(Self::ALL)
-    }
-    /// Creates a subscription mask with voice and media file streams.
-    pub fn all_audio() -> Self {
-        Self(Self::VOICE | Self::MEDIAFILE)
-    }
-    /// Creates a subscription mask with user, channel, broadcast, and custom messages.
-    pub fn all_text() -> Self {
-        Self(Self::USER_MSG | Self::CHANNEL_MSG | Self::BROADCAST_MSG | Self::CUSTOM_MSG)
-    }
-    /// Creates a subscription mask with desktop control streams.
-    pub fn all_control() -> Self {
-        Self(Self::DESKTOP | Self::DESKTOPINPUT)
-    }
-    /// Creates a subscription mask from raw bits.
-    pub fn from_raw(raw: u32) -> Self {
-        Self(raw)
-    }
-    /// Adds a subscription flag.
-    pub fn add(&mut self, flag: u32) {
-        self.0 |= flag;
-    }
-    /// Removes a subscription flag.
-    pub fn remove(&mut self, flag: u32) {
-        self.0 &= !flag;
-    }
-    /// Returns true if the flag is present.
-    pub fn has(&self, flag: u32) -> bool {
-        (self.0 & flag) != 0
-    }
-    /// Returns the raw bitmask.
-    pub fn raw(&self) -> u32 {
-        self.0
-    }
-}
+/// Transmit-list user id which grants a classroom stream to every user.
+pub const CLASSROOM_FREEFORALL: UserId = UserId(ffi::TT_CLASSROOM_FREEFORALL);
 
 /// Bitmask of user state flags.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct UserState(u32);
 
 impl UserState {
@@ -187,40 +139,9 @@ impl UserState {
     }
 }
 
-/// Channel type bitmask.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub struct ChannelType(u32);
-
-impl ChannelType {
-    /// Default channel type.
-    pub const DEFAULT: u32 = 0x0000;
-    /// Permanent channel flag.
-    pub const PERMANENT: u32 = 0x0001;
-    /// Solo transmit flag.
-    pub const SOLO_TRANSMIT: u32 = 0x0002;
-    /// Classroom flag.
-    pub const CLASSROOM: u32 = 0x0004;
-    /// Operator receive-only flag.
-    pub const OPERATOR_RECVONLY: u32 = 0x0008;
-    /// Disable voice activation flag.
-    pub const NO_VOICEACTIVATION: u32 = 0x0010;
-    /// Disable recording flag.
-    pub const NO_RECORDING: u32 = 0x0020;
-    /// Hidden channel flag.
-    pub const HIDDEN: u32 = 0x0040;
-
-    /// Creates from raw bits.
-    pub fn from_raw(raw: u32) -> Self {
-        Self(raw)
-    }
-    /// Returns the raw bitmask.
-    pub fn raw(&self) -> u32 {
-        self.0
-    }
-}
-
 /// Presence status of a user.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UserPresence {
     #[default]
     Available,
@@ -230,6 +151,7 @@ pub enum UserPresence {
 
 /// Gender metadata for a user profile.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UserGender {
     Male,
     Female,
@@ -239,6 +161,7 @@ pub enum UserGender {
 
 /// User status flags and presence metadata.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UserStatus {
     pub presence: UserPresence,
     pub gender: UserGender,
@@ -302,6 +225,7 @@ impl UserStatus {
 
 /// Speex DSP preprocessing settings.
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpeexDSP {
     pub enable_agc: bool,
     pub gain_level: i32,
@@ -352,6 +276,7 @@ impl SpeexDSP {
 
 /// WebRTC audio preprocessing settings.
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WebRTCConfig {
     pub preamplifier_enable: bool,
     pub preamplifier_gain: f32,
@@ -391,13 +316,68 @@ impl WebRTCConfig {
     }
 }
 
+/// TeamTalk gain and channel muting preprocessor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TeamTalkPreprocessor {
+    pub gain_level: i32,
+    pub mute_left: bool,
+    pub mute_right: bool,
+}
+
+impl Default for TeamTalkPreprocessor {
+    fn default() -> Self {
+        Self {
+            gain_level: SOUND_GAIN_DEFAULT,
+            mute_left: false,
+            mute_right: false,
+        }
+    }
+}
+
+impl TeamTalkPreprocessor {
+    /// Creates a preprocessor applying `gain_level` (1000 is unity gain).
+    pub fn gain(gain_level: i32) -> Self {
+        Self {
+            gain_level: gain_level.clamp(0, SOUND_GAIN_MAX),
+            ..Self::default()
+        }
+    }
+
+    /// Converts to the raw TeamTalk struct.
+    pub fn to_ffi(&self) -> ffi::TTAudioPreprocessor {
+        ffi::TTAudioPreprocessor {
+            nGainLevel: self.gain_level,
+            bMuteLeftSpeaker: self.mute_left as i32,
+            bMuteRightSpeaker: self.mute_right as i32,
+        }
+    }
+}
+
+impl From<ffi::TTAudioPreprocessor> for TeamTalkPreprocessor {
+    fn from(p: ffi::TTAudioPreprocessor) -> Self {
+        Self {
+            gain_level: p.nGainLevel,
+            mute_left: p.bMuteLeftSpeaker != 0,
+            mute_right: p.bMuteRightSpeaker != 0,
+        }
+    }
+}
+
 /// Audio preprocessing configuration.
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "preprocessor", rename_all = "snake_case")
+)]
 pub enum AudioPreprocessor {
     #[default]
     None,
     Speex(SpeexDSP),
     WebRTC(WebRTCConfig),
+    #[cfg_attr(feature = "serde", serde(rename = "teamtalk"))]
+    TeamTalk(TeamTalkPreprocessor),
 }
 
 impl From<ffi::AudioPreprocessor> for AudioPreprocessor {
@@ -409,6 +389,11 @@ impl From<ffi::AudioPreprocessor> for AudioPreprocessor {
             ffi::AudioPreprocessorType::WEBRTC_AUDIOPREPROCESSOR => {
                 Self::WebRTC(WebRTCConfig::from(unsafe { p.__bindgen_anon_1.webrtc }))
             }
+            ffi::AudioPreprocessorType::TEAMTALK_AUDIOPREPROCESSOR => {
+                Self::TeamTalk(TeamTalkPreprocessor::from(unsafe {
+                    p.__bindgen_anon_1.ttpreprocessor
+                }))
+            }
             _ => Self::None,
         }
     }
@@ -428,6 +413,10 @@ impl AudioPreprocessor {
                 raw.nPreprocessor = ffi::AudioPreprocessorType::WEBRTC_AUDIOPREPROCESSOR;
                 raw.__bindgen_anon_1.webrtc = w.to_ffi();
             }
+            Self::TeamTalk(t) => {
+                raw.nPreprocessor = ffi::AudioPreprocessorType::TEAMTALK_AUDIOPREPROCESSOR;
+                raw.__bindgen_anon_1.ttpreprocessor = t.to_ffi();
+            }
         }
         raw
     }
@@ -435,6 +424,7 @@ impl AudioPreprocessor {
 
 /// Jitter control configuration.
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct JitterConfig {
     pub fixed_delay_ms: i32,
     pub use_adaptive: bool,
@@ -467,11 +457,13 @@ impl JitterConfig {
 
 /// Video format configuration.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VideoFormat {
     pub width: i32,
     pub height: i32,
     pub fps_numerator: i32,
     pub fps_denominator: i32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::fourcc"))]
     pub fourcc: ffi::FourCC,
 }
 
@@ -514,6 +506,7 @@ impl VideoFormat {
 
 /// Video codec configuration.
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VideoCodec {
     pub bitrate: i32,
     pub deadline: u32,
@@ -551,6 +544,7 @@ impl VideoCodec {
 
 /// TLS encryption context settings.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EncryptionContext {
     pub cert_file: String,
     pub key_file: String,
@@ -588,6 +582,7 @@ impl EncryptionContext {
 
 /// Keep-alive configuration for client connections.
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClientKeepAlive {
     pub lost_ms: i32,
     pub tcp_interval_ms: i32,
@@ -625,7 +620,8 @@ impl ClientKeepAlive {
 }
 
 /// Abuse prevention configuration.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AbusePrevention {
     pub commands_limit: i32,
     pub commands_interval_ms: i32,
@@ -652,7 +648,12 @@ impl AbusePrevention {
 
 /// Audio format description.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AudioFormat {
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_support::audio_file_format")
+    )]
     pub format: ffi::AudioFileFormat,
     pub sample_rate: i32,
     pub channels: i32,
@@ -714,6 +715,7 @@ impl From<ffi::VideoFrame> for VideoFrame {
 
 /// Audio input progress information.
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AudioInputProgress {
     pub stream_id: i32,
     pub queue_ms: u32,
@@ -732,6 +734,7 @@ impl From<ffi::AudioInputProgress> for AudioInputProgress {
 
 /// SDK error message payload.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ErrorMessage {
     pub code: i32,
     pub message: String,
@@ -748,12 +751,13 @@ impl From<ffi::ClientErrorMsg> for ErrorMessage {
 
 /// User state snapshot.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct User {
     pub id: UserId,
     pub username: String,
     pub nickname: String,
     pub user_data: i32,
-    pub user_type: u32,
+    pub user_type: UserTypes,
     pub ip_address: String,
     pub version: u32,
     pub channel_id: ChannelId,
@@ -784,7 +788,7 @@ impl From<ffi::User> for User {
             username: crate::utils::strings::to_string(&u.szUsername),
             nickname: crate::utils::strings::to_string(&u.szNickname),
             user_data: u.nUserData,
-            user_type: u.uUserType,
+            user_type: UserTypes::from_raw(u.uUserType),
             ip_address: crate::utils::strings::to_string(&u.szIPAddress),
             version: u.uVersion,
             channel_id: ChannelId(u.nChannelID),
@@ -814,7 +818,8 @@ impl From<ffi::User> for User {
 }
 
 /// Speex audio codec configuration.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpeexCodec {
     pub bandmode: i32,
     pub quality: i32,
@@ -846,7 +851,8 @@ impl SpeexCodec {
 }
 
 /// Speex VBR audio codec configuration.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpeexVBRCodec {
     pub bandmode: i32,
     pub quality: i32,
@@ -887,7 +893,8 @@ impl SpeexVBRCodec {
 }
 
 /// Opus audio codec configuration.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpusCodec {
     pub sample_rate: i32,
     pub channels: i32,
@@ -940,7 +947,9 @@ impl OpusCodec {
 }
 
 /// Audio codec selection.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "codec", rename_all = "snake_case"))]
 pub enum AudioCodec {
     #[default]
     None,
@@ -987,10 +996,50 @@ impl AudioCodec {
         }
         raw
     }
+
+    /// Returns the sample rate of encoded audio, or 0 without a codec.
+    pub fn sample_rate(&self) -> i32 {
+        let speex_rate = |bandmode: i32| match bandmode {
+            1 => 16000,
+            2 => 32000,
+            _ => 8000,
+        };
+        match self {
+            Self::None => 0,
+            Self::Speex(c) => speex_rate(c.bandmode),
+            Self::SpeexVBR(c) => speex_rate(c.bandmode),
+            Self::Opus(c) => c.sample_rate,
+        }
+    }
+
+    /// Returns the number of encoded channels, or 0 without a codec.
+    pub fn channels(&self) -> i32 {
+        match self {
+            Self::None => 0,
+            Self::Speex(_) | Self::SpeexVBR(_) => 1,
+            Self::Opus(c) => c.channels,
+        }
+    }
+
+    /// Returns the transmit interval in milliseconds.
+    pub fn tx_interval_msec(&self) -> i32 {
+        match self {
+            Self::None => 0,
+            Self::Speex(c) => c.tx_interval_msec,
+            Self::SpeexVBR(c) => c.tx_interval_msec,
+            Self::Opus(c) => c.tx_interval_msec,
+        }
+    }
+
+    /// Returns the samples per channel sent in one transmit interval.
+    pub fn frame_samples(&self) -> usize {
+        (self.sample_rate().max(0) as i64 * self.tx_interval_msec().max(0) as i64 / 1000) as usize
+    }
 }
 
 /// Audio input configuration.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AudioConfig {
     pub enable_agc: bool,
     pub gain_level: i32,
@@ -1017,6 +1066,7 @@ impl AudioConfig {
 
 /// Channel definition and configuration.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Channel {
     pub id: ChannelId,
     pub parent_id: ChannelId,
@@ -1024,12 +1074,14 @@ pub struct Channel {
     pub topic: String,
     pub channel_type: ChannelType,
     pub has_password: bool,
+    pub password: String,
+    pub op_password: String,
     pub user_data: i32,
     pub disk_quota: i64,
     pub max_users: i32,
     pub audio_codec: AudioCodec,
     pub audio_cfg: AudioConfig,
-    pub transmit_users: Vec<(UserId, u32)>,
+    pub transmit_users: Vec<(UserId, StreamTypes)>,
     pub transmit_users_queue: Vec<UserId>,
     pub queue_delay_ms: i32,
     pub timeout_voice_ms: i32,
@@ -1041,6 +1093,46 @@ impl Channel {
     pub fn builder(name: &str) -> ChannelBuilder {
         ChannelBuilder::new(name)
     }
+
+    /// Returns the streams a user may transmit in a classroom channel.
+    pub fn transmit_streams(&self, user_id: UserId) -> StreamTypes {
+        self.transmit_users
+            .iter()
+            .filter(|(id, _)| *id == user_id || *id == CLASSROOM_FREEFORALL)
+            .fold(StreamTypes::NONE, |acc, (_, streams)| acc | *streams)
+    }
+
+    /// Adds streams to a user's transmit entry; returns false if the list is full.
+    pub fn allow_transmit(&mut self, user_id: UserId, streams: StreamTypes) -> bool {
+        if let Some(entry) = self
+            .transmit_users
+            .iter_mut()
+            .find(|(id, _)| *id == user_id)
+        {
+            entry.1 |= streams;
+            return true;
+        }
+        if self.transmit_users.len() >= ffi::TT_TRANSMITUSERS_MAX as usize {
+            return false;
+        }
+        self.transmit_users.push((user_id, streams));
+        true
+    }
+
+    /// Removes streams from a user's transmit entry; returns true if it changed.
+    pub fn revoke_transmit(&mut self, user_id: UserId, streams: StreamTypes) -> bool {
+        let before = self.transmit_users.clone();
+        for entry in self
+            .transmit_users
+            .iter_mut()
+            .filter(|(id, _)| *id == user_id)
+        {
+            entry.1 -= streams;
+        }
+        self.transmit_users
+            .retain(|(_, streams)| !streams.is_empty());
+        self.transmit_users != before
+    }
 }
 
 /// Builder for channel configuration.
@@ -1059,6 +1151,8 @@ impl ChannelBuilder {
                 topic: String::new(),
                 channel_type: ChannelType::default(),
                 has_password: false,
+                password: String::new(),
+                op_password: String::new(),
                 user_data: 0,
                 disk_quota: 0,
                 max_users: 0,
@@ -1103,6 +1197,61 @@ impl ChannelBuilder {
         self
     }
 
+    /// Sets the channel password.
+    pub fn password(mut self, password: &str) -> Self {
+        self.inner.password = password.to_string();
+        self.inner.has_password = !password.is_empty();
+        self
+    }
+
+    /// Sets the operator password.
+    pub fn op_password(mut self, password: &str) -> Self {
+        self.inner.op_password = password.to_string();
+        self
+    }
+
+    /// Sets the disk quota in bytes.
+    pub fn disk_quota(mut self, bytes: i64) -> Self {
+        self.inner.disk_quota = bytes;
+        self
+    }
+
+    /// Sets the user data value.
+    pub fn user_data(mut self, data: i32) -> Self {
+        self.inner.user_data = data;
+        self
+    }
+
+    /// Sets the audio configuration.
+    pub fn audio_config(mut self, cfg: AudioConfig) -> Self {
+        self.inner.audio_cfg = cfg;
+        self
+    }
+
+    /// Sets the voice transmission timeout in milliseconds.
+    pub fn timeout_voice_ms(mut self, ms: i32) -> Self {
+        self.inner.timeout_voice_ms = ms;
+        self
+    }
+
+    /// Sets the media file transmission timeout in milliseconds.
+    pub fn timeout_media_ms(mut self, ms: i32) -> Self {
+        self.inner.timeout_media_ms = ms;
+        self
+    }
+
+    /// Sets the delay before the next user in the transmit queue may talk.
+    pub fn queue_delay_ms(mut self, ms: i32) -> Self {
+        self.inner.queue_delay_ms = ms;
+        self
+    }
+
+    /// Allows a user to transmit the given streams.
+    pub fn transmit_user(mut self, user_id: UserId, streams: StreamTypes) -> Self {
+        self.inner.transmit_users.push((user_id, streams));
+        self
+    }
+
     /// Builds the channel.
     pub fn build(self) -> Channel {
         self.inner
@@ -1117,7 +1266,10 @@ impl From<ffi::Channel> for Channel {
             if id == 0 {
                 break;
             }
-            transmit_users.push((UserId(id), c.transmitUsers[i][1] as u32));
+            transmit_users.push((
+                UserId(id),
+                StreamTypes::from_raw(c.transmitUsers[i][1] as u32),
+            ));
         }
         let transmit_users_queue = c
             .transmitUsersQueue
@@ -1132,6 +1284,8 @@ impl From<ffi::Channel> for Channel {
             topic: crate::utils::strings::to_string(&c.szTopic),
             channel_type: ChannelType::from_raw(c.uChannelType),
             has_password: c.bPassword != 0,
+            password: crate::utils::strings::to_string(&c.szPassword),
+            op_password: crate::utils::strings::to_string(&c.szOpPassword),
             user_data: c.nUserData,
             disk_quota: c.nDiskQuota,
             max_users: c.nMaxUsers,
@@ -1156,11 +1310,17 @@ impl Channel {
         };
         let n = crate::utils::ToTT::tt(&self.name);
         let t = crate::utils::ToTT::tt(&self.topic);
+        let p = crate::utils::ToTT::tt(&self.password);
+        let op = crate::utils::ToTT::tt(&self.op_password);
         unsafe {
             let n_len = n.len().min(511);
             std::ptr::copy_nonoverlapping(n.as_ptr(), raw.szName.as_mut_ptr(), n_len);
             let t_len = t.len().min(511);
             std::ptr::copy_nonoverlapping(t.as_ptr(), raw.szTopic.as_mut_ptr(), t_len);
+            let p_len = p.len().min(511);
+            std::ptr::copy_nonoverlapping(p.as_ptr(), raw.szPassword.as_mut_ptr(), p_len);
+            let op_len = op.len().min(511);
+            std::ptr::copy_nonoverlapping(op.as_ptr(), raw.szOpPassword.as_mut_ptr(), op_len);
         }
         raw.uChannelType = self.channel_type.raw();
         raw.nUserData = self.user_data;
@@ -1173,7 +1333,7 @@ impl Channel {
         raw.nTimeOutTimerMediaFileMSec = self.timeout_media_ms;
         for (i, (uid, types)) in self.transmit_users.iter().take(128).enumerate() {
             raw.transmitUsers[i][0] = uid.0;
-            raw.transmitUsers[i][1] = *types as i32;
+            raw.transmitUsers[i][1] = types.raw() as i32;
         }
         for (i, id) in self.transmit_users_queue.iter().take(16).enumerate() {
             raw.transmitUsersQueue[i] = id.0;
@@ -1184,7 +1344,9 @@ impl Channel {
 
 /// Text message payload.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextMessage {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::text_msg_type"))]
     pub msg_type: ffi::TextMsgType,
     pub from_id: UserId,
     pub from_username: String,
@@ -1232,9 +1394,12 @@ impl From<&TextMessage> for MessageTarget {
 }
 
 /// Sound device description.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SoundDevice {
     pub id: i32,
     pub name: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::sound_system"))]
     pub system: ffi::SoundSystem,
     pub device_uid: String,
     pub max_input_channels: i32,
@@ -1274,6 +1439,7 @@ impl From<ffi::SoundDevice> for SoundDevice {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// File transfer status.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FileTransferStatus {
     Closed,
     Error,
@@ -1293,6 +1459,7 @@ impl From<ffi::FileTransferStatus> for FileTransferStatus {
 }
 
 /// File transfer information.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FileTransfer {
     pub status: FileTransferStatus,
     pub id: TransferId,
@@ -1332,6 +1499,7 @@ impl From<ffi::FileTransfer> for FileTransfer {
 
 #[derive(Debug, Clone, Default)]
 /// Remote file metadata.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RemoteFile {
     pub channel_id: ChannelId,
     pub id: FileId,
@@ -1356,10 +1524,17 @@ impl From<ffi::RemoteFile> for RemoteFile {
 
 #[derive(Debug, Clone)]
 /// Media file information.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MediaFileInfo {
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_support::media_file_status")
+    )]
     pub status: ffi::MediaFileStatus,
     pub name: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::audio_format"))]
     pub audio_fmt: ffi::AudioFormat,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::video_format"))]
     pub video_fmt: ffi::VideoFormat,
     pub duration_ms: u32,
     pub elapsed_ms: u32,
@@ -1378,7 +1553,32 @@ impl From<ffi::MediaFileInfo> for MediaFileInfo {
     }
 }
 
+impl MediaFileInfo {
+    /// Returns playback progress from 0.0 to 1.0, if the duration is known.
+    pub fn progress(&self) -> Option<f64> {
+        playback_progress(self.elapsed_ms, self.duration_ms)
+    }
+
+    /// Returns true once playback has finished, failed or been stopped.
+    pub fn is_done(&self) -> bool {
+        matches!(
+            self.status,
+            ffi::MediaFileStatus::MFS_FINISHED
+                | ffi::MediaFileStatus::MFS_ERROR
+                | ffi::MediaFileStatus::MFS_ABORTED
+                | ffi::MediaFileStatus::MFS_CLOSED
+        )
+    }
+}
+
+/// Returns `elapsed_ms / duration_ms` capped at 1.0, or `None` for an unknown duration.
+pub(crate) fn playback_progress(elapsed_ms: u32, duration_ms: u32) -> Option<f64> {
+    (duration_ms > 0).then(|| (elapsed_ms as f64 / duration_ms as f64).min(1.0))
+}
+
 /// Server properties snapshot.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ServerProperties {
     pub name: String,
     pub motd: String,
@@ -1399,7 +1599,7 @@ pub struct ServerProperties {
     pub protocol_version: String,
     pub login_delay: i32,
     pub access_token: String,
-    pub log_events: u32,
+    pub log_events: ServerLogEvents,
 }
 
 impl From<ffi::ServerProperties> for ServerProperties {
@@ -1424,7 +1624,7 @@ impl From<ffi::ServerProperties> for ServerProperties {
             protocol_version: crate::utils::strings::to_string(&p.szServerProtocolVersion),
             login_delay: p.nLoginDelayMSec,
             access_token: crate::utils::strings::to_string(&p.szAccessToken),
-            log_events: p.uServerLogEvents,
+            log_events: ServerLogEvents::from_raw(p.uServerLogEvents),
         }
     }
 }
@@ -1454,12 +1654,276 @@ impl ServerProperties {
         raw.nUdpPort = self.udp_port;
         raw.nUserTimeout = self.user_timeout;
         raw.nLoginDelayMSec = self.login_delay;
-        raw.uServerLogEvents = self.log_events;
+        raw.uServerLogEvents = self.log_events.raw();
         raw
     }
 }
 
+/// Selected server property changes applied on top of the current values.
+#[derive(Debug, Clone, Default)]
+pub struct ServerPropertiesPatch {
+    pub name: Option<String>,
+    pub motd_raw: Option<String>,
+    pub max_users: Option<i32>,
+    pub max_login_attempts: Option<i32>,
+    pub max_logins_per_ip: Option<i32>,
+    pub max_voice_tx: Option<i32>,
+    pub max_video_tx: Option<i32>,
+    pub max_media_tx: Option<i32>,
+    pub max_desktop_tx: Option<i32>,
+    pub max_total_tx: Option<i32>,
+    pub auto_save: Option<bool>,
+    pub tcp_port: Option<i32>,
+    pub udp_port: Option<i32>,
+    pub user_timeout: Option<i32>,
+    pub login_delay: Option<i32>,
+    pub log_events: Option<ServerLogEvents>,
+    /// Saves the server configuration after the update succeeds.
+    pub save_config: bool,
+}
+
+impl ServerPropertiesPatch {
+    /// Creates an empty patch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the server name.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Sets the raw message of the day.
+    pub fn motd_raw(mut self, motd: &str) -> Self {
+        self.motd_raw = Some(motd.to_string());
+        self
+    }
+
+    /// Sets the maximum number of users.
+    pub fn max_users(mut self, max_users: i32) -> Self {
+        self.max_users = Some(max_users);
+        self
+    }
+
+    /// Sets the login attempts allowed before a ban.
+    pub fn max_login_attempts(mut self, max_login_attempts: i32) -> Self {
+        self.max_login_attempts = Some(max_login_attempts);
+        self
+    }
+
+    /// Sets the maximum logins per IP address.
+    pub fn max_logins_per_ip(mut self, max_logins_per_ip: i32) -> Self {
+        self.max_logins_per_ip = Some(max_logins_per_ip);
+        self
+    }
+
+    /// Sets the voice bandwidth limit in bytes per second.
+    pub fn max_voice_tx(mut self, max_voice_tx: i32) -> Self {
+        self.max_voice_tx = Some(max_voice_tx);
+        self
+    }
+
+    /// Sets the video capture bandwidth limit in bytes per second.
+    pub fn max_video_tx(mut self, max_video_tx: i32) -> Self {
+        self.max_video_tx = Some(max_video_tx);
+        self
+    }
+
+    /// Sets the media file bandwidth limit in bytes per second.
+    pub fn max_media_tx(mut self, max_media_tx: i32) -> Self {
+        self.max_media_tx = Some(max_media_tx);
+        self
+    }
+
+    /// Sets the desktop bandwidth limit in bytes per second.
+    pub fn max_desktop_tx(mut self, max_desktop_tx: i32) -> Self {
+        self.max_desktop_tx = Some(max_desktop_tx);
+        self
+    }
+
+    /// Sets the total bandwidth limit in bytes per second.
+    pub fn max_total_tx(mut self, max_total_tx: i32) -> Self {
+        self.max_total_tx = Some(max_total_tx);
+        self
+    }
+
+    /// Sets whether the server saves changes automatically.
+    pub fn auto_save(mut self, auto_save: bool) -> Self {
+        self.auto_save = Some(auto_save);
+        self
+    }
+
+    /// Sets the TCP port.
+    pub fn tcp_port(mut self, tcp_port: i32) -> Self {
+        self.tcp_port = Some(tcp_port);
+        self
+    }
+
+    /// Sets the UDP port.
+    pub fn udp_port(mut self, udp_port: i32) -> Self {
+        self.udp_port = Some(udp_port);
+        self
+    }
+
+    /// Sets the user timeout in seconds.
+    pub fn user_timeout(mut self, user_timeout: i32) -> Self {
+        self.user_timeout = Some(user_timeout);
+        self
+    }
+
+    /// Sets the delay between logins in milliseconds.
+    pub fn login_delay(mut self, login_delay: i32) -> Self {
+        self.login_delay = Some(login_delay);
+        self
+    }
+
+    /// Sets the server log events.
+    pub fn log_events(mut self, log_events: ServerLogEvents) -> Self {
+        self.log_events = Some(log_events);
+        self
+    }
+
+    /// Saves the server configuration after the update succeeds.
+    pub fn save_config(mut self, save: bool) -> Self {
+        self.save_config = save;
+        self
+    }
+
+    /// Returns true if the patch changes no property.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.motd_raw.is_none()
+            && self.max_users.is_none()
+            && self.max_login_attempts.is_none()
+            && self.max_logins_per_ip.is_none()
+            && self.max_voice_tx.is_none()
+            && self.max_video_tx.is_none()
+            && self.max_media_tx.is_none()
+            && self.max_desktop_tx.is_none()
+            && self.max_total_tx.is_none()
+            && self.auto_save.is_none()
+            && self.tcp_port.is_none()
+            && self.udp_port.is_none()
+            && self.user_timeout.is_none()
+            && self.login_delay.is_none()
+            && self.log_events.is_none()
+    }
+
+    /// Writes the chosen fields into a properties snapshot.
+    pub fn apply_to(&self, props: &mut ServerProperties) {
+        if let Some(value) = &self.name {
+            props.name = value.clone();
+        }
+        if let Some(value) = &self.motd_raw {
+            props.motd_raw = value.clone();
+        }
+        if let Some(value) = self.max_users {
+            props.max_users = value;
+        }
+        if let Some(value) = self.max_login_attempts {
+            props.max_login_attempts = value;
+        }
+        if let Some(value) = self.max_logins_per_ip {
+            props.max_logins_per_ip = value;
+        }
+        if let Some(value) = self.max_voice_tx {
+            props.max_voice_tx = value;
+        }
+        if let Some(value) = self.max_video_tx {
+            props.max_video_tx = value;
+        }
+        if let Some(value) = self.max_media_tx {
+            props.max_media_tx = value;
+        }
+        if let Some(value) = self.max_desktop_tx {
+            props.max_desktop_tx = value;
+        }
+        if let Some(value) = self.max_total_tx {
+            props.max_total_tx = value;
+        }
+        if let Some(value) = self.auto_save {
+            props.auto_save = value;
+        }
+        if let Some(value) = self.tcp_port {
+            props.tcp_port = value;
+        }
+        if let Some(value) = self.udp_port {
+            props.udp_port = value;
+        }
+        if let Some(value) = self.user_timeout {
+            props.user_timeout = value;
+        }
+        if let Some(value) = self.login_delay {
+            props.login_delay = value;
+        }
+        if let Some(value) = self.log_events {
+            props.log_events = value;
+        }
+    }
+}
+
+/// Single property difference between two server snapshots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyChange {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+impl std::fmt::Display for PropertyChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.old, self.new)
+    }
+}
+
+impl ServerProperties {
+    /// Returns the fields that differ from `other`, excluding the access token.
+    pub fn diff(&self, other: &ServerProperties) -> Vec<PropertyChange> {
+        let mut out = Vec::new();
+        macro_rules! field {
+            ($name:ident) => {
+                field!($name, |v| format!("{v:?}"))
+            };
+            ($name:ident, $show:expr) => {
+                if self.$name != other.$name {
+                    let show = $show;
+                    out.push(PropertyChange {
+                        field: stringify!($name),
+                        old: show(&self.$name),
+                        new: show(&other.$name),
+                    });
+                }
+            };
+        }
+        field!(name);
+        field!(motd_raw);
+        field!(max_users);
+        field!(max_login_attempts);
+        field!(max_logins_per_ip);
+        field!(max_voice_tx);
+        field!(max_video_tx);
+        field!(max_media_tx);
+        field!(max_desktop_tx);
+        field!(max_total_tx);
+        field!(auto_save);
+        field!(tcp_port);
+        field!(udp_port);
+        field!(user_timeout);
+        field!(login_delay);
+        field!(log_events, |v: &ServerLogEvents| format!(
+            "{:#010x}",
+            v.raw()
+        ));
+        field!(version);
+        field!(protocol_version);
+        out
+    }
+}
+
 /// Client statistics snapshot.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClientStatistics {
     pub udp_ping: i32,
     pub tcp_ping: i32,
@@ -1500,6 +1964,8 @@ impl From<ffi::ClientStatistics> for ClientStatistics {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 /// Client flag bitmask.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct ClientFlags(u32);
 
 impl ClientFlags {
@@ -1556,13 +2022,14 @@ impl ClientFlags {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 /// User account definition.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UserAccount {
     pub username: String,
     pub password: String,
-    pub user_type: u32,
-    pub user_rights: u32,
+    pub user_type: UserTypes,
+    pub user_rights: UserRights,
     pub note: String,
     pub init_channel: String,
     pub user_data: i32,
@@ -1590,8 +2057,8 @@ impl UserAccountBuilder {
             inner: UserAccount {
                 username: username.to_string(),
                 password: String::new(),
-                user_type: 0,
-                user_rights: 0,
+                user_type: UserTypes::NONE,
+                user_rights: UserRights::NONE,
                 note: String::new(),
                 init_channel: String::new(),
                 user_data: 0,
@@ -1609,17 +2076,41 @@ impl UserAccountBuilder {
     }
 
     /// Sets the user type.
-    pub fn user_type(mut self, t: u32) -> Self {
+    pub fn user_type(mut self, t: UserTypes) -> Self {
         self.inner.user_type = t;
         self
     }
 
     /// Sets the user rights.
-    pub fn rights(mut self, r: u32) -> Self {
+    pub fn rights(mut self, r: UserRights) -> Self {
         self.inner.user_rights = r;
         self
     }
 
+    /// Sets the account note.
+    pub fn note(mut self, note: &str) -> Self {
+        self.inner.note = note.to_string();
+        self
+    }
+
+    /// Sets the channel path joined after login.
+    pub fn init_channel(mut self, path: &str) -> Self {
+        self.inner.init_channel = path.to_string();
+        self
+    }
+
+    /// Sets the user data value.
+    pub fn user_data(mut self, data: i32) -> Self {
+        self.inner.user_data = data;
+        self
+    }
+
+    /// Sets the channels where the user automatically becomes operator.
+    pub fn auto_operator_channels(mut self, channels: &[ChannelId]) -> Self {
+        self.inner.auto_operator_channels = channels.to_vec();
+        self
+    }
+
     /// Builds the user account.
     pub fn build(self) -> UserAccount {
         self.inner
@@ -1644,8 +2135,8 @@ impl UserAccount {
             let c_len = c.len().min(511);
             std::ptr::copy_nonoverlapping(c.as_ptr(), raw.szInitChannel.as_mut_ptr(), c_len);
         }
-        raw.uUserType = self.user_type;
-        raw.uUserRights = self.user_rights;
+        raw.uUserType = self.user_type.raw();
+        raw.uUserRights = self.user_rights.raw();
         raw.nUserData = self.user_data;
         raw.nAudioCodecBpsLimit = self.audio_codec_bps_limit;
         raw.abusePrevent = self.abuse_prevent.to_ffi();
@@ -1660,9 +2151,9 @@ impl From<ffi::UserAccount> for UserAccount {
     fn from(a: ffi::UserAccount) -> Self {
         Self {
             username: crate::utils::strings::to_string(&a.szUsername),
-            password: String::new(),
-            user_type: a.uUserType,
-            user_rights: a.uUserRights,
+            password: crate::utils::strings::to_string(&a.szPassword),
+            user_type: UserTypes::from_raw(a.uUserType),
+            user_rights: UserRights::from_raw(a.uUserRights),
             note: crate::utils::strings::to_string(&a.szNote),
             init_channel: crate::utils::strings::to_string(&a.szInitChannel),
             user_data: a.nUserData,
@@ -1678,15 +2169,16 @@ impl From<ffi::UserAccount> for UserAccount {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 /// Banned user entry.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BannedUser {
     pub ip: String,
     pub channel_path: String,
     pub nickname: String,
     pub username: String,
     pub ban_time: String,
-    pub ban_types: u32,
+    pub ban_types: BanTypes,
     pub owner: String,
 }
 
@@ -1698,7 +2190,7 @@ impl From<ffi::BannedUser> for BannedUser {
             nickname: crate::utils::strings::to_string(&b.szNickname),
             username: crate::utils::strings::to_string(&b.szUsername),
             ban_time: crate::utils::strings::to_string(&b.szBanTime),
-            ban_types: b.uBanTypes,
+            ban_types: BanTypes::from_raw(b.uBanTypes),
             owner: crate::utils::strings::to_string(&b.szOwner),
         }
     }
@@ -1725,12 +2217,14 @@ impl BannedUser {
             let owner_len = owner.len().min(511);
             std::ptr::copy_nonoverlapping(owner.as_ptr(), raw.szOwner.as_mut_ptr(), owner_len);
         }
-        raw.uBanTypes = self.ban_types;
+        raw.uBanTypes = self.ban_types.raw();
         raw
     }
 }
 
 /// User statistics snapshot.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UserStatistics {
     pub voice_recv: i64,
     pub voice_lost: i64,
@@ -1766,6 +2260,8 @@ impl From<ffi::UserStatistics> for UserStatistics {
 }
 
 /// Server statistics snapshot.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ServerStatistics {
     pub total_tx: i64,
     pub total_rx: i64,
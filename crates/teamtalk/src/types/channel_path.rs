@@ -0,0 +1,205 @@
+//! Normalized channel paths.
+use crate::events::{Error, Result};
+use crate::utils::strings::glob_match;
+
+/// Channel path such as `/Lobby/Music`, stored as unescaped channel names.
+///
+/// Text form separates names with `/`; a literal `/` or `\` inside a name is
+/// written as `\/` or `\\`. Empty segments, surrounding whitespace and
+/// leading/trailing slashes are ignored, so `Lobby//Music/` equals `/Lobby/Music`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub struct ChannelPath {
+    segments: Vec<String>,
+}
+
+impl ChannelPath {
+    /// Returns the root channel path `/`.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /// Parses and normalizes a path.
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(Self {
+            segments: split_segments(text)?,
+        })
+    }
+
+    /// Builds a path from unescaped channel names, kept verbatim; empty names are skipped.
+    pub fn from_names<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            segments: names
+                .into_iter()
+                .map(|name| name.as_ref().to_string())
+                .filter(|name| !name.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Builds a path from the SDK form, e.g. `/Lobby/Music/`, without unescaping.
+    pub fn from_sdk_path(path: &str) -> Self {
+        Self::from_names(path.split('/'))
+    }
+
+    /// Returns the channel names from the root down.
+    pub fn names(&self) -> &[String] {
+        &self.segments
+    }
+
+    /// Returns the last channel name, or `None` for the root.
+    pub fn name(&self) -> Option<&str> {
+        self.segments.last().map(String::as_str)
+    }
+
+    /// Returns true for the root path.
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Returns the number of names below the root.
+    pub fn depth(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns the parent path, or `None` for the root.
+    pub fn parent(&self) -> Option<Self> {
+        let (_, rest) = self.segments.split_last()?;
+        Some(Self {
+            segments: rest.to_vec(),
+        })
+    }
+
+    /// Returns the path of a direct child channel.
+    pub fn child(&self, name: &str) -> Self {
+        let mut path = self.clone();
+        let name = name.trim();
+        if !name.is_empty() {
+            path.segments.push(name.to_string());
+        }
+        path
+    }
+
+    /// Appends a relative path in text form.
+    pub fn join(&self, relative: &str) -> Result<Self> {
+        let mut path = self.clone();
+        path.segments.extend(split_segments(relative)?);
+        Ok(path)
+    }
+
+    /// Returns the ancestors from the root down to the parent.
+    pub fn ancestors(&self) -> impl Iterator<Item = Self> + '_ {
+        (0..self.segments.len()).map(|len| Self {
+            segments: self.segments[..len].to_vec(),
+        })
+    }
+
+    /// Returns true if `self` is `other` or one of its descendants.
+    pub fn starts_with(&self, other: &ChannelPath) -> bool {
+        self.segments.starts_with(&other.segments)
+    }
+
+    /// Returns the path in the form used by the SDK, e.g. `/Lobby/Music/`.
+    pub fn to_sdk_path(&self) -> String {
+        if self.is_root() {
+            return "/".to_string();
+        }
+        format!("/{}/", self.segments.join("/"))
+    }
+
+    /// Matches the path against a glob pattern.
+    ///
+    /// `*` matches any run of characters within a name, `?` a single character
+    /// and a `**` segment any number of names.
+    pub fn matches(&self, pattern: &str) -> bool {
+        let Ok(pattern) = split_segments(pattern) else {
+            return false;
+        };
+        let names: Vec<&str> = self.segments.iter().map(String::as_str).collect();
+        let pattern: Vec<&str> = pattern.iter().map(String::as_str).collect();
+        match_segments(&pattern, &names)
+    }
+}
+
+fn split_segments(text: &str) -> Result<Vec<String>> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(next @ ('/' | '\\')) => current.push(next),
+                Some(next) => {
+                    return Err(Error::Format(format!(
+                        "invalid escape '\\{next}' in channel path"
+                    )));
+                }
+                None => {
+                    return Err(Error::Format("dangling escape in channel path".to_string()));
+                }
+            },
+            '/' => push_segment(&mut segments, &mut current),
+            _ => current.push(c),
+        }
+    }
+    push_segment(&mut segments, &mut current);
+    Ok(segments)
+}
+
+fn push_segment(segments: &mut Vec<String>, current: &mut String) {
+    let name = current.trim();
+    if !name.is_empty() {
+        segments.push(name.to_string());
+    }
+    current.clear();
+}
+
+fn match_segments(pattern: &[&str], names: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => names.is_empty(),
+        Some((&"**", rest)) => (0..=names.len()).any(|skip| match_segments(rest, &names[skip..])),
+        Some((first, rest)) => match names.split_first() {
+            Some((name, names)) => glob_match(first, name) && match_segments(rest, names),
+            None => false,
+        },
+    }
+}
+
+impl std::fmt::Display for ChannelPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_root() {
+            return f.write_str("/");
+        }
+        for name in &self.segments {
+            write!(f, "/{}", name.replace('\\', "\\\\").replace('/', "\\/"))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for ChannelPath {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        Self::parse(text)
+    }
+}
+
+impl TryFrom<String> for ChannelPath {
+    type Error = Error;
+
+    fn try_from(text: String) -> Result<Self> {
+        Self::parse(&text)
+    }
+}
+
+impl From<ChannelPath> for String {
+    fn from(path: ChannelPath) -> String {
+        path.to_string()
+    }
+}
@@ -0,0 +1,429 @@
+//! Typed bitflag sets mirroring the TeamTalk flag enums.
+use teamtalk_sys as ffi;
+
+macro_rules! bitflag_set {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident {
+            $(
+                $(#[$doc:meta])*
+                const $flag:ident = $value:expr;
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[cfg_attr(feature = "serde", serde(transparent))]
+        pub struct $name(u32);
+
+        impl $name {
+            $(
+                $(#[$doc])*
+                pub const $flag: Self = Self($value as u32);
+            )*
+
+            /// Named flags in declaration order.
+            pub const FLAGS: &'static [(&'static str, Self)] = &[$((stringify!($flag), Self::$flag)),*];
+
+            /// Creates an empty set.
+            pub const fn new() -> Self {
+                Self(0)
+            }
+            /// Creates a set from raw bits.
+            pub const fn from_raw(raw: u32) -> Self {
+                Self(raw)
+            }
+            /// Returns the raw bitmask.
+            pub const fn raw(&self) -> u32 {
+                self.0
+            }
+            /// Returns true if no flag is set.
+            pub const fn is_empty(&self) -> bool {
+                self.0 == 0
+            }
+            /// Returns true if every flag in `other` is set.
+            pub const fn contains(&self, other: Self) -> bool {
+                self.0 & other.0 == other.0
+            }
+            /// Returns true if any flag in `other` is set.
+            pub const fn intersects(&self, other: Self) -> bool {
+                self.0 & other.0 != 0
+            }
+            /// Sets the flags in `other`.
+            pub fn insert(&mut self, other: Self) {
+                self.0 |= other.0;
+            }
+            /// Clears the flags in `other`.
+            pub fn remove(&mut self, other: Self) {
+                self.0 &= !other.0;
+            }
+            /// Flips the flags in `other`.
+            pub fn toggle(&mut self, other: Self) {
+                self.0 ^= other.0;
+            }
+            /// Sets or clears the flags in `other`.
+            pub fn set(&mut self, other: Self, value: bool) {
+                if value {
+                    self.insert(other);
+                } else {
+                    self.remove(other);
+                }
+            }
+            /// Returns the names of the single-bit flags that are set.
+            pub fn names(&self) -> Vec<&'static str> {
+                Self::FLAGS
+                    .iter()
+                    .filter(|(_, flag)| flag.0.is_power_of_two() && self.contains(*flag))
+                    .map(|(name, _)| *name)
+                    .collect()
+            }
+        }
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let mut parts: Vec<String> = self.names().iter().map(|n| n.to_string()).collect();
+                let known = Self::FLAGS
+                    .iter()
+                    .filter(|(_, flag)| flag.0.is_power_of_two())
+                    .fold(0, |acc, (_, flag)| acc | flag.0);
+                let unknown = self.0 & !known;
+                if unknown != 0 {
+                    parts.push(format!("{unknown:#x}"));
+                }
+                if parts.is_empty() {
+                    write!(f, "{}(empty)", stringify!($name))
+                } else {
+                    write!(f, "{}({})", stringify!($name), parts.join(" | "))
+                }
+            }
+        }
+
+        impl std::ops::BitOr for $name {
+            type Output = Self;
+            fn bitor(self, rhs: Self) -> Self {
+                Self(self.0 | rhs.0)
+            }
+        }
+
+        impl std::ops::BitOrAssign for $name {
+            fn bitor_assign(&mut self, rhs: Self) {
+                self.0 |= rhs.0;
+            }
+        }
+
+        impl std::ops::BitAnd for $name {
+            type Output = Self;
+            fn bitand(self, rhs: Self) -> Self {
+                Self(self.0 & rhs.0)
+            }
+        }
+
+        impl std::ops::BitAndAssign for $name {
+            fn bitand_assign(&mut self, rhs: Self) {
+                self.0 &= rhs.0;
+            }
+        }
+
+        impl std::ops::Sub for $name {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0 & !rhs.0)
+            }
+        }
+
+        impl std::ops::SubAssign for $name {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 &= !rhs.0;
+            }
+        }
+
+        impl From<u32> for $name {
+            fn from(raw: u32) -> Self {
+                Self(raw)
+            }
+        }
+
+        impl From<$name> for u32 {
+            fn from(flags: $name) -> u32 {
+                flags.0
+            }
+        }
+
+        impl PartialEq<u32> for $name {
+            fn eq(&self, other: &u32) -> bool {
+                self.0 == *other
+            }
+        }
+    };
+}
+
+bitflag_set! {
+    /// Subscription mask for user streams.
+    pub struct SubscriptionFlags {
+        /// No subscriptions.
+        const NONE = ffi::Subscription::SUBSCRIBE_NONE;
+        /// User messages subscription flag.
+        const USER_MSG = ffi::Subscription::SUBSCRIBE_USER_MSG;
+        /// Channel messages subscription flag.
+        const CHANNEL_MSG = ffi::Subscription::SUBSCRIBE_CHANNEL_MSG;
+        /// Broadcast messages subscription flag.
+        const BROADCAST_MSG = ffi::Subscription::SUBSCRIBE_BROADCAST_MSG;
+        /// Custom messages subscription flag.
+        const CUSTOM_MSG = ffi::Subscription::SUBSCRIBE_CUSTOM_MSG;
+        /// Voice subscription flag.
+        const VOICE = ffi::Subscription::SUBSCRIBE_VOICE;
+        /// Video capture subscription flag.
+        const VIDEOCAPTURE = ffi::Subscription::SUBSCRIBE_VIDEOCAPTURE;
+        /// Desktop subscription flag.
+        const DESKTOP = ffi::Subscription::SUBSCRIBE_DESKTOP;
+        /// Desktop input subscription flag.
+        const DESKTOPINPUT = ffi::Subscription::SUBSCRIBE_DESKTOPINPUT;
+        /// Media file subscription flag.
+        const MEDIAFILE = ffi::Subscription::SUBSCRIBE_MEDIAFILE;
+        /// Intercept user messages flag.
+        const INTERCEPT_USER_MSG = ffi::Subscription::SUBSCRIBE_INTERCEPT_USER_MSG;
+        /// Intercept channel messages flag.
+        const INTERCEPT_CHANNEL_MSG = ffi::Subscription::SUBSCRIBE_INTERCEPT_CHANNEL_MSG;
+        /// Intercept custom messages flag.
+        const INTERCEPT_CUSTOM_MSG = ffi::Subscription::SUBSCRIBE_INTERCEPT_CUSTOM_MSG;
+        /// Intercept voice streams flag.
+        const INTERCEPT_VOICE = ffi::Subscription::SUBSCRIBE_INTERCEPT_VOICE;
+        /// Intercept video capture streams flag.
+        const INTERCEPT_VIDEOCAPTURE = ffi::Subscription::SUBSCRIBE_INTERCEPT_VIDEOCAPTURE;
+        /// Intercept desktop streams flag.
+        const INTERCEPT_DESKTOP = ffi::Subscription::SUBSCRIBE_INTERCEPT_DESKTOP;
+        /// Intercept media file streams flag.
+        const INTERCEPT_MEDIAFILE = ffi::Subscription::SUBSCRIBE_INTERCEPT_MEDIAFILE;
+        /// All subscription flags.
+        const ALL = 0xFFFFFFFFu32;
+    }
+}
+
+impl SubscriptionFlags {
+    /// Creates a subscription mask with all flags set.
+    pub fn all() -> Self {
+        Self::ALL
+    }
+    /// Creates a subscription mask with voice and media file streams.
+    pub fn all_audio() -> Self {
+        Self::VOICE | Self::MEDIAFILE
+    }
+    /// Creates a subscription mask with user, channel, broadcast, and custom messages.
+    pub fn all_text() -> Self {
+        Self::USER_MSG | Self::CHANNEL_MSG | Self::BROADCAST_MSG | Self::CUSTOM_MSG
+    }
+    /// Creates a subscription mask with desktop control streams.
+    pub fn all_control() -> Self {
+        Self::DESKTOP | Self::DESKTOPINPUT
+    }
+    /// Adds a subscription flag.
+    pub fn add(&mut self, flag: Self) {
+        self.insert(flag);
+    }
+    /// Returns true if any of the flags is present.
+    pub fn has(&self, flag: Self) -> bool {
+        self.intersects(flag)
+    }
+}
+
+bitflag_set! {
+    /// Channel type bitmask.
+    pub struct ChannelTypes {
+        /// Default channel type.
+        const DEFAULT = ffi::ChannelType::CHANNEL_DEFAULT;
+        /// Permanent channel flag.
+        const PERMANENT = ffi::ChannelType::CHANNEL_PERMANENT;
+        /// Solo transmit flag.
+        const SOLO_TRANSMIT = ffi::ChannelType::CHANNEL_SOLO_TRANSMIT;
+        /// Classroom flag.
+        const CLASSROOM = ffi::ChannelType::CHANNEL_CLASSROOM;
+        /// Operator receive-only flag.
+        const OPERATOR_RECVONLY = ffi::ChannelType::CHANNEL_OPERATOR_RECVONLY;
+        /// Disable voice activation flag.
+        const NO_VOICEACTIVATION = ffi::ChannelType::CHANNEL_NO_VOICEACTIVATION;
+        /// Disable recording flag.
+        const NO_RECORDING = ffi::ChannelType::CHANNEL_NO_RECORDING;
+        /// Hidden channel flag.
+        const HIDDEN = ffi::ChannelType::CHANNEL_HIDDEN;
+    }
+}
+
+bitflag_set! {
+    /// User rights bitmask.
+    pub struct UserRights {
+        /// No rights.
+        const NONE = ffi::UserRight::USERRIGHT_NONE;
+        /// Allow multiple logins with the same account.
+        const MULTI_LOGIN = ffi::UserRight::USERRIGHT_MULTI_LOGIN;
+        /// See users in all channels.
+        const VIEW_ALL_USERS = ffi::UserRight::USERRIGHT_VIEW_ALL_USERS;
+        /// Create temporary channels.
+        const CREATE_TEMPORARY_CHANNEL = ffi::UserRight::USERRIGHT_CREATE_TEMPORARY_CHANNEL;
+        /// Create, update and remove channels.
+        const MODIFY_CHANNELS = ffi::UserRight::USERRIGHT_MODIFY_CHANNELS;
+        /// Send broadcast text messages.
+        const TEXTMESSAGE_BROADCAST = ffi::UserRight::USERRIGHT_TEXTMESSAGE_BROADCAST;
+        /// Kick users from the server.
+        const KICK_USERS = ffi::UserRight::USERRIGHT_KICK_USERS;
+        /// Ban users from the server.
+        const BAN_USERS = ffi::UserRight::USERRIGHT_BAN_USERS;
+        /// Move users between channels.
+        const MOVE_USERS = ffi::UserRight::USERRIGHT_MOVE_USERS;
+        /// Make other users channel operators.
+        const OPERATOR_ENABLE = ffi::UserRight::USERRIGHT_OPERATOR_ENABLE;
+        /// Upload files.
+        const UPLOAD_FILES = ffi::UserRight::USERRIGHT_UPLOAD_FILES;
+        /// Download files.
+        const DOWNLOAD_FILES = ffi::UserRight::USERRIGHT_DOWNLOAD_FILES;
+        /// Update server properties.
+        const UPDATE_SERVERPROPERTIES = ffi::UserRight::USERRIGHT_UPDATE_SERVERPROPERTIES;
+        /// Transmit voice.
+        const TRANSMIT_VOICE = ffi::UserRight::USERRIGHT_TRANSMIT_VOICE;
+        /// Transmit video capture.
+        const TRANSMIT_VIDEOCAPTURE = ffi::UserRight::USERRIGHT_TRANSMIT_VIDEOCAPTURE;
+        /// Transmit desktop sessions.
+        const TRANSMIT_DESKTOP = ffi::UserRight::USERRIGHT_TRANSMIT_DESKTOP;
+        /// Transmit desktop input.
+        const TRANSMIT_DESKTOPINPUT = ffi::UserRight::USERRIGHT_TRANSMIT_DESKTOPINPUT;
+        /// Transmit media file audio.
+        const TRANSMIT_MEDIAFILE_AUDIO = ffi::UserRight::USERRIGHT_TRANSMIT_MEDIAFILE_AUDIO;
+        /// Transmit media file video.
+        const TRANSMIT_MEDIAFILE_VIDEO = ffi::UserRight::USERRIGHT_TRANSMIT_MEDIAFILE_VIDEO;
+        /// Transmit media file audio and video.
+        const TRANSMIT_MEDIAFILE = ffi::UserRight::USERRIGHT_TRANSMIT_MEDIAFILE;
+        /// Nickname cannot be changed.
+        const LOCKED_NICKNAME = ffi::UserRight::USERRIGHT_LOCKED_NICKNAME;
+        /// Status cannot be changed.
+        const LOCKED_STATUS = ffi::UserRight::USERRIGHT_LOCKED_STATUS;
+        /// Record voice in channels that allow it.
+        const RECORD_VOICE = ffi::UserRight::USERRIGHT_RECORD_VOICE;
+        /// See hidden channels.
+        const VIEW_HIDDEN_CHANNELS = ffi::UserRight::USERRIGHT_VIEW_HIDDEN_CHANNELS;
+        /// Send private text messages.
+        const TEXTMESSAGE_USER = ffi::UserRight::USERRIGHT_TEXTMESSAGE_USER;
+        /// Send channel text messages.
+        const TEXTMESSAGE_CHANNEL = ffi::UserRight::USERRIGHT_TEXTMESSAGE_CHANNEL;
+    }
+}
+
+bitflag_set! {
+    /// User type bitmask.
+    pub struct UserTypes {
+        /// No user type.
+        const NONE = ffi::UserType::USERTYPE_NONE;
+        /// Default user.
+        const DEFAULT = ffi::UserType::USERTYPE_DEFAULT;
+        /// Administrator.
+        const ADMIN = ffi::UserType::USERTYPE_ADMIN;
+    }
+}
+
+bitflag_set! {
+    /// Stream type bitmask.
+    pub struct StreamTypes {
+        /// No stream.
+        const NONE = ffi::StreamType::STREAMTYPE_NONE;
+        /// Voice stream.
+        const VOICE = ffi::StreamType::STREAMTYPE_VOICE;
+        /// Video capture stream.
+        const VIDEOCAPTURE = ffi::StreamType::STREAMTYPE_VIDEOCAPTURE;
+        /// Media file audio stream.
+        const MEDIAFILE_AUDIO = ffi::StreamType::STREAMTYPE_MEDIAFILE_AUDIO;
+        /// Media file video stream.
+        const MEDIAFILE_VIDEO = ffi::StreamType::STREAMTYPE_MEDIAFILE_VIDEO;
+        /// Desktop stream.
+        const DESKTOP = ffi::StreamType::STREAMTYPE_DESKTOP;
+        /// Desktop input stream.
+        const DESKTOPINPUT = ffi::StreamType::STREAMTYPE_DESKTOPINPUT;
+        /// Media file audio and video streams.
+        const MEDIAFILE = ffi::StreamType::STREAMTYPE_MEDIAFILE;
+        /// Channel text messages.
+        const CHANNELMSG = ffi::StreamType::STREAMTYPE_CHANNELMSG;
+        /// Local media playback audio.
+        const LOCALMEDIAPLAYBACK_AUDIO = ffi::StreamType::STREAMTYPE_LOCALMEDIAPLAYBACK_AUDIO;
+        /// Streams controlled by classroom channels.
+        const CLASSROOM_ALL = ffi::StreamType::STREAMTYPE_CLASSROOM_ALL;
+    }
+}
+
+bitflag_set! {
+    /// Ban type bitmask.
+    pub struct BanTypes {
+        /// No ban type.
+        const NONE = ffi::BanType::BANTYPE_NONE;
+        /// Ban applies to a channel instead of the server.
+        const CHANNEL = ffi::BanType::BANTYPE_CHANNEL;
+        /// Ban by IP address.
+        const IPADDR = ffi::BanType::BANTYPE_IPADDR;
+        /// Ban by username.
+        const USERNAME = ffi::BanType::BANTYPE_USERNAME;
+    }
+}
+
+bitflag_set! {
+    /// Server log event bitmask.
+    pub struct ServerLogEvents {
+        /// No events are logged.
+        const NONE = ffi::ServerLogEvent::SERVERLOGEVENT_NONE;
+        /// User connected.
+        const USER_CONNECTED = ffi::ServerLogEvent::SERVERLOGEVENT_USER_CONNECTED;
+        /// User disconnected.
+        const USER_DISCONNECTED = ffi::ServerLogEvent::SERVERLOGEVENT_USER_DISCONNECTED;
+        /// User logged in.
+        const USER_LOGGEDIN = ffi::ServerLogEvent::SERVERLOGEVENT_USER_LOGGEDIN;
+        /// User logged out.
+        const USER_LOGGEDOUT = ffi::ServerLogEvent::SERVERLOGEVENT_USER_LOGGEDOUT;
+        /// User login failed.
+        const USER_LOGINFAILED = ffi::ServerLogEvent::SERVERLOGEVENT_USER_LOGINFAILED;
+        /// User connection timed out.
+        const USER_TIMEDOUT = ffi::ServerLogEvent::SERVERLOGEVENT_USER_TIMEDOUT;
+        /// User was kicked.
+        const USER_KICKED = ffi::ServerLogEvent::SERVERLOGEVENT_USER_KICKED;
+        /// User was banned.
+        const USER_BANNED = ffi::ServerLogEvent::SERVERLOGEVENT_USER_BANNED;
+        /// User was unbanned.
+        const USER_UNBANNED = ffi::ServerLogEvent::SERVERLOGEVENT_USER_UNBANNED;
+        /// User status was updated.
+        const USER_UPDATED = ffi::ServerLogEvent::SERVERLOGEVENT_USER_UPDATED;
+        /// User joined a channel.
+        const USER_JOINEDCHANNEL = ffi::ServerLogEvent::SERVERLOGEVENT_USER_JOINEDCHANNEL;
+        /// User left a channel.
+        const USER_LEFTCHANNEL = ffi::ServerLogEvent::SERVERLOGEVENT_USER_LEFTCHANNEL;
+        /// User was moved.
+        const USER_MOVED = ffi::ServerLogEvent::SERVERLOGEVENT_USER_MOVED;
+        /// Private text message.
+        const USER_TEXTMESSAGE_PRIVATE = ffi::ServerLogEvent::SERVERLOGEVENT_USER_TEXTMESSAGE_PRIVATE;
+        /// Custom text message.
+        const USER_TEXTMESSAGE_CUSTOM = ffi::ServerLogEvent::SERVERLOGEVENT_USER_TEXTMESSAGE_CUSTOM;
+        /// Channel text message.
+        const USER_TEXTMESSAGE_CHANNEL = ffi::ServerLogEvent::SERVERLOGEVENT_USER_TEXTMESSAGE_CHANNEL;
+        /// Broadcast text message.
+        const USER_TEXTMESSAGE_BROADCAST = ffi::ServerLogEvent::SERVERLOGEVENT_USER_TEXTMESSAGE_BROADCAST;
+        /// Channel was created.
+        const CHANNEL_CREATED = ffi::ServerLogEvent::SERVERLOGEVENT_CHANNEL_CREATED;
+        /// Channel was updated.
+        const CHANNEL_UPDATED = ffi::ServerLogEvent::SERVERLOGEVENT_CHANNEL_UPDATED;
+        /// Channel was removed.
+        const CHANNEL_REMOVED = ffi::ServerLogEvent::SERVERLOGEVENT_CHANNEL_REMOVED;
+        /// File was uploaded.
+        const FILE_UPLOADED = ffi::ServerLogEvent::SERVERLOGEVENT_FILE_UPLOADED;
+        /// File was downloaded.
+        const FILE_DOWNLOADED = ffi::ServerLogEvent::SERVERLOGEVENT_FILE_DOWNLOADED;
+        /// File was deleted.
+        const FILE_DELETED = ffi::ServerLogEvent::SERVERLOGEVENT_FILE_DELETED;
+        /// Server properties were updated.
+        const SERVER_UPDATED = ffi::ServerLogEvent::SERVERLOGEVENT_SERVER_UPDATED;
+        /// Server configuration was saved.
+        const SERVER_SAVECONFIG = ffi::ServerLogEvent::SERVERLOGEVENT_SERVER_SAVECONFIG;
+        /// All log events.
+        const ALL = 0x01FFFFFFu32;
+    }
+}
+
+impl ServerLogEvents {
+    /// Creates a log event mask with all events set.
+    pub fn all() -> Self {
+        Self::ALL
+    }
+}
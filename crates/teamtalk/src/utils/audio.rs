@@ -0,0 +1,175 @@
+//! DSP helpers for interleaved 16-bit PCM buffers.
+use super::math::{amplitude_to_dbfs, db_to_gain, dbfs_to_amplitude};
+use crate::types::AudioFormat;
+
+fn clamp(value: f32) -> i16 {
+    value.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16
+}
+
+/// Maps one frame to another channel count, averaging when mixing down to mono.
+pub fn remix_frame(frame: &[f32], channels: usize) -> Vec<f32> {
+    let src = frame.len();
+    match (src, channels) {
+        (0, _) => vec![0.0; channels],
+        (a, b) if a == b => frame.to_vec(),
+        (_, 1) => vec![frame.iter().sum::<f32>() / src as f32],
+        _ => (0..channels).map(|c| frame[c % src]).collect(),
+    }
+}
+
+/// Converts interleaved samples between channel counts.
+pub fn remix(samples: &[i16], from_channels: usize, to_channels: usize) -> Vec<i16> {
+    if from_channels == 0 || to_channels == 0 {
+        return Vec::new();
+    }
+    if from_channels == to_channels {
+        return samples.to_vec();
+    }
+    let mut out = Vec::with_capacity(samples.len() / from_channels * to_channels);
+    let mut frame = Vec::with_capacity(from_channels);
+    for chunk in samples.chunks_exact(from_channels) {
+        frame.clear();
+        frame.extend(chunk.iter().map(|s| *s as f32));
+        out.extend(remix_frame(&frame, to_channels).into_iter().map(clamp));
+    }
+    out
+}
+
+/// Resamples interleaved samples with linear interpolation.
+pub fn resample(samples: &[i16], channels: usize, from_rate: u32, to_rate: u32) -> Vec<i16> {
+    if channels == 0 || from_rate == 0 || to_rate == 0 {
+        return Vec::new();
+    }
+    if from_rate == to_rate {
+        return samples.to_vec();
+    }
+    let frames = samples.len() / channels;
+    if frames == 0 {
+        return Vec::new();
+    }
+    let out_frames = (frames as u64 * to_rate as u64).div_ceil(from_rate as u64) as usize;
+    let step = from_rate as f64 / to_rate as f64;
+    let mut out = Vec::with_capacity(out_frames * channels);
+    for i in 0..out_frames {
+        let pos = i as f64 * step;
+        let index = (pos as usize).min(frames - 1);
+        let next = (index + 1).min(frames - 1);
+        let t = (pos - index as f64) as f32;
+        for c in 0..channels {
+            let a = samples[index * channels + c] as f32;
+            let b = samples[next * channels + c] as f32;
+            out.push(clamp(a + (b - a) * t));
+        }
+    }
+    out
+}
+
+/// Converts samples between two audio formats, ignoring the file format field.
+pub fn convert(samples: &[i16], from: &AudioFormat, to: &AudioFormat) -> Vec<i16> {
+    let (Ok(from_channels), Ok(to_channels)) =
+        (usize::try_from(from.channels), usize::try_from(to.channels))
+    else {
+        return Vec::new();
+    };
+    let remixed = remix(samples, from_channels, to_channels);
+    resample(
+        &remixed,
+        to_channels,
+        from.sample_rate.max(0) as u32,
+        to.sample_rate.max(0) as u32,
+    )
+}
+
+/// Multiplies samples by a linear gain, saturating at the 16-bit range.
+pub fn apply_gain(samples: &mut [i16], gain: f32) {
+    for sample in samples {
+        *sample = clamp(*sample as f32 * gain);
+    }
+}
+
+/// Changes the level of samples by `db` decibels.
+pub fn apply_gain_db(samples: &mut [i16], db: f64) {
+    apply_gain(samples, db_to_gain(db) as f32);
+}
+
+/// Returns the largest absolute sample value.
+pub fn peak(samples: &[i16]) -> u16 {
+    samples.iter().map(|s| s.unsigned_abs()).max().unwrap_or(0)
+}
+
+/// Returns the root mean square of the samples.
+pub fn rms(samples: &[i16]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum: f64 = samples.iter().map(|s| (*s as f64) * (*s as f64)).sum();
+    (sum / samples.len() as f64).sqrt()
+}
+
+/// Returns the peak level in dBFS.
+pub fn peak_dbfs(samples: &[i16]) -> f64 {
+    amplitude_to_dbfs(peak(samples) as f64)
+}
+
+/// Returns the RMS level in dBFS.
+pub fn rms_dbfs(samples: &[i16]) -> f64 {
+    amplitude_to_dbfs(rms(samples))
+}
+
+/// Clips samples to `±ceiling`.
+pub fn hard_limit(samples: &mut [i16], ceiling: i16) {
+    let ceiling = ceiling.unsigned_abs().min(i16::MAX as u16) as i16;
+    for sample in samples {
+        *sample = (*sample).clamp(-ceiling, ceiling);
+    }
+}
+
+/// Peak limiter with instant attack and exponential release.
+#[derive(Debug, Clone)]
+pub struct Limiter {
+    ceiling: f32,
+    release: f32,
+    gain: f32,
+}
+
+impl Limiter {
+    /// Creates a limiter holding peaks at `ceiling_dbfs`, recovering over `release_ms`.
+    pub fn new(ceiling_dbfs: f64, release_ms: u32, sample_rate: u32) -> Self {
+        let release_samples = release_ms as f32 * sample_rate as f32 / 1000.0;
+        let release = if release_samples > 0.0 {
+            (-1.0 / release_samples).exp()
+        } else {
+            0.0
+        };
+        Self {
+            ceiling: dbfs_to_amplitude(ceiling_dbfs.min(0.0)) as f32,
+            release,
+            gain: 1.0,
+        }
+    }
+
+    /// Returns the current gain reduction factor.
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Limits interleaved samples in place, applying one gain per frame.
+    pub fn process(&mut self, samples: &mut [i16], channels: usize) {
+        for frame in samples.chunks_mut(channels.max(1)) {
+            let frame_peak = frame.iter().map(|s| s.unsigned_abs()).max().unwrap_or(0) as f32;
+            let target = if frame_peak > self.ceiling {
+                self.ceiling / frame_peak
+            } else {
+                1.0
+            };
+            self.gain = if target < self.gain {
+                target
+            } else {
+                target + (self.gain - target) * self.release
+            };
+            for sample in frame {
+                *sample = clamp(*sample as f32 * self.gain);
+            }
+        }
+    }
+}
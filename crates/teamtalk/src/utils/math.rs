@@ -8,3 +8,23 @@ pub fn ref_gain(percent: f64) -> i32 {
     let gain = 82.832 * (0.0508 * percent).exp() - 50.0;
     gain as i32
 }
+
+/// Converts decibels into a linear amplitude factor.
+pub fn db_to_gain(db: f64) -> f64 {
+    10f64.powf(db / 20.0)
+}
+
+/// Converts a linear amplitude factor into decibels; zero maps to negative infinity.
+pub fn gain_to_db(gain: f64) -> f64 {
+    20.0 * gain.abs().log10()
+}
+
+/// Converts a 16-bit amplitude into dBFS, where `i16::MAX` is 0 dBFS.
+pub fn amplitude_to_dbfs(amplitude: f64) -> f64 {
+    gain_to_db(amplitude / i16::MAX as f64)
+}
+
+/// Converts dBFS into a 16-bit amplitude.
+pub fn dbfs_to_amplitude(dbfs: f64) -> f64 {
+    db_to_gain(dbfs) * i16::MAX as f64
+}
@@ -1,6 +1,9 @@
 //! Utility helpers used across the TeamTalk SDK.
+pub mod audio;
 pub mod backoff;
+pub mod csv;
 pub mod math;
 pub mod strings;
+pub mod wav;
 
 pub use strings::{ToTT, from_tt, to_string};
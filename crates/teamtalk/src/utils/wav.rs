@@ -0,0 +1,306 @@
+//! 16-bit PCM WAV writing and reading.
+use crate::events::{Error, Result};
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const HEADER_LEN: u64 = 44;
+
+/// Format of a 16-bit PCM WAV file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WavSpec {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl WavSpec {
+    /// Creates a format description.
+    pub fn new(sample_rate: u32, channels: u16) -> Self {
+        Self {
+            sample_rate,
+            channels,
+        }
+    }
+
+    /// Returns the duration of `samples` interleaved samples.
+    pub fn duration_of(&self, samples: u64) -> Duration {
+        let frames_per_sec = self.sample_rate as u64 * self.channels.max(1) as u64;
+        if frames_per_sec == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(samples as f64 / frames_per_sec as f64)
+    }
+
+    fn header(&self, data_len: u32) -> [u8; HEADER_LEN as usize] {
+        let block_align = self.channels as u32 * 2;
+        let mut header = [0u8; HEADER_LEN as usize];
+        header[0..4].copy_from_slice(b"RIFF");
+        header[4..8].copy_from_slice(&data_len.saturating_add(36).to_le_bytes());
+        header[8..12].copy_from_slice(b"WAVE");
+        header[12..16].copy_from_slice(b"fmt ");
+        header[16..20].copy_from_slice(&16u32.to_le_bytes());
+        header[20..22].copy_from_slice(&1u16.to_le_bytes());
+        header[22..24].copy_from_slice(&self.channels.to_le_bytes());
+        header[24..28].copy_from_slice(&self.sample_rate.to_le_bytes());
+        header[28..32].copy_from_slice(&(self.sample_rate * block_align).to_le_bytes());
+        header[32..34].copy_from_slice(&(block_align as u16).to_le_bytes());
+        header[34..36].copy_from_slice(&16u16.to_le_bytes());
+        header[36..40].copy_from_slice(b"data");
+        header[40..44].copy_from_slice(&data_len.to_le_bytes());
+        header
+    }
+}
+
+/// Writer for a single WAV file; the header is completed on `finish` or drop.
+pub struct WavWriter {
+    path: PathBuf,
+    spec: WavSpec,
+    file: Option<BufWriter<File>>,
+    samples: u64,
+}
+
+impl WavWriter {
+    /// Creates the file and writes a provisional header.
+    pub fn create(path: impl AsRef<Path>, spec: WavSpec) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = BufWriter::new(File::create(&path)?);
+        file.write_all(&spec.header(0))?;
+        Ok(Self {
+            path,
+            spec,
+            file: Some(file),
+            samples: 0,
+        })
+    }
+
+    /// Returns the file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the file format.
+    pub fn spec(&self) -> WavSpec {
+        self.spec
+    }
+
+    /// Appends interleaved samples.
+    pub fn write_samples(&mut self, samples: &[i16]) -> Result<()> {
+        let file = self.file.as_mut().ok_or_else(|| Error::CommandFailed {
+            code: -1,
+            message: "WAV writer is finished".to_string(),
+        })?;
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        file.write_all(&bytes)?;
+        self.samples += samples.len() as u64;
+        Ok(())
+    }
+
+    /// Returns the number of interleaved samples written.
+    pub fn samples_written(&self) -> u64 {
+        self.samples
+    }
+
+    /// Returns the file size including the header.
+    pub fn len_bytes(&self) -> u64 {
+        HEADER_LEN + self.samples * 2
+    }
+
+    /// Returns the written audio duration.
+    pub fn duration(&self) -> Duration {
+        self.spec.duration_of(self.samples)
+    }
+
+    /// Completes the header and closes the file.
+    pub fn finish(mut self) -> Result<PathBuf> {
+        self.close()?;
+        Ok(std::mem::take(&mut self.path))
+    }
+
+    fn close(&mut self) -> Result<()> {
+        let Some(mut file) = self.file.take() else {
+            return Ok(());
+        };
+        let data_len = u32::try_from(self.samples * 2).unwrap_or(u32::MAX);
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&self.spec.header(data_len))?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for WavWriter {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+/// Limits after which `RotatingWavWriter` starts a new file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rotation {
+    pub max_bytes: Option<u64>,
+    pub max_duration: Option<Duration>,
+}
+
+impl Rotation {
+    /// Creates a policy that never rotates.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rotates once a file reaches `bytes`.
+    pub fn max_bytes(mut self, bytes: u64) -> Self {
+        self.max_bytes = Some(bytes);
+        self
+    }
+
+    /// Rotates once a file holds `duration` of audio.
+    pub fn max_duration(mut self, duration: Duration) -> Self {
+        self.max_duration = Some(duration);
+        self
+    }
+
+    fn samples_left(&self, spec: WavSpec, written: u64) -> Option<u64> {
+        let by_bytes = self
+            .max_bytes
+            .map(|bytes| bytes.saturating_sub(HEADER_LEN) / 2);
+        let by_duration = self.max_duration.map(|duration| {
+            (duration.as_secs_f64() * spec.sample_rate as f64).round() as u64
+                * spec.channels.max(1) as u64
+        });
+        let limit = match (by_bytes, by_duration) {
+            (Some(a), Some(b)) => a.min(b),
+            (a, b) => a.or(b)?,
+        };
+        let frame = spec.channels.max(1) as u64;
+        Some((limit / frame * frame).max(frame).saturating_sub(written))
+    }
+}
+
+/// WAV writer that splits output into numbered files.
+///
+/// Files are named `<prefix>_<n>.wav` in the target directory, starting at 1.
+pub struct RotatingWavWriter {
+    dir: PathBuf,
+    prefix: String,
+    spec: WavSpec,
+    rotation: Rotation,
+    index: u32,
+    current: Option<WavWriter>,
+    finished: Vec<PathBuf>,
+}
+
+impl RotatingWavWriter {
+    /// Creates a writer; the first file is opened on the first write.
+    pub fn new(dir: impl AsRef<Path>, prefix: &str, spec: WavSpec, rotation: Rotation) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            prefix: prefix.to_string(),
+            spec,
+            rotation,
+            index: 0,
+            current: None,
+            finished: Vec::new(),
+        }
+    }
+
+    /// Returns the file format.
+    pub fn spec(&self) -> WavSpec {
+        self.spec
+    }
+
+    /// Returns the path of the file being written.
+    pub fn current_path(&self) -> Option<&Path> {
+        self.current.as_ref().map(|w| w.path())
+    }
+
+    /// Appends interleaved samples, rotating files as limits are reached.
+    pub fn write_samples(&mut self, mut samples: &[i16]) -> Result<()> {
+        while !samples.is_empty() {
+            if self.current.is_none() {
+                self.index += 1;
+                let path = self.dir.join(format!("{}_{}.wav", self.prefix, self.index));
+                self.current = Some(WavWriter::create(path, self.spec)?);
+            }
+            let writer = self.current.as_mut().expect("writer opened above");
+            let take = self
+                .rotation
+                .samples_left(self.spec, writer.samples_written())
+                .map_or(samples.len(), |left| {
+                    left.min(samples.len() as u64) as usize
+                });
+            writer.write_samples(&samples[..take])?;
+            samples = &samples[take..];
+            let written = writer.samples_written();
+            if self.rotation.samples_left(self.spec, written) == Some(0) {
+                self.rotate()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Closes the current file; the next write starts a new one.
+    pub fn rotate(&mut self) -> Result<Option<PathBuf>> {
+        let Some(writer) = self.current.take() else {
+            return Ok(None);
+        };
+        let path = writer.finish()?;
+        self.finished.push(path.clone());
+        Ok(Some(path))
+    }
+
+    /// Returns and clears the paths of files completed since the last call.
+    pub fn take_finished(&mut self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.finished)
+    }
+
+    /// Closes the current file and returns all completed paths not yet taken.
+    pub fn finish(mut self) -> Result<Vec<PathBuf>> {
+        self.rotate()?;
+        Ok(std::mem::take(&mut self.finished))
+    }
+}
+
+/// Reads a 16-bit PCM WAV file.
+pub fn read(path: impl AsRef<Path>) -> Result<(WavSpec, Vec<i16>)> {
+    let bytes = std::fs::read(path)?;
+    let invalid = |what: &str| Error::Format(format!("invalid WAV file: {what}"));
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(invalid("missing RIFF header"));
+    }
+    let u16_at = |pos: usize| u16::from_le_bytes([bytes[pos], bytes[pos + 1]]);
+    let u32_at = |pos: usize| {
+        u32::from_le_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]])
+    };
+    let mut spec = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let len = u32_at(pos + 4) as usize;
+        let body = pos + 8;
+        let end = body.saturating_add(len).min(bytes.len());
+        if id == b"fmt " {
+            if len < 16 || end < body + 16 {
+                return Err(invalid("short fmt chunk"));
+            }
+            if u16_at(body) != 1 || u16_at(body + 14) != 16 {
+                return Err(invalid("not 16-bit PCM"));
+            }
+            spec = Some(WavSpec::new(u32_at(body + 4), u16_at(body + 2)));
+        } else if id == b"data" {
+            let spec = spec.ok_or_else(|| invalid("data before fmt"))?;
+            let samples = bytes[body..end]
+                .chunks_exact(2)
+                .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+                .collect();
+            return Ok((spec, samples));
+        }
+        pos = body + len + (len & 1);
+    }
+    Err(invalid("missing data chunk"))
+}
@@ -0,0 +1,73 @@
+use teamtalk::client::AccountPlan;
+use teamtalk::client::PlanAction;
+use teamtalk::client::accounts::{accounts_from_csv, accounts_to_csv};
+use teamtalk::types::{ChannelId, UserAccount, UserRights, UserTypes};
+
+fn account(name: &str, rights: u32) -> UserAccount {
+    UserAccount::builder(name)
+        .password("secret")
+        .user_type(UserTypes::DEFAULT)
+        .rights(UserRights::from_raw(rights))
+        .note("note, with comma")
+        .init_channel("/Lobby")
+        .auto_operator_channels(&[ChannelId(2), ChannelId(5)])
+        .build()
+}
+
+#[test]
+fn csv_roundtrip() {
+    let accounts = vec![account("alice", 7), account("bob", 0)];
+    let text = accounts_to_csv(&accounts);
+    assert!(text.starts_with("username,password,user_type,user_rights"));
+    assert_eq!(accounts_from_csv(&text).unwrap(), accounts);
+}
+
+#[test]
+fn csv_columns_by_header() {
+    let parsed = accounts_from_csv("user_rights,username\n3,carol\n,dave\n").unwrap();
+    assert_eq!(parsed[0].username, "carol");
+    assert_eq!(parsed[0].user_rights, 3);
+    assert_eq!(parsed[1].user_rights, 0);
+    assert!(accounts_from_csv("note\nhello\n").is_err());
+    assert!(accounts_from_csv("username,user_type\neve,admin\n").is_err());
+}
+
+#[test]
+fn diff_reconciles_accounts() {
+    let mut keep = account("alice", 7);
+    let current = vec![keep.clone(), account("bob", 0), account("old", 0)];
+    keep.password.clear();
+    let mut changed = account("bob", 1);
+    changed.password.clear();
+    let declared = vec![keep, changed, account("new", 0)];
+
+    let plan = AccountPlan::diff(&declared, &current, true);
+    let steps: Vec<(PlanAction, &str)> = plan
+        .steps
+        .iter()
+        .map(|s| (s.action, s.account.username.as_str()))
+        .collect();
+    assert_eq!(
+        steps,
+        vec![
+            (PlanAction::Update, "bob"),
+            (PlanAction::Create, "new"),
+            (PlanAction::Remove, "old"),
+        ]
+    );
+    assert_eq!(plan.steps[0].changes, vec!["user_rights"]);
+    assert_eq!(plan.steps[0].account.password, "secret");
+    assert_eq!(
+        AccountPlan::diff(&declared, &current, false).count(PlanAction::Remove),
+        0
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_roundtrip() {
+    use teamtalk::client::accounts::{accounts_from_json, accounts_to_json};
+    let accounts = vec![account("alice", 7)];
+    let text = accounts_to_json(&accounts).unwrap();
+    assert_eq!(accounts_from_json(&text).unwrap(), accounts);
+}
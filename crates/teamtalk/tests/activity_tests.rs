@@ -0,0 +1,136 @@
+mod common;
+
+use common::temp_dir;
+use std::time::{Duration, SystemTime};
+use teamtalk::client::{ActivityConfig, ActivityTracker, ExportFormat};
+use teamtalk::events::Event;
+use teamtalk::types::{ChannelId, User, UserId, UserPresence, UserState, UserStatus};
+
+fn user(id: i32, channel: i32) -> User {
+    User {
+        id: UserId(id),
+        username: format!("user{id}"),
+        nickname: format!("User {id}"),
+        channel_id: ChannelId(channel),
+        ..Default::default()
+    }
+}
+
+fn talking(id: i32, channel: i32, on: bool) -> User {
+    User {
+        state: UserState::from_raw(if on { UserState::VOICE } else { 0 }),
+        ..user(id, channel)
+    }
+}
+
+fn at(secs: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000 + secs)
+}
+
+#[test]
+fn accumulates_sessions_channels_and_talk_time() {
+    let mut tracker = ActivityTracker::default();
+    tracker.record(Event::UserLoggedIn, &user(1, 0), at(0));
+    tracker.record(Event::UserJoined, &user(1, 2), at(10));
+    tracker.record(Event::UserStateChange, &talking(1, 2, true), at(20));
+    tracker.record(Event::UserStateChange, &talking(1, 2, false), at(50));
+    tracker.record(Event::UserJoined, &user(1, 3), at(100));
+    tracker.record(Event::UserStateChange, &talking(1, 3, true), at(110));
+    tracker.record(Event::UserLoggedOut, &user(1, 3), at(120));
+
+    let activity = tracker.user(UserId(1)).unwrap();
+    assert!(!activity.is_online());
+    assert_eq!(activity.sessions, 1);
+    assert_eq!(activity.total_online(at(500)), Duration::from_secs(120));
+    assert_eq!(
+        activity.time_in_channel(ChannelId(2), at(500)),
+        Duration::from_secs(90)
+    );
+    assert_eq!(
+        activity.time_in_channel(ChannelId(3), at(500)),
+        Duration::from_secs(20)
+    );
+    assert_eq!(
+        activity.talk_time_in_channel(ChannelId(2), at(500)),
+        Duration::from_secs(30)
+    );
+    assert_eq!(activity.total_talk_time(at(500)), Duration::from_secs(40));
+    assert_eq!(tracker.last_seen(UserId(1)), Some(at(120)));
+
+    tracker.record(Event::UserLoggedIn, &user(1, 0), at(600));
+    let activity = tracker.user(UserId(1)).unwrap();
+    assert_eq!(activity.sessions, 2);
+    assert_eq!(activity.total_online(at(630)), Duration::from_secs(150));
+}
+
+#[test]
+fn idle_detection_uses_presence_and_inactivity() {
+    let mut tracker =
+        ActivityTracker::new(ActivityConfig::new().idle_after(Duration::from_secs(60)));
+    tracker.record(Event::UserLoggedIn, &user(1, 0), at(0));
+    tracker.record(Event::UserLoggedIn, &user(2, 0), at(0));
+    assert!(tracker.idle_users(at(30)).is_empty());
+    tracker.record(Event::UserStateChange, &talking(2, 0, true), at(40));
+    assert_eq!(tracker.idle_users(at(90)), vec![UserId(1)]);
+
+    let away = User {
+        status: UserStatus {
+            presence: UserPresence::Away,
+            ..Default::default()
+        },
+        ..user(2, 0)
+    };
+    tracker.record(Event::UserStateChange, &talking(2, 0, false), at(95));
+    tracker.record(Event::UserUpdate, &away, at(96));
+    assert!(tracker.is_idle(UserId(2), at(97)));
+    assert_eq!(
+        tracker.top_talkers(at(100))[0],
+        (UserId(2), Duration::from_secs(55))
+    );
+}
+
+#[test]
+fn csv_export_and_periodic_file() {
+    let dir = temp_dir("activity_export");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("activity.csv");
+    let config = ActivityConfig::new().export(&path, ExportFormat::Csv, Duration::from_secs(60));
+    let mut tracker = ActivityTracker::new(config);
+    tracker.record(Event::UserJoined, &user(4, 2), at(0));
+
+    let csv = tracker.to_csv(at(30));
+    assert!(csv.starts_with("user_id,username,nickname,online,idle"));
+    assert!(csv.contains("4,user4,User 4,true,false,1,30,0,"));
+    assert!(tracker.channels_to_csv(at(30)).contains("4,2,30,0"));
+
+    assert!(tracker.tick(at(30)).unwrap());
+    assert!(!tracker.tick(at(60)).unwrap());
+    assert!(tracker.tick(at(90)).unwrap());
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert!(written.contains("4,user4,User 4,true,false,1,90,0,"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_export_lists_channels() {
+    let mut tracker = ActivityTracker::default();
+    tracker.record(Event::UserJoined, &user(4, 2), at(0));
+    let json: serde_json::Value = serde_json::from_str(&tracker.to_json(at(30)).unwrap()).unwrap();
+    assert_eq!(json[0]["user_id"], 4);
+    assert_eq!(json[0]["channels"][0]["time_secs"], 30);
+}
+
+#[cfg(not(feature = "json"))]
+#[test]
+fn json_export_requires_feature() {
+    let dir = temp_dir("activity_json");
+    let config = ActivityConfig::new().export(
+        dir.join("activity.json"),
+        ExportFormat::Json,
+        Duration::from_secs(60),
+    );
+    let mut tracker = ActivityTracker::new(config);
+    assert!(tracker.export(at(0)).is_err());
+    assert!(!dir.exists());
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "mock")]
+
+use std::time::Duration;
+use teamtalk::mock::MockAudioBlock;
+use teamtalk::types::StreamTypes;
+
+#[test]
+fn null_block_yields_no_guard() {
+    let mut block = MockAudioBlock::null();
+    assert!(block.guard().is_none());
+    assert_eq!(block.released(), 0);
+}
+
+#[test]
+fn guard_exposes_block_fields() {
+    let mut block = MockAudioBlock::new(8000, 2, vec![1, -1, 2, -2, 3, -3, 4, -4])
+        .stream_id(7)
+        .sample_index(160)
+        .stream_types(StreamTypes::VOICE);
+    let guard = block.guard().unwrap();
+    assert_eq!(guard.stream_id(), 7);
+    assert_eq!(guard.sample_rate(), 8000);
+    assert_eq!(guard.channels(), 2);
+    assert_eq!(guard.sample_count(), 4);
+    assert_eq!(guard.sample_index(), 160);
+    assert_eq!(guard.stream_types(), StreamTypes::VOICE);
+    assert_eq!(guard.duration(), Duration::from_micros(500));
+    assert_eq!(guard.samples(), [1, -1, 2, -2, 3, -3, 4, -4]);
+}
+
+#[test]
+fn guard_without_audio_returns_no_samples() {
+    let mut block = MockAudioBlock::new(0, 1, Vec::new());
+    let guard = block.guard().unwrap();
+    assert_eq!(guard.sample_count(), 0);
+    assert!(guard.samples().is_empty());
+    assert_eq!(guard.duration(), Duration::ZERO);
+}
+
+#[test]
+fn guard_releases_block_on_drop() {
+    let mut block = MockAudioBlock::new(48000, 1, vec![0; 480]);
+    {
+        let guard = block.guard().unwrap();
+        assert_eq!(guard.samples().len(), 480);
+    }
+    assert_eq!(block.released(), 1);
+    drop(block.guard());
+    assert_eq!(block.released(), 2);
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use common::temp_dir;
+use std::time::Duration;
+use teamtalk::client::{AudioChunk, AudioStreamConfig, AudioStreams, UserAudioStream};
+use teamtalk::types::{MUXED_USER_ID, UserId};
+use teamtalk::utils::wav::{self, RotatingWavWriter, Rotation, WavSpec, WavWriter};
+
+fn chunk(stream_id: i32, index: u32, samples: &[i16]) -> AudioChunk {
+    AudioChunk {
+        stream_id,
+        sample_rate: 1000,
+        channels: 1,
+        sample_index: index,
+        samples: samples.to_vec(),
+    }
+}
+
+#[test]
+fn stream_fills_gaps_and_trims_overlaps() {
+    let config = AudioStreamConfig::new().max_gap(Duration::from_millis(5));
+    let mut stream = UserAudioStream::new(UserId(3), config);
+    stream.push(&chunk(1, 100, &[1, 2])).unwrap();
+    stream.push(&chunk(1, 104, &[3, 4])).unwrap();
+    stream.push(&chunk(1, 105, &[9, 5])).unwrap();
+    assert_eq!(stream.buffered(), &[1, 2, 0, 0, 3, 4, 5]);
+    assert_eq!(stream.silence_frames(), 2);
+
+    stream.push(&chunk(1, 200, &[6])).unwrap();
+    assert_eq!(stream.skipped_frames(), 93);
+    stream.push(&chunk(2, 0, &[7])).unwrap();
+    assert_eq!(stream.stream_id(), Some(2));
+    assert_eq!(stream.take(), vec![1, 2, 0, 0, 3, 4, 5, 6, 7]);
+    assert_eq!(stream.buffered_duration(), Duration::ZERO);
+}
+
+#[test]
+fn stale_duplicate_keeps_the_expected_index() {
+    let mut stream = UserAudioStream::new(UserId(3), AudioStreamConfig::new());
+    stream.push(&chunk(1, 100, &[1, 2])).unwrap();
+    stream.push(&chunk(1, 102, &[3, 4])).unwrap();
+    stream.push(&chunk(1, 100, &[1, 2])).unwrap();
+    stream.push(&chunk(1, 104, &[5, 6])).unwrap();
+    assert_eq!(stream.buffered(), &[1, 2, 3, 4, 5, 6]);
+    assert_eq!(stream.silence_frames(), 0);
+}
+
+#[test]
+fn stream_buffer_is_bounded() {
+    let config = AudioStreamConfig::new().max_buffer(Duration::from_millis(3));
+    let mut streams = AudioStreams::new(config);
+    streams
+        .push(MUXED_USER_ID, &chunk(1, 0, &[1, 2, 3, 4, 5]))
+        .unwrap();
+    let stream = streams.get(MUXED_USER_ID).unwrap();
+    assert_eq!(stream.buffered(), &[3, 4, 5]);
+    assert_eq!(stream.dropped_frames(), 2);
+}
+
+#[test]
+fn streams_write_rotated_wav_files() {
+    let dir = temp_dir("audio_streams");
+    let config = AudioStreamConfig::new()
+        .wav_dir(&dir)
+        .rotation(Rotation::new().max_duration(Duration::from_millis(3)));
+    let mut streams = AudioStreams::new(config);
+    streams
+        .push(MUXED_USER_ID, &chunk(1, 0, &[1, 2, 3, 4]))
+        .unwrap();
+    streams.push(UserId(5), &chunk(9, 0, &[1])).unwrap();
+    let mut files = streams.finish().unwrap();
+    files.sort();
+    let names: Vec<_> = files
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, ["muxed_1_1.wav", "muxed_1_2.wav", "user5_9_1.wav"]);
+    assert_eq!(wav::read(&files[0]).unwrap().1, vec![1, 2, 3]);
+    assert_eq!(wav::read(&files[1]).unwrap().1, vec![4]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn wav_writer_round_trip_and_size_rotation() {
+    let dir = temp_dir("audio_wav");
+    let spec = WavSpec::new(8000, 2);
+    let mut writer = WavWriter::create(dir.join("a.wav"), spec).unwrap();
+    writer.write_samples(&[1, -1, 2, -2]).unwrap();
+    assert_eq!(writer.len_bytes(), 52);
+    assert_eq!(writer.duration(), Duration::from_micros(250));
+    let path = writer.finish().unwrap();
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), 52);
+    assert_eq!(wav::read(&path).unwrap(), (spec, vec![1, -1, 2, -2]));
+
+    let mut rotating = RotatingWavWriter::new(&dir, "b", spec, Rotation::new().max_bytes(50));
+    rotating.write_samples(&[1, 2, 3, 4, 5]).unwrap();
+    assert_eq!(rotating.take_finished().len(), 2);
+    let rest = rotating.finish().unwrap();
+    assert_eq!(rest.len(), 1);
+    assert_eq!(wav::read(&rest[0]).unwrap().1, vec![5]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::temp_dir;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use teamtalk::client::TempBanScheduler;
+use teamtalk::types::{BanTypes, BannedUser};
+
+fn ban(ip: &str, flags: BanTypes) -> BannedUser {
+    BannedUser {
+        ip: ip.to_string(),
+        channel_path: "/Lobby/".to_string(),
+        nickname: "troll, the".to_string(),
+        username: "troll".to_string(),
+        ban_types: flags,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn ban_types_flags() {
+    let mut types = BanTypes::new();
+    types.insert(BanTypes::CHANNEL | BanTypes::IPADDR);
+    assert!(types.contains(BanTypes::CHANNEL));
+    assert!(!types.contains(BanTypes::USERNAME));
+    types.remove(BanTypes::CHANNEL);
+    assert_eq!(types, BanTypes::IPADDR);
+    assert_eq!(BanTypes::USERNAME.raw(), 4);
+}
+
+#[test]
+fn scheduler_persists_and_expires() {
+    let path = temp_dir("bans_persist");
+    let past = UNIX_EPOCH + Duration::from_secs(1_000);
+    let future = SystemTime::now() + Duration::from_secs(3600);
+    {
+        let mut scheduler = TempBanScheduler::open(&path).unwrap();
+        assert!(scheduler.entries().is_empty());
+        scheduler
+            .schedule_at(ban("10.0.0.1", BanTypes::IPADDR), past)
+            .unwrap();
+        scheduler
+            .schedule_at(
+                ban("10.0.0.2", BanTypes::IPADDR | BanTypes::CHANNEL),
+                future,
+            )
+            .unwrap();
+        scheduler
+            .schedule_at(ban("10.0.0.1", BanTypes::IPADDR), past)
+            .unwrap();
+    }
+    let mut scheduler = TempBanScheduler::open(&path).unwrap();
+    assert_eq!(scheduler.entries().len(), 2);
+    assert_eq!(scheduler.entries()[1].ban.nickname, "troll, the");
+    assert_eq!(scheduler.next_expiry(), Some(past));
+    let due = scheduler.due(SystemTime::now());
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].ban.ip, "10.0.0.1");
+
+    assert!(
+        scheduler
+            .cancel(&ban("10.0.0.1", BanTypes::IPADDR))
+            .unwrap()
+    );
+    assert!(
+        !scheduler
+            .cancel(&ban("10.0.0.9", BanTypes::IPADDR))
+            .unwrap()
+    );
+    assert_eq!(TempBanScheduler::open(&path).unwrap().entries().len(), 1);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn scheduler_rejects_corrupt_file() {
+    let path = temp_dir("bans_corrupt");
+    std::fs::write(&path, "expires_at,ip\nsoon,1.2.3.4\n").unwrap();
+    assert!(TempBanScheduler::open(&path).is_err());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn bans_json_roundtrip() {
+    use teamtalk::client::bans::{bans_from_json, bans_to_json};
+    let bans = vec![ban("10.0.0.1", BanTypes::IPADDR | BanTypes::USERNAME)];
+    let text = bans_to_json(&bans).unwrap();
+    assert!(text.contains("\"ban_types\": 6"));
+    assert_eq!(bans_from_json(&text).unwrap(), bans);
+}
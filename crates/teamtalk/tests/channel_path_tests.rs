@@ -0,0 +1,63 @@
+use teamtalk::types::ChannelPath;
+
+#[test]
+fn parse_normalizes_slashes() {
+    let path = ChannelPath::parse("Lobby//Music/").unwrap();
+    assert_eq!(path.to_string(), "/Lobby/Music");
+    assert_eq!(path.to_sdk_path(), "/Lobby/Music/");
+    assert_eq!(path, "/ Lobby / Music ".parse().unwrap());
+    assert!(ChannelPath::parse("/").unwrap().is_root());
+    assert_eq!(ChannelPath::root().to_string(), "/");
+    assert_eq!(ChannelPath::root().to_sdk_path(), "/");
+}
+
+#[test]
+fn escapes_round_trip() {
+    let path = ChannelPath::parse(r"/AC\/DC/C:\\music").unwrap();
+    assert_eq!(path.names(), ["AC/DC", r"C:\music"]);
+    assert_eq!(path.to_string(), r"/AC\/DC/C:\\music");
+    assert_eq!(ChannelPath::parse(&path.to_string()).unwrap(), path);
+    assert!(ChannelPath::parse(r"/bad\x").is_err());
+    assert!(ChannelPath::parse(r"/bad\").is_err());
+}
+
+#[test]
+fn navigation() {
+    let path = ChannelPath::from_names(["Lobby", "Music", "Jazz"]);
+    assert_eq!(path.name(), Some("Jazz"));
+    assert_eq!(path.depth(), 3);
+    let parent = path.parent().unwrap();
+    assert_eq!(parent.to_string(), "/Lobby/Music");
+    assert_eq!(parent.child("Jazz"), path);
+    assert_eq!(
+        parent.join("Rock/Live").unwrap().to_string(),
+        "/Lobby/Music/Rock/Live"
+    );
+    assert!(path.starts_with(&parent));
+    assert!(!parent.starts_with(&path));
+    let ancestors: Vec<String> = path.ancestors().map(|p| p.to_string()).collect();
+    assert_eq!(ancestors, ["/", "/Lobby", "/Lobby/Music"]);
+    assert_eq!(ChannelPath::root().parent(), None);
+}
+
+#[test]
+fn glob_matching() {
+    let path = ChannelPath::parse("/Lobby/Music/Jazz").unwrap();
+    assert!(path.matches("/Lobby/Music/Jazz/"));
+    assert!(path.matches("/Lobby/*/Jazz"));
+    assert!(path.matches("/Lobby/Mu?ic/J*"));
+    assert!(path.matches("/**/Jazz"));
+    assert!(path.matches("/Lobby/**"));
+    assert!(!path.matches("/Lobby/*"));
+    assert!(!path.matches("/Lobby/Music/Jazz/**/Live"));
+    assert!(ChannelPath::root().matches("/**"));
+}
+
+#[test]
+fn sdk_paths_are_taken_verbatim() {
+    let path = ChannelPath::from_sdk_path(r"/C:\music/ Jazz /");
+    assert_eq!(path.names(), [r"C:\music", " Jazz "]);
+    assert_eq!(path.to_sdk_path(), r"/C:\music/ Jazz /");
+    assert_eq!(path.to_string(), r"/C:\\music/ Jazz ");
+    assert!(ChannelPath::from_sdk_path("/").is_root());
+}
@@ -0,0 +1,128 @@
+use teamtalk::client::{ChannelSpec, ChannelTree, PlanAction};
+use teamtalk::types::{ChannelPath, ChannelType};
+
+fn path(text: &str) -> ChannelPath {
+    text.parse().unwrap()
+}
+
+fn live() -> ChannelTree {
+    ChannelTree::new()
+        .channel(path("/"), ChannelSpec::new().topic("root").max_users(100))
+        .channel(
+            path("/Lobby/"),
+            ChannelSpec::new().topic("hello").max_users(10),
+        )
+        .channel(path("/Old/"), ChannelSpec::new().topic("").max_users(10))
+        .channel(
+            path("/Old/Child/"),
+            ChannelSpec::new().topic("").max_users(10),
+        )
+}
+
+#[test]
+fn paths_are_normalized_and_escaped() {
+    let tree = ChannelTree::new()
+        .channel(path("Lobby//Music/"), ChannelSpec::new().max_users(5))
+        .channel(path("/AC\\/DC"), ChannelSpec::new().max_users(7));
+    assert_eq!(tree.get(&path("/Lobby/Music")).unwrap().max_users, Some(5));
+    assert_eq!(
+        tree.get(&ChannelPath::from_names(["AC/DC"]))
+            .unwrap()
+            .max_users,
+        Some(7)
+    );
+    let plan = tree.diff(&ChannelTree::new());
+    let created: Vec<String> = plan.steps.iter().map(|s| s.path.to_string()).collect();
+    assert_eq!(created, vec!["/AC\\/DC", "/Lobby", "/Lobby/Music"]);
+}
+
+#[test]
+fn diff_creates_updates_and_prunes() {
+    let desired = ChannelTree::new()
+        .prune(true)
+        .channel(path("/Lobby"), ChannelSpec::new().topic("welcome"))
+        .channel(
+            path("/Games/Chess"),
+            ChannelSpec::new().channel_type(ChannelType::PERMANENT),
+        );
+    let plan = desired.diff(&live());
+    let steps: Vec<(PlanAction, String)> = plan
+        .steps
+        .iter()
+        .map(|s| (s.action, s.path.to_string()))
+        .collect();
+    assert_eq!(
+        steps,
+        vec![
+            (PlanAction::Create, "/Games".to_string()),
+            (PlanAction::Create, "/Games/Chess".to_string()),
+            (PlanAction::Update, "/Lobby".to_string()),
+            (PlanAction::Remove, "/Old/Child".to_string()),
+            (PlanAction::Remove, "/Old".to_string()),
+        ]
+    );
+    assert_eq!(plan.steps[1].changes, vec!["channel_type"]);
+    assert_eq!(plan.steps[2].changes, vec!["topic"]);
+    assert_eq!(plan.count(PlanAction::Remove), 2);
+}
+
+#[test]
+fn diff_ignores_unmanaged_settings_and_keeps_extra_channels() {
+    let desired = ChannelTree::new().channel(path("/Lobby"), ChannelSpec::new().max_users(10));
+    assert!(desired.diff(&live()).is_empty());
+    assert!(live().diff(&live()).is_empty());
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn tree_from_toml() {
+    let tree = ChannelTree::from_toml(
+        r#"
+prune = true
+
+[channels."/Lobby"]
+topic = "welcome"
+max_users = 20
+
+[channels."/Lobby/Music".audio_codec]
+codec = "opus"
+sample_rate = 48000
+channels = 2
+application = 2048
+complexity = 10
+fec = true
+dtx = false
+bitrate = 64000
+vbr = true
+vbr_constraint = false
+tx_interval_msec = 20
+frame_size_msec = 20
+"#,
+    )
+    .unwrap();
+    assert!(tree.prune);
+    assert_eq!(tree.get(&path("/Lobby")).unwrap().max_users, Some(20));
+    assert!(
+        tree.get(&path("/Lobby/Music/"))
+            .unwrap()
+            .audio_codec
+            .is_some()
+    );
+    let again = ChannelTree::from_toml(&tree.to_toml().unwrap()).unwrap();
+    assert_eq!(again, tree);
+}
+
+#[cfg(all(feature = "json", feature = "yaml"))]
+#[test]
+fn tree_roundtrips_json_and_yaml() {
+    let tree = live().prune(true);
+    assert_eq!(
+        ChannelTree::from_json(&tree.to_json().unwrap()).unwrap(),
+        tree
+    );
+    assert_eq!(
+        ChannelTree::from_yaml(&tree.to_yaml().unwrap()).unwrap(),
+        tree
+    );
+    assert!(ChannelTree::from_json("{\"channels\": 5}").is_err());
+}
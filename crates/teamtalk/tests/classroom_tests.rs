@@ -0,0 +1,111 @@
+use teamtalk::client::{RaiseHandQueue, TransmitMonitor};
+use teamtalk::events::Event;
+use teamtalk::types::{CLASSROOM_FREEFORALL, Channel, ChannelId, StreamTypes, UserId};
+
+fn classroom() -> Channel {
+    let mut channel = Channel::builder("class").build();
+    channel.id = ChannelId(3);
+    channel
+}
+
+#[test]
+fn channel_transmit_list_edits() {
+    let mut channel = classroom();
+    assert!(channel.allow_transmit(UserId(5), StreamTypes::VOICE));
+    assert!(channel.allow_transmit(UserId(5), StreamTypes::MEDIAFILE_AUDIO));
+    assert_eq!(
+        channel.transmit_users,
+        vec![(UserId(5), StreamTypes::VOICE | StreamTypes::MEDIAFILE_AUDIO)]
+    );
+    assert!(channel.revoke_transmit(UserId(5), StreamTypes::VOICE));
+    assert_eq!(
+        channel.transmit_streams(UserId(5)),
+        StreamTypes::MEDIAFILE_AUDIO
+    );
+    assert!(!channel.revoke_transmit(UserId(9), StreamTypes::VOICE));
+    assert!(channel.revoke_transmit(UserId(5), StreamTypes::MEDIAFILE_AUDIO));
+    assert!(channel.transmit_users.is_empty());
+
+    channel.allow_transmit(CLASSROOM_FREEFORALL, StreamTypes::VOICE);
+    assert_eq!(channel.transmit_streams(UserId(42)), StreamTypes::VOICE);
+}
+
+#[test]
+fn monitor_reports_transmit_changes() {
+    let mut monitor = TransmitMonitor::new();
+    let mut channel = classroom();
+    assert!(monitor.record(&channel).is_empty());
+    assert!(monitor.record(&channel).is_empty());
+
+    channel.allow_transmit(UserId(5), StreamTypes::VOICE);
+    assert_eq!(
+        monitor.record(&channel),
+        vec![Event::TransmitUsersChanged {
+            channel: ChannelId(3)
+        }]
+    );
+
+    channel.transmit_users_queue = vec![UserId(7), UserId(8)];
+    assert_eq!(
+        monitor.record(&channel),
+        vec![Event::TransmitQueueChanged {
+            channel: ChannelId(3)
+        }]
+    );
+    assert_eq!(monitor.queue(ChannelId(3)), [UserId(7), UserId(8)]);
+    assert!(monitor.queue(ChannelId(4)).is_empty());
+}
+
+#[test]
+fn raise_hand_queue_order() {
+    let mut queue = RaiseHandQueue::new(ChannelId(3), StreamTypes::VOICE);
+    assert_eq!(queue.raise(UserId(1)), 0);
+    assert_eq!(queue.raise(UserId(2)), 1);
+    assert_eq!(queue.raise(UserId(1)), 0);
+    assert_eq!(queue.len(), 2);
+    assert!(queue.lower(UserId(1)));
+    assert!(!queue.lower(UserId(1)));
+    assert_eq!(queue.position(UserId(2)), Some(0));
+    assert!(!queue.remove_user(UserId(2)));
+    assert!(queue.is_empty());
+    assert_eq!(queue.speaker(), None);
+}
+
+#[test]
+fn raise_hand_handover_revokes_previous_speaker() {
+    let mut channel = classroom();
+    let mut queue = RaiseHandQueue::new(ChannelId(3), StreamTypes::VOICE);
+    queue.raise(UserId(1));
+    queue.raise(UserId(2));
+    assert_eq!(queue.advance(&mut channel).unwrap(), Some(UserId(1)));
+    assert_eq!(
+        channel.transmit_users,
+        vec![(UserId(1), StreamTypes::VOICE)]
+    );
+
+    assert_eq!(queue.advance(&mut channel).unwrap(), Some(UserId(2)));
+    assert_eq!(queue.speaker(), Some(UserId(2)));
+    assert!(queue.is_empty());
+    assert_eq!(channel.transmit_streams(UserId(1)), StreamTypes::NONE);
+    assert_eq!(
+        channel.transmit_users,
+        vec![(UserId(2), StreamTypes::VOICE)]
+    );
+
+    assert_eq!(queue.advance(&mut channel).unwrap(), None);
+    assert_eq!(queue.speaker(), None);
+    assert!(channel.transmit_users.is_empty());
+}
+
+#[test]
+fn raise_hand_handover_failure_keeps_queue() {
+    let mut channel = classroom();
+    for id in 100..100 + teamtalk::client::ffi::TT_TRANSMITUSERS_MAX {
+        assert!(channel.allow_transmit(UserId(id), StreamTypes::VOICE));
+    }
+    let mut queue = RaiseHandQueue::new(ChannelId(3), StreamTypes::VOICE);
+    queue.raise(UserId(1));
+    assert!(queue.advance(&mut channel).is_err());
+    assert_eq!(queue.pending().collect::<Vec<_>>(), vec![UserId(1)]);
+    assert_eq!(queue.speaker(), None);
+}
@@ -0,0 +1,15 @@
+//! Helpers shared by the integration tests.
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Returns a fresh path under the system temp directory.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    std::env::temp_dir().join(format!(
+        "teamtalk_test_{name}_{}_{nanos}",
+        std::process::id()
+    ))
+}
@@ -0,0 +1,127 @@
+#![cfg(feature = "history")]
+mod common;
+
+use common::temp_dir;
+use std::time::{Duration, SystemTime};
+use teamtalk::client::ffi;
+use teamtalk::history::{ChatHistory, HistoryQuery, MessageKind, Retention, replay_line};
+use teamtalk::types::{ChannelId, ChannelPath, TextMessage, UserId};
+
+fn at(secs: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000 + secs)
+}
+
+fn channel_msg(from: i32, text: &str, more: bool) -> TextMessage {
+    TextMessage {
+        msg_type: ffi::TextMsgType::MSGTYPE_CHANNEL,
+        from_id: UserId(from),
+        from_username: format!("user{from}"),
+        to_id: UserId(0),
+        channel_id: ChannelId(2),
+        text: text.to_string(),
+        more,
+    }
+}
+
+fn lobby() -> Option<ChannelPath> {
+    Some(ChannelPath::from_names(["Lobby"]))
+}
+
+#[test]
+fn joins_fragments_and_persists() {
+    let dir = temp_dir("history_persist");
+    let mut history = ChatHistory::open(&dir, "tt.example.com:10333").unwrap();
+    assert!(
+        history
+            .record(&channel_msg(1, "hello, ", true), lobby().as_ref(), at(0))
+            .unwrap()
+            .is_none()
+    );
+    let entry = history
+        .record(
+            &channel_msg(1, "\"world\"\nbye", false),
+            lobby().as_ref(),
+            at(1),
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.text, "hello, \"world\"\nbye");
+    assert_eq!(entry.kind, MessageKind::Channel);
+    assert!(history.path().ends_with("tt.example.com_10333.csv"));
+
+    let reopened = ChatHistory::open(&dir, "tt.example.com:10333").unwrap();
+    assert_eq!(reopened.entries(), history.entries());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn search_filters_and_prune() {
+    let dir = temp_dir("history_search");
+    let mut history = ChatHistory::open(&dir, "host").unwrap();
+    history
+        .record(
+            &channel_msg(1, "Good morning", false),
+            lobby().as_ref(),
+            at(0),
+        )
+        .unwrap();
+    history
+        .record(&channel_msg(2, "hi there", false), lobby().as_ref(), at(60))
+        .unwrap();
+    let private = TextMessage {
+        msg_type: ffi::TextMsgType::MSGTYPE_USER,
+        to_id: UserId(1),
+        channel_id: ChannelId(0),
+        ..channel_msg(2, "secret morning", false)
+    };
+    history.record(&private, None, at(120)).unwrap();
+
+    let found = history.search(&HistoryQuery::new().text("MORNING"));
+    assert_eq!(found.len(), 2);
+    let found = history.search(&HistoryQuery::new().user(UserId(1)));
+    assert_eq!(found.len(), 2);
+    let found = history.search(
+        &HistoryQuery::new()
+            .channel_path("/Lobby/".parse().unwrap())
+            .since(at(30)),
+    );
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].from_username, "user2");
+    assert_eq!(history.recent(&lobby().unwrap(), 1)[0].text, "hi there");
+    assert_eq!(replay_line(found[0]), "[22:14] user2: hi there");
+
+    history.set_retention(Retention::new().max_age(Duration::from_secs(90)));
+    assert_eq!(history.prune(at(150)).unwrap(), 1);
+    history.set_retention(Retention::new().max_entries(1));
+    assert_eq!(history.prune(at(150)).unwrap(), 1);
+    let reopened = ChatHistory::open(&dir, "host").unwrap();
+    assert_eq!(reopened.entries().len(), 1);
+    assert_eq!(reopened.entries()[0].kind, MessageKind::User);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn open_truncates_torn_last_row() {
+    let dir = temp_dir("history_torn");
+    let mut history = ChatHistory::open(&dir, "host").unwrap();
+    history
+        .record(&channel_msg(1, "kept", false), lobby().as_ref(), at(0))
+        .unwrap();
+    let complete = std::fs::read(history.path()).unwrap();
+    let mut torn = complete.clone();
+    torn.extend_from_slice(b"2,1700000001000,channel,1,user1,0,2,/Lobby,\"line one\nline");
+    std::fs::write(history.path(), &torn).unwrap();
+
+    let mut reopened = ChatHistory::open(&dir, "host").unwrap();
+    assert_eq!(reopened.entries(), history.entries());
+    assert_eq!(std::fs::read(reopened.path()).unwrap(), complete);
+    let entry = reopened
+        .record(&channel_msg(2, "after", false), lobby().as_ref(), at(5))
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.id, 2);
+    let again = ChatHistory::open(&dir, "host").unwrap();
+    assert_eq!(again.entries().len(), 2);
+    assert_eq!(again.entries()[1].channel_path, lobby());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
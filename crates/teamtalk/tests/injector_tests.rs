@@ -0,0 +1,113 @@
+use teamtalk::client::{AudioInjector, InjectorEvent, PcmSource};
+use teamtalk::types::{AudioCodec, AudioInputProgress, OpusCodec, SpeexCodec};
+
+fn progress(stream_id: i32, queue_ms: u32) -> AudioInputProgress {
+    AudioInputProgress {
+        stream_id,
+        queue_ms,
+        elapsed_ms: 0,
+    }
+}
+
+#[test]
+fn codec_frame_sizes() {
+    let speex = AudioCodec::Speex(SpeexCodec {
+        bandmode: 1,
+        quality: 4,
+        tx_interval_msec: 40,
+        stereo_playback: false,
+    });
+    assert_eq!(
+        (speex.sample_rate(), speex.channels(), speex.frame_samples()),
+        (16000, 1, 640)
+    );
+    let opus = AudioCodec::Opus(OpusCodec {
+        sample_rate: 48000,
+        channels: 2,
+        application: 2048,
+        complexity: 10,
+        fec: true,
+        dtx: false,
+        bitrate: 64000,
+        vbr: true,
+        vbr_constraint: false,
+        tx_interval_msec: 20,
+        frame_size_msec: 0,
+    });
+    let injector = AudioInjector::for_codec(&opus).unwrap();
+    assert_eq!(injector.frame_samples(), 960);
+    assert_eq!(injector.channels(), 2);
+    assert!(AudioInjector::for_codec(&AudioCodec::None).is_err());
+}
+
+#[test]
+fn rechunks_pads_and_finishes() {
+    let mut injector = AudioInjector::new(1000, 1, 4).buffer_ms(0);
+    let first = injector.enqueue(PcmSource::from_samples((1..=6).collect(), 1000, 1));
+    let second = injector.enqueue(PcmSource::from_samples(vec![9], 1000, 1));
+
+    let frame = injector.next_frame().unwrap();
+    assert_eq!(frame.samples, vec![1, 2, 3, 4]);
+    assert_eq!(frame.sample_index, 0);
+    let frame = injector.next_frame().unwrap();
+    assert_eq!(frame.samples, vec![5, 6, 0, 0]);
+    assert_eq!(frame.sample_index, 4);
+    let stream_id = frame.stream_id;
+    assert!(injector.next_frame().is_none());
+    assert_eq!(injector.take_events(), vec![InjectorEvent::Started(first)]);
+
+    injector.on_progress(&progress(stream_id, 4));
+    assert_eq!(injector.current(), Some(first));
+    injector.on_progress(&progress(stream_id, 0));
+    assert_eq!(injector.take_events(), vec![InjectorEvent::Finished(first)]);
+
+    let frame = injector.next_frame().unwrap();
+    assert_ne!(frame.stream_id, stream_id);
+    assert_eq!(frame.samples, vec![9, 0, 0, 0]);
+    assert_eq!(injector.take_events(), vec![InjectorEvent::Started(second)]);
+}
+
+#[test]
+fn converts_rate_and_channels() {
+    let mut injector = AudioInjector::new(2000, 2, 4).buffer_ms(0);
+    injector.enqueue(PcmSource::from_samples(vec![0, 100, 200], 1000, 1));
+    let frame = injector.next_frame().unwrap();
+    assert_eq!(frame.samples, vec![0, 0, 50, 50, 100, 100, 150, 150]);
+
+    let mut injector = AudioInjector::new(1000, 1, 2).buffer_ms(0);
+    let bytes: Vec<u8> = [10i16, 30, -10, -30]
+        .iter()
+        .flat_map(|s| s.to_le_bytes())
+        .collect();
+    injector.enqueue(PcmSource::from_reader(std::io::Cursor::new(bytes), 1000, 2));
+    assert_eq!(injector.next_frame().unwrap().samples, vec![20, -20]);
+}
+
+#[test]
+fn pacing_and_cancellation() {
+    let mut injector = AudioInjector::new(1000, 1, 100).buffer_ms(200);
+    let (tx, rx) = std::sync::mpsc::channel();
+    let id = injector.enqueue(PcmSource::from_receiver(rx, 1000, 1));
+    let queued = injector.enqueue(PcmSource::from_samples(vec![1; 100], 1000, 1));
+    tx.send(vec![1; 1000]).unwrap();
+    drop(tx);
+
+    assert!(injector.next_frame().is_some());
+    assert!(injector.next_frame().is_some());
+    assert!(injector.next_frame().is_none());
+    injector.on_progress(&progress(1, 100));
+    assert!(injector.next_frame().is_some());
+
+    assert!(injector.cancel_local(queued));
+    assert!(injector.cancel_local(id));
+    assert!(!injector.cancel_local(id));
+    assert!(injector.is_idle());
+    assert_eq!(
+        injector.take_events(),
+        vec![
+            InjectorEvent::Started(id),
+            InjectorEvent::Cancelled(queued),
+            InjectorEvent::Cancelled(id),
+        ]
+    );
+}
@@ -0,0 +1,81 @@
+use std::time::{Duration, SystemTime};
+use teamtalk::types::{ClientKeepAlive, ClientStatistics};
+use teamtalk::{ClientHealth, HealthStatus, WatchdogConfig};
+
+#[test]
+fn watchdog_ping_failures() {
+    let config = WatchdogConfig::new().max_ping_ms(200);
+    let mut stats = ClientStatistics {
+        tcp_ping: 50,
+        udp_ping: 40,
+        ..Default::default()
+    };
+    assert!(!config.ping_failed(Some(&stats)));
+    stats.udp_ping = 500;
+    assert!(config.ping_failed(Some(&stats)));
+    stats.tcp_ping = -1;
+    stats.udp_ping = -1;
+    assert!(config.ping_failed(Some(&stats)));
+    assert!(config.ping_failed(None));
+}
+
+#[test]
+fn watchdog_classifies_silence_and_pings() {
+    let config = WatchdogConfig::new()
+        .degraded_after(Duration::from_secs(10))
+        .unhealthy_after(Duration::from_secs(30))
+        .unhealthy_failed_pings(2);
+    let now = SystemTime::now();
+    let mut health = ClientHealth {
+        last_event_at: Some(now - Duration::from_secs(1)),
+        ..Default::default()
+    };
+    assert_eq!(config.classify(&health, now), HealthStatus::Healthy);
+    health.failed_pings = 1;
+    assert_eq!(config.classify(&health, now), HealthStatus::Degraded);
+    health.failed_pings = 2;
+    assert_eq!(config.classify(&health, now), HealthStatus::Unhealthy);
+    health.failed_pings = 0;
+    health.last_event_at = Some(now - Duration::from_secs(15));
+    assert_eq!(config.classify(&health, now), HealthStatus::Degraded);
+    health.last_reconnect_at = Some(now);
+    assert_eq!(config.classify(&health, now), HealthStatus::Healthy);
+}
+
+#[test]
+fn silence_counts_from_registration_and_reconnect_resets() {
+    let config = WatchdogConfig::new()
+        .unhealthy_after(Duration::from_secs(30))
+        .unhealthy_failed_pings(1);
+    let start = SystemTime::now();
+    let mut health = ClientHealth::new(start);
+    let later = start + Duration::from_secs(40);
+    assert_eq!(health.silence(later), Duration::from_secs(40));
+    assert_eq!(config.classify(&health, later), HealthStatus::Unhealthy);
+
+    health.status = HealthStatus::Unhealthy;
+    health.failed_pings = 1;
+    health.record_forced_reconnect(later);
+    assert_eq!(health.status, HealthStatus::Unhealthy);
+    assert_eq!(health.failed_pings, 0);
+    assert_eq!(health.forced_reconnects, 1);
+    assert_eq!(health.silence(later), Duration::ZERO);
+    assert_eq!(config.classify(&health, later), HealthStatus::Healthy);
+    health.failed_pings = 1;
+    assert_eq!(config.classify(&health, later), HealthStatus::Unhealthy);
+}
+
+#[test]
+fn watchdog_from_keep_alive() {
+    let keep_alive = ClientKeepAlive {
+        lost_ms: 20_000,
+        tcp_interval_ms: 5_000,
+        udp_interval_ms: 1_000,
+        udp_rtx_ms: 500,
+        udp_connect_rtx_ms: 500,
+        udp_timeout_ms: 10_000,
+    };
+    let config = WatchdogConfig::from_keep_alive(&keep_alive);
+    assert_eq!(config.degraded_after, Duration::from_secs(10));
+    assert_eq!(config.unhealthy_after, Duration::from_secs(20));
+}
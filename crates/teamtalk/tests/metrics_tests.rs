@@ -0,0 +1,99 @@
+#![cfg(feature = "metrics")]
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+use teamtalk::metrics::{MetricsCollector, MetricsServer, UserSample, counter_rate, escape_label};
+use teamtalk::types::{ClientId, ClientStatistics, User, UserId, UserStatistics};
+
+#[test]
+fn counter_rate_handles_resets() {
+    assert_eq!(counter_rate(100, 300, Duration::from_secs(2)), 100.0);
+    assert_eq!(counter_rate(300, 100, Duration::from_secs(2)), 0.0);
+    assert_eq!(counter_rate(0, 100, Duration::ZERO), 0.0);
+}
+
+#[test]
+fn escape_label_quotes() {
+    assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+}
+
+#[test]
+fn render_client_rates() {
+    let mut collector = MetricsCollector::new(Duration::from_secs(1));
+    let start = Instant::now();
+    let mut stats = ClientStatistics {
+        tcp_ping: 20,
+        udp_ping: 15,
+        voice_sent: 1000,
+        ..Default::default()
+    };
+    collector.record_client(ClientId(7), Some("bot".to_string()), &stats, start);
+    stats.voice_sent = 3000;
+    collector.record_client(
+        ClientId(7),
+        Some("bot".to_string()),
+        &stats,
+        start + Duration::from_secs(2),
+    );
+    let text = collector.render();
+    assert!(text.contains("# TYPE teamtalk_client_bytes counter"));
+    assert!(text.contains(
+        "teamtalk_client_bytes_total{client_id=\"7\",client_label=\"bot\",stream=\"voice\",direction=\"sent\"} 3000"
+    ));
+    assert!(text.contains(
+        "teamtalk_client_bytes_per_second{client_id=\"7\",client_label=\"bot\",stream=\"voice\",direction=\"sent\"} 1000"
+    ));
+    assert!(text.contains("protocol=\"tcp\"} 20"));
+    assert!(text.ends_with("# EOF\n"));
+}
+
+fn user_sample(id: i32, voice_recv: i64) -> UserSample {
+    UserSample {
+        user: User {
+            id: UserId(id),
+            nickname: format!("user{id}"),
+            ..Default::default()
+        },
+        channel_path: "/".to_string(),
+        stats: UserStatistics {
+            voice_recv,
+            ..Default::default()
+        },
+    }
+}
+
+#[test]
+fn user_rates_forget_departed_users() {
+    let mut collector = MetricsCollector::new(Duration::from_secs(1));
+    let start = Instant::now();
+    let at = |secs| start + Duration::from_secs(secs);
+    collector.record_users(ClientId(1), None, &[user_sample(1, 100)], at(0));
+    collector.record_users(ClientId(1), None, &[user_sample(2, 100)], at(1));
+    collector.record_users(ClientId(1), None, &[user_sample(1, 300)], at(2));
+    let text = collector.render();
+    let rate = "teamtalk_user_packets_per_second{client_id=\"1\",client_label=\"\",user=\"1\",nickname=\"user1\",channel=\"/\",stream=\"voice\",kind=\"recv\"}";
+    assert!(text.contains(&format!("{rate} 0\n")), "{text}");
+    assert!(!text.contains("user=\"2\""));
+}
+
+#[test]
+fn server_serves_published_text() {
+    let mut collector = MetricsCollector::new(Duration::from_secs(1));
+    collector.record_client(
+        ClientId(1),
+        None,
+        &ClientStatistics::default(),
+        Instant::now(),
+    );
+    collector.publish();
+    let server = MetricsServer::start("127.0.0.1:0", collector.handle()).unwrap();
+    let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+    stream
+        .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("teamtalk_client_ping_milliseconds"));
+}
@@ -0,0 +1,96 @@
+use teamtalk::client::ffi;
+use teamtalk::client::{Playlist, RepeatMode, Track};
+use teamtalk::types::{AudioPreprocessor, MediaFileInfo, TeamTalkPreprocessor};
+
+fn playlist(count: usize) -> Playlist {
+    let mut playlist = Playlist::new();
+    playlist.extend((0..count).map(|i| Track::new(format!("/music/{i}.mp3"))));
+    playlist
+}
+
+#[test]
+fn advance_respects_repeat_modes() {
+    let mut list = playlist(3);
+    assert_eq!(list.advance(false), Some(0));
+    assert_eq!(list.advance(false), Some(1));
+    assert_eq!(list.advance(false), Some(2));
+    assert_eq!(list.advance(false), None);
+
+    list.set_repeat(RepeatMode::All);
+    assert_eq!(list.advance(false), Some(0));
+    assert_eq!(list.retreat(), Some(2));
+    assert_eq!(list.advance(false), Some(0));
+
+    list.set_repeat(RepeatMode::One);
+    assert_eq!(list.advance(false), Some(0));
+    assert_eq!(list.advance(true), Some(1));
+    assert_eq!(list.select(2), Some(2));
+    assert_eq!(list.select(7), None);
+}
+
+#[test]
+fn shuffle_is_a_permutation_and_remove_keeps_order() {
+    let mut list = playlist(6);
+    list.set_shuffle(true);
+    let mut order = list.order().to_vec();
+    order.sort();
+    assert_eq!(order, (0..6).collect::<Vec<_>>());
+    list.set_shuffle(false);
+    assert_eq!(list.order(), &[0, 1, 2, 3, 4, 5]);
+
+    list.advance(false);
+    list.advance(false);
+    assert_eq!(list.remove(0).unwrap().source, "/music/0.mp3");
+    assert_eq!(list.order(), &[0, 1, 2, 3, 4]);
+    assert_eq!(list.advance(false), Some(1));
+    assert_eq!(list.tracks()[1].source, "/music/2.mp3");
+    assert!(list.remove(9).is_none());
+}
+
+#[test]
+fn track_names_and_volume() {
+    assert_eq!(Track::new("/music/Song One.ogg").display_name(), "Song One");
+    assert_eq!(
+        Track::new("http://radio.example/live/stream").display_name(),
+        "stream"
+    );
+    assert_eq!(Track::new("x.mp3").title("Intro").display_name(), "Intro");
+
+    let mut list = Playlist::new();
+    list.push(Track::new("a.mp3"));
+    list.push(Track::new("b.mp3").volume(2000));
+    assert_eq!(list.volume_for(0), 1000);
+    assert_eq!(list.volume_for(1), 2000);
+    assert_eq!(TeamTalkPreprocessor::gain(40000).gain_level, 32000);
+}
+
+#[test]
+fn update_ignores_idle_playlist() {
+    let mut list = playlist(1);
+    let info = MediaFileInfo {
+        status: ffi::MediaFileStatus::MFS_FINISHED,
+        name: "/music/0.mp3".to_string(),
+        audio_fmt: Default::default(),
+        video_fmt: Default::default(),
+        duration_ms: 1000,
+        elapsed_ms: 1000,
+    };
+    assert_eq!(list.update(&info), None);
+    assert!(list.now_playing().is_none());
+}
+
+#[test]
+fn teamtalk_preprocessor_round_trips() {
+    let pre = AudioPreprocessor::TeamTalk(TeamTalkPreprocessor {
+        gain_level: 1500,
+        mute_left: true,
+        mute_right: false,
+    });
+    match AudioPreprocessor::from(pre.to_ffi()) {
+        AudioPreprocessor::TeamTalk(t) => {
+            assert_eq!(t.gain_level, 1500);
+            assert!(t.mute_left && !t.mute_right);
+        }
+        other => panic!("unexpected {other:?}"),
+    }
+}
@@ -0,0 +1,48 @@
+use teamtalk::client::quality::{mos_score, trend};
+use teamtalk::types::{UserId, UserStatistics};
+use teamtalk::{Event, QualityConfig, QualityMonitor};
+
+fn stats(recv: i64, lost: i64) -> UserStatistics {
+    UserStatistics {
+        voice_recv: recv,
+        voice_lost: lost,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn mos_score_drops_with_loss() {
+    let clean = mos_score(20.0, 2.0, 0.0);
+    let lossy = mos_score(20.0, 2.0, 10.0);
+    assert!(clean > 4.0);
+    assert!(lossy < clean);
+    assert_eq!(mos_score(2000.0, 500.0, 100.0), 1.0);
+}
+
+#[test]
+fn trend_slope() {
+    assert_eq!(trend(&[]), 0.0);
+    assert_eq!(trend(&[10.0, 20.0, 30.0]), 10.0);
+    assert!(trend(&[30.0, 20.0, 10.0]) < 0.0);
+}
+
+#[test]
+fn monitor_emits_transitions() {
+    let mut monitor = QualityMonitor::new(QualityConfig::new().min_packets(10));
+    let user = UserId(5);
+    monitor.record_ping(Some(30));
+    assert_eq!(monitor.record_user(user, &stats(0, 0)), None);
+    assert_eq!(monitor.record_user(user, &stats(100, 0)), None);
+    assert_eq!(
+        monitor.record_user(user, &stats(180, 20)),
+        Some(Event::QualityDegraded { user, loss: 20 })
+    );
+    assert_eq!(monitor.record_user(user, &stats(185, 20)), None);
+    assert_eq!(
+        monitor.record_user(user, &stats(285, 21)),
+        Some(Event::QualityRecovered { user })
+    );
+    let report = monitor.report(user).unwrap();
+    assert!(!report.degraded);
+    assert_eq!(report.ping_ms, Some(30));
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use common::temp_dir;
+use std::time::{Duration, SystemTime};
+use teamtalk::client::ffi;
+use teamtalk::client::{RecordTarget, Recorder, RecorderConfig, RecorderEvent};
+use teamtalk::types::{ChannelId, MediaFileInfo, User, UserId};
+
+fn at(secs: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000 + secs)
+}
+
+fn info(name: &str, status: ffi::MediaFileStatus) -> MediaFileInfo {
+    MediaFileInfo {
+        status,
+        name: name.to_string(),
+        audio_fmt: Default::default(),
+        video_fmt: Default::default(),
+        duration_ms: 0,
+        elapsed_ms: 0,
+    }
+}
+
+#[test]
+fn file_names_follow_template() {
+    let config = RecorderConfig::new("/rec");
+    let target = RecordTarget::Channel(ChannelId(4));
+    assert_eq!(
+        config.file_name(&target, 2, at(0), at(60)),
+        "channel4_1700000060_2.wav"
+    );
+    let config = config
+        .template("{session}-{index}-{target}")
+        .format(ffi::AudioFileFormat::AFF_MP3_64KBIT_FORMAT);
+    assert_eq!(
+        config.file_name(&RecordTarget::ChannelUsers(ChannelId(1)), 1, at(0), at(5)),
+        "1700000000-1-users1.mp3"
+    );
+}
+
+#[test]
+fn user_files_and_sidecar() {
+    let mut recorder = Recorder::new(
+        RecordTarget::Users(vec![UserId(3)]),
+        RecorderConfig::new("/rec"),
+    );
+    let alice = User {
+        id: UserId(3),
+        username: "alice".to_string(),
+        nickname: "Al \"A\"".to_string(),
+        ..Default::default()
+    };
+    recorder.participant_joined(&alice, at(0));
+    recorder.participant_joined(&alice, at(1));
+    assert_eq!(recorder.participants().len(), 1);
+
+    let started = recorder.user_file(
+        UserId(3),
+        &info("/rec/a.wav", ffi::MediaFileStatus::MFS_STARTED),
+        at(2),
+    );
+    assert_eq!(
+        started,
+        Some(RecorderEvent::FileStarted {
+            user: Some(UserId(3)),
+            path: "/rec/a.wav".into()
+        })
+    );
+    recorder.user_file(
+        UserId(3),
+        &info("/rec/a.wav", ffi::MediaFileStatus::MFS_FINISHED),
+        at(5),
+    );
+    let failed = recorder.user_file(
+        UserId(3),
+        &info("/rec/b.wav", ffi::MediaFileStatus::MFS_ERROR),
+        at(6),
+    );
+    assert!(matches!(failed, Some(RecorderEvent::FileFailed { .. })));
+    assert!(
+        recorder
+            .user_file(
+                UserId(3),
+                &info("/rec/c.wav", ffi::MediaFileStatus::MFS_FINISHED),
+                at(7)
+            )
+            .is_none()
+    );
+    recorder.participant_left(UserId(3), at(8));
+
+    let files = recorder.files();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].finished, Some(at(5)));
+    assert!(files[1].failed);
+
+    #[cfg(feature = "json")]
+    {
+        let json: serde_json::Value =
+            serde_json::from_str(&recorder.sidecar_json().unwrap()).unwrap();
+        assert_eq!(json["target"], "users");
+        assert_eq!(json["stopped"], serde_json::Value::Null);
+        assert_eq!(json["files"][0]["path"], "/rec/a.wav");
+        assert_eq!(json["files"][0]["user_id"], 3);
+        assert_eq!(json["files"][0]["finished"], 1_700_000_005_000u64);
+        assert_eq!(json["files"][1]["failed"], true);
+        assert_eq!(json["participants"][0]["nickname"], "Al \"A\"");
+        assert_eq!(json["participants"][0]["left"], 1_700_000_008_000u64);
+    }
+}
+
+#[test]
+fn stop_without_start_writes_nothing() {
+    let dir = temp_dir("recorder_never_started");
+    let mut recorder = Recorder::new(
+        RecordTarget::Channel(ChannelId(1)),
+        RecorderConfig::new(&dir),
+    );
+    assert_eq!(recorder.stop(at(0)).unwrap(), None);
+    assert!(!dir.exists());
+}
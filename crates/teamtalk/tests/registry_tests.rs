@@ -0,0 +1,31 @@
+#![cfg(feature = "json")]
+
+use std::time::{Duration, UNIX_EPOCH};
+use teamtalk::client::ClientInfo;
+use teamtalk::events::{ConnectionState, Event};
+use teamtalk::types::{ChannelId, ClientId};
+
+#[test]
+fn client_info_json() {
+    let info = ClientInfo {
+        id: ClientId(3),
+        label: Some("bot \"a\"".to_string()),
+        state: ConnectionState::Joined(ChannelId(5)),
+        last_event: Some(Event::ConnectSuccess),
+        last_event_at: Some(UNIX_EPOCH + Duration::from_millis(1_500)),
+        server_host: Some("tt.example".to_string()),
+        nickname: None,
+        channel_path: Some("/Lobby/".to_string()),
+        reconnect_attempts: 2,
+        last_error: None,
+    };
+    let json: serde_json::Value = serde_json::from_str(&info.to_json().unwrap()).unwrap();
+    assert_eq!(json["id"], 3);
+    assert_eq!(json["label"], "bot \"a\"");
+    assert_eq!(json["state"], "joined");
+    assert_eq!(json["channel"], 5);
+    assert_eq!(json["last_event"], "ConnectSuccess");
+    assert_eq!(json["last_event_at_ms"], 1500);
+    assert_eq!(json["nickname"], serde_json::Value::Null);
+    assert_eq!(json["reconnect_attempts"], 2);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use common::temp_dir;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use teamtalk::client::{AudioChunk, SegmenterConfig, UtteranceSegmenter};
+use teamtalk::types::{ChannelId, User, UserId, UserState};
+use teamtalk::utils::wav;
+
+fn at(ms: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_000 + ms)
+}
+
+fn block(value: i16) -> AudioChunk {
+    AudioChunk {
+        stream_id: 1,
+        sample_rate: 1000,
+        channels: 1,
+        sample_index: 0,
+        samples: vec![value; 100],
+    }
+}
+
+fn user(talking: bool) -> User {
+    User {
+        id: UserId(7),
+        username: "bob smith".to_string(),
+        channel_id: ChannelId(3),
+        state: UserState::from_raw(if talking { UserState::VOICE } else { 0 }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn talking_flag_clips_with_hangover() {
+    let dir = temp_dir("segments_talking");
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = seen.clone();
+    let config = SegmenterConfig::new(&dir)
+        .hangover(Duration::from_millis(300))
+        .min_duration(Duration::from_millis(150));
+    let mut segmenter = UtteranceSegmenter::new(config)
+        .on_utterance_finished(move |u| sink.lock().unwrap().push(u.clone()));
+
+    segmenter.push_audio(UserId(7), &block(5), at(0)).unwrap();
+    assert!(!segmenter.is_recording(UserId(7)));
+    segmenter.update_user(&user(true), at(100)).unwrap();
+    segmenter.push_audio(UserId(7), &block(5), at(200)).unwrap();
+    segmenter.push_audio(UserId(7), &block(6), at(300)).unwrap();
+    segmenter.update_user(&user(false), at(400)).unwrap();
+    segmenter.push_audio(UserId(7), &block(0), at(500)).unwrap();
+    assert!(segmenter.tick(at(600)).unwrap().is_empty());
+    let finished = segmenter.tick(at(700)).unwrap();
+
+    assert_eq!(finished.len(), 1);
+    let clip = &finished[0];
+    assert_eq!(clip.user, UserId(7));
+    assert_eq!(clip.channel, ChannelId(3));
+    assert_eq!(clip.started, at(200));
+    assert_eq!(clip.duration, Duration::from_millis(300));
+    assert!(clip.path.ends_with("bob_smith_ch3_1700000000200.wav"));
+    assert_eq!(wav::read(&clip.path).unwrap().1.len(), 300);
+    assert_eq!(*seen.lock().unwrap(), finished);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn energy_detection_splits_and_drops_short_clips() {
+    let dir = temp_dir("segments_energy");
+    let config = SegmenterConfig::new(&dir)
+        .use_talking_flag(false)
+        .energy_threshold_dbfs(-40.0)
+        .hangover(Duration::from_millis(100))
+        .min_duration(Duration::from_millis(150))
+        .max_duration(Duration::from_millis(200));
+    let mut segmenter = UtteranceSegmenter::new(config);
+
+    assert!(
+        segmenter
+            .push_audio(UserId(2), &block(10), at(0))
+            .unwrap()
+            .is_empty()
+    );
+    segmenter
+        .push_audio(UserId(2), &block(3000), at(0))
+        .unwrap();
+    let split = segmenter
+        .push_audio(UserId(2), &block(3000), at(100))
+        .unwrap();
+    assert_eq!(split.len(), 1);
+    assert!(split[0].path.ends_with("user2_ch0_1700000000000.wav"));
+
+    segmenter
+        .push_audio(UserId(2), &block(3000), at(200))
+        .unwrap();
+    let dropped = segmenter.tick(at(300)).unwrap();
+    assert!(dropped.is_empty());
+    assert!(!segmenter.is_recording(UserId(2)));
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn removed_user_is_forgotten() {
+    let dir = temp_dir("segments_removed");
+    let mut segmenter = UtteranceSegmenter::new(SegmenterConfig::new(&dir));
+    segmenter.update_user(&user(true), at(0)).unwrap();
+    segmenter.push_audio(UserId(7), &block(5), at(0)).unwrap();
+    assert!(segmenter.is_recording(UserId(7)));
+
+    segmenter.remove_user(UserId(7)).unwrap();
+    assert!(!segmenter.is_recording(UserId(7)));
+    segmenter.push_audio(UserId(7), &block(5), at(100)).unwrap();
+    assert!(!segmenter.is_recording(UserId(7)));
+    let _ = std::fs::remove_dir_all(&dir);
+}
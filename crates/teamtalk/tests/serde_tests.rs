@@ -0,0 +1,97 @@
+#![cfg(feature = "serde")]
+
+use teamtalk::ConnectionState;
+use teamtalk::client::ffi;
+use teamtalk::types::{
+    AudioCodec, ChannelId, OpusCodec, Subscriptions, TextMessage, UserId, UserStatistics,
+};
+
+#[test]
+fn ids_and_flags_are_transparent() {
+    assert_eq!(serde_json::to_string(&UserId(7)).unwrap(), "7");
+    let subs = Subscriptions::USER_MSG;
+    let json = serde_json::to_string(&subs).unwrap();
+    assert_eq!(json, Subscriptions::USER_MSG.raw().to_string());
+    let parsed: Subscriptions = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, subs);
+}
+
+#[test]
+fn text_message_uses_stable_type_names() {
+    let msg = TextMessage {
+        msg_type: ffi::TextMsgType::MSGTYPE_CHANNEL,
+        from_id: UserId(1),
+        from_username: "alice".to_string(),
+        to_id: UserId(0),
+        channel_id: ChannelId(2),
+        text: "hi".to_string(),
+        more: false,
+    };
+    let value = serde_json::to_value(&msg).unwrap();
+    assert_eq!(value["msg_type"], "channel");
+    let parsed: TextMessage = serde_json::from_value(value).unwrap();
+    assert_eq!(parsed.msg_type, ffi::TextMsgType::MSGTYPE_CHANNEL);
+    assert!(serde_json::from_str::<TextMessage>(&msg_with_type("bogus")).is_err());
+}
+
+fn msg_with_type(kind: &str) -> String {
+    format!(
+        "{{\"msg_type\":\"{kind}\",\"from_id\":1,\"from_username\":\"\",\"to_id\":0,\"channel_id\":0,\"text\":\"\",\"more\":false}}"
+    )
+}
+
+#[test]
+fn codec_and_state_are_tagged() {
+    let codec = AudioCodec::Opus(OpusCodec {
+        sample_rate: 48000,
+        channels: 2,
+        application: 2048,
+        complexity: 10,
+        fec: true,
+        dtx: false,
+        bitrate: 64000,
+        vbr: true,
+        vbr_constraint: false,
+        tx_interval_msec: 20,
+        frame_size_msec: 20,
+    });
+    let value = serde_json::to_value(codec).unwrap();
+    assert_eq!(value["codec"], "opus");
+    assert_eq!(value["sample_rate"], 48000);
+    let none = serde_json::to_string(&AudioCodec::None).unwrap();
+    assert_eq!(none, "{\"codec\":\"none\"}");
+
+    let state = ConnectionState::Joined(ChannelId(3));
+    let json = serde_json::to_string(&state).unwrap();
+    assert_eq!(json, "{\"state\":\"joined\",\"channel\":3}");
+    assert_eq!(
+        serde_json::from_str::<ConnectionState>(&json).unwrap(),
+        state
+    );
+    assert_eq!(
+        serde_json::to_string(&ConnectionState::Creating).unwrap(),
+        "{\"state\":\"creating\"}"
+    );
+}
+
+#[test]
+fn statistics_roundtrip() {
+    let stats = UserStatistics {
+        voice_recv: 10,
+        voice_lost: 2,
+        ..Default::default()
+    };
+    let json = serde_json::to_string(&stats).unwrap();
+    let parsed: UserStatistics = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.voice_recv, 10);
+    assert_eq!(parsed.voice_lost, 2);
+}
+
+#[test]
+fn channel_path_serializes_as_string() {
+    let path = teamtalk::types::ChannelPath::parse("/Lobby/Music").unwrap();
+    let json = serde_json::to_string(&path).unwrap();
+    assert_eq!(json, "\"/Lobby/Music\"");
+    let parsed: teamtalk::types::ChannelPath = serde_json::from_str("\"Lobby/Music/\"").unwrap();
+    assert_eq!(parsed, path);
+}
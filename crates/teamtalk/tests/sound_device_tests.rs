@@ -0,0 +1,132 @@
+use teamtalk::client::ffi;
+use teamtalk::client::{DeviceDirection, SoundDeviceSelector};
+use teamtalk::types::SoundDevice;
+
+fn device(id: i32, name: &str, uid: &str, system: ffi::SoundSystem, input: bool) -> SoundDevice {
+    SoundDevice {
+        id,
+        name: name.to_string(),
+        system,
+        device_uid: uid.to_string(),
+        max_input_channels: if input { 2 } else { 0 },
+        max_output_channels: 2,
+        input_sample_rates: vec![16000, 48000],
+        output_sample_rates: vec![44100, 48000],
+        default_sample_rate: 48000,
+        features: 0,
+    }
+}
+
+fn devices() -> Vec<SoundDevice> {
+    vec![
+        device(
+            1,
+            "Speakers",
+            "spk",
+            ffi::SoundSystem::SOUNDSYSTEM_ALSA,
+            false,
+        ),
+        device(
+            2,
+            "USB Headset",
+            "usb-1",
+            ffi::SoundSystem::SOUNDSYSTEM_ALSA,
+            true,
+        ),
+        device(
+            7,
+            "USB Headset",
+            "usb-1",
+            ffi::SoundSystem::SOUNDSYSTEM_PULSEAUDIO,
+            true,
+        ),
+        device(
+            3,
+            "Built-in Mic",
+            "mic",
+            ffi::SoundSystem::SOUNDSYSTEM_ALSA,
+            true,
+        ),
+    ]
+}
+
+#[test]
+fn selects_by_uid_name_and_system() {
+    let devices = devices();
+    let by_uid = SoundDeviceSelector::new().device_uid("mic").name("*usb*");
+    assert_eq!(
+        by_uid
+            .select(&devices, DeviceDirection::Input, None)
+            .unwrap()
+            .id,
+        3
+    );
+
+    let by_name = SoundDeviceSelector::new()
+        .name("*usb*")
+        .sound_system(ffi::SoundSystem::SOUNDSYSTEM_PULSEAUDIO);
+    assert_eq!(
+        by_name
+            .select(&devices, DeviceDirection::Input, None)
+            .unwrap()
+            .id,
+        7
+    );
+    assert!(by_name.matches(&devices[1]));
+    assert!(!by_name.matches(&devices[0]));
+
+    let output = SoundDeviceSelector::new().name("speak?rs");
+    assert_eq!(
+        output
+            .select(&devices, DeviceDirection::Output, Some(2))
+            .unwrap()
+            .id,
+        1
+    );
+    assert!(
+        output
+            .select(&devices, DeviceDirection::Input, Some(2))
+            .is_some_and(|d| d.id == 2)
+    );
+}
+
+#[test]
+fn falls_back_to_default_and_checks_sample_rate() {
+    let devices = devices();
+    let default = SoundDeviceSelector::new();
+    assert_eq!(
+        default
+            .select(&devices, DeviceDirection::Input, Some(3))
+            .unwrap()
+            .id,
+        3
+    );
+    assert_eq!(
+        default
+            .select(&devices, DeviceDirection::Input, Some(99))
+            .unwrap()
+            .id,
+        2
+    );
+
+    let strict = SoundDeviceSelector::new()
+        .name("Missing*")
+        .fallback_to_default(false);
+    assert!(
+        strict
+            .select(&devices, DeviceDirection::Input, Some(3))
+            .is_none()
+    );
+
+    let rate = SoundDeviceSelector::new().sample_rate(44100);
+    assert!(
+        rate.select(&devices, DeviceDirection::Input, Some(3))
+            .is_none()
+    );
+    assert_eq!(
+        rate.select(&devices, DeviceDirection::Output, None)
+            .unwrap()
+            .id,
+        1
+    );
+}
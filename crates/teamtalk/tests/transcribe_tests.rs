@@ -0,0 +1,116 @@
+use teamtalk::client::transcribe::format_transcript;
+use teamtalk::client::{AudioChunk, StubTranscriber, Transcriber, Transcript, Transcription};
+use teamtalk::types::UserId;
+use teamtalk::utils::wav::WavSpec;
+
+fn chunk(len: usize) -> AudioChunk {
+    AudioChunk {
+        stream_id: 1,
+        sample_rate: 16000,
+        channels: 1,
+        sample_index: 0,
+        samples: vec![100; len],
+    }
+}
+
+#[test]
+fn stub_returns_scripted_finals() {
+    let mut stub = StubTranscriber::new(["hello", "bye"]);
+    assert!(stub.finish().unwrap().is_empty());
+    stub.push(&[1, 2, 3], WavSpec::new(16000, 1)).unwrap();
+    assert!(stub.poll().unwrap().is_empty());
+    assert_eq!(
+        stub.finish().unwrap(),
+        vec![Transcript::final_text("hello")]
+    );
+    assert!(stub.finish().unwrap().is_empty());
+    assert_eq!(stub.samples_received(), 3);
+}
+
+#[test]
+fn transcription_routes_users() {
+    let mut hub = Transcription::new(|user_id| {
+        Box::new(StubTranscriber::new([format!("from {}", user_id.0)])) as Box<dyn Transcriber>
+    });
+    hub.push(UserId(1), &chunk(160)).unwrap();
+    hub.push(UserId(2), &chunk(160)).unwrap();
+    assert_eq!(
+        hub.finish_user(UserId(2)).unwrap(),
+        vec![(UserId(2), Transcript::final_text("from 2"))]
+    );
+    assert_eq!(
+        hub.remove_user(UserId(1)).unwrap(),
+        vec![(UserId(1), Transcript::final_text("from 1"))]
+    );
+    assert!(hub.finish_user(UserId(1)).unwrap().is_empty());
+}
+
+#[test]
+fn transcript_lines() {
+    assert_eq!(
+        format_transcript("Ann", &Transcript::final_text(" hi ")),
+        "Ann: hi"
+    );
+    assert_eq!(
+        format_transcript("Ann", &Transcript::partial("h")),
+        "Ann: h…"
+    );
+}
+
+#[cfg(all(feature = "json", unix))]
+fn poll_until_idle(
+    command: &mut teamtalk::client::CommandTranscriber,
+) -> Vec<teamtalk::Result<Vec<Transcript>>> {
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    let mut polls = Vec::new();
+    while command.pending() > 0 && std::time::Instant::now() < deadline {
+        polls.push(command.poll());
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert_eq!(command.pending(), 0);
+    polls
+}
+
+#[cfg(all(feature = "json", unix))]
+#[test]
+fn command_transcriber_reads_json_lines() {
+    use teamtalk::client::CommandTranscriber;
+    let script =
+        r#"n=$(wc -c); echo "{\"text\": \"bytes\"}"; echo "{\"text\": \"$n\", \"final\": true}""#;
+    let mut command = CommandTranscriber::new("sh")
+        .arg("-c")
+        .arg(script)
+        .format(WavSpec::new(8000, 1));
+    command.push(&[1; 320], WavSpec::new(16000, 2)).unwrap();
+    let mut results = command.finish().unwrap();
+    for poll in poll_until_idle(&mut command) {
+        results.extend(poll.unwrap());
+    }
+    assert_eq!(
+        results,
+        vec![Transcript::partial("bytes"), Transcript::final_text("160")]
+    );
+    assert!(command.poll().unwrap().is_empty());
+}
+
+#[cfg(all(feature = "json", unix))]
+#[test]
+fn command_transcriber_finish_does_not_wait() {
+    use teamtalk::client::CommandTranscriber;
+    let mut command = CommandTranscriber::new("sh")
+        .arg("-c")
+        .arg("cat > /dev/null; sleep 1; exit 3");
+    command.push(&[1; 160], WavSpec::new(16000, 1)).unwrap();
+    let started = std::time::Instant::now();
+    assert!(command.finish().unwrap().is_empty());
+    assert!(started.elapsed() < std::time::Duration::from_millis(500));
+    assert_eq!(command.pending(), 1);
+    let errors: Vec<_> = poll_until_idle(&mut command)
+        .into_iter()
+        .filter_map(|poll| poll.err())
+        .collect();
+    assert!(matches!(
+        errors.as_slice(),
+        [teamtalk::Error::CommandFailed { code: 3, .. }]
+    ));
+}
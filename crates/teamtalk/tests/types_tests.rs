@@ -1,6 +1,8 @@
+use teamtalk::client::ffi;
 use teamtalk::types::{
-    Channel, ChannelId, ChannelType, MessageTarget, Subscriptions, UserGender, UserId,
-    UserPresence, UserState, UserStatus,
+    Channel, ChannelId, ChannelType, MediaFileInfo, MessageTarget, ServerLogEvents,
+    ServerProperties, ServerPropertiesPatch, StreamTypes, Subscriptions, UserGender, UserId,
+    UserPresence, UserRights, UserState, UserStatus, UserTypes,
 };
 
 #[test]
@@ -61,12 +63,39 @@ fn channel_builder_sets_fields() {
     let channel = Channel::builder("room")
         .topic("topic")
         .max_users(42)
-        .channel_type(ChannelType::from_raw(ChannelType::HIDDEN))
+        .channel_type(ChannelType::HIDDEN)
         .build();
     assert_eq!(channel.name, "room");
     assert_eq!(channel.topic, "topic");
     assert_eq!(channel.max_users, 42);
-    assert_eq!(channel.channel_type.raw(), ChannelType::HIDDEN);
+    assert_eq!(channel.channel_type, ChannelType::HIDDEN);
+}
+
+#[test]
+fn channel_builder_temporary_channel_settings() {
+    let channel = Channel::builder("class")
+        .parent(ChannelId(1))
+        .password("secret")
+        .op_password("teacher")
+        .disk_quota(1 << 20)
+        .timeout_voice_ms(30_000)
+        .timeout_media_ms(60_000)
+        .queue_delay_ms(500)
+        .channel_type(ChannelType::CLASSROOM)
+        .transmit_user(UserId(7), StreamTypes::VOICE | StreamTypes::MEDIAFILE)
+        .build();
+    assert!(channel.has_password);
+    let back = Channel::from(channel.to_ffi());
+    assert_eq!(back.password, "secret");
+    assert_eq!(back.op_password, "teacher");
+    assert_eq!(back.disk_quota, 1 << 20);
+    assert_eq!(back.timeout_voice_ms, 30_000);
+    assert_eq!(back.timeout_media_ms, 60_000);
+    assert_eq!(back.queue_delay_ms, 500);
+    assert_eq!(
+        back.transmit_users,
+        vec![(UserId(7), StreamTypes::VOICE | StreamTypes::MEDIAFILE)]
+    );
 }
 
 #[test]
@@ -82,3 +111,127 @@ fn message_target_from_ids() {
         _ => panic!("expected channel target"),
     }
 }
+
+#[test]
+fn connection_state_names() {
+    use teamtalk::ConnectionState;
+    assert_eq!(ConnectionState::Idle.name(), "idle");
+    assert_eq!(ConnectionState::Joined(ChannelId(3)).name(), "joined");
+    assert_eq!(
+        ConnectionState::Joining(ChannelId(4)).channel(),
+        Some(ChannelId(4))
+    );
+    assert_eq!(ConnectionState::LoggedIn.channel(), None);
+    assert_eq!(ConnectionState::Creating.name(), "creating");
+    assert_eq!(ConnectionState::Creating.channel(), None);
+}
+
+#[test]
+fn server_log_events_flags() {
+    let mut events = ServerLogEvents::new();
+    events.insert(ServerLogEvents::USER_LOGGEDIN | ServerLogEvents::USER_KICKED);
+    assert!(events.contains(ServerLogEvents::USER_KICKED));
+    events.remove(ServerLogEvents::USER_KICKED);
+    assert_eq!(events.raw(), 4);
+    assert!(ServerLogEvents::all().contains(ServerLogEvents::SERVER_SAVECONFIG));
+}
+
+#[test]
+fn server_properties_patch_and_diff() {
+    let current = ServerProperties {
+        name: "Main".to_string(),
+        max_users: 100,
+        tcp_port: 10333,
+        access_token: "secret".to_string(),
+        ..Default::default()
+    };
+    let patch = ServerPropertiesPatch::new()
+        .max_users(50)
+        .log_events(ServerLogEvents::USER_CONNECTED);
+    assert!(!patch.is_empty());
+    assert!(ServerPropertiesPatch::new().save_config(true).is_empty());
+
+    let mut updated = current.clone();
+    patch.apply_to(&mut updated);
+    assert_eq!(updated.name, "Main");
+    assert_eq!(updated.max_users, 50);
+
+    let changes = current.diff(&updated);
+    let text: Vec<String> = changes.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        text,
+        vec![
+            "max_users: 100 -> 50",
+            "log_events: 0x00000000 -> 0x00000001"
+        ]
+    );
+    updated.access_token = "other".to_string();
+    assert_eq!(current.diff(&updated).len(), 2);
+    assert!(current.diff(&current).is_empty());
+}
+
+#[test]
+fn flag_sets_debug_lists_flags() {
+    let rights = UserRights::TRANSMIT_VOICE | UserRights::KICK_USERS;
+    assert_eq!(
+        format!("{rights:?}"),
+        "UserRights(KICK_USERS | TRANSMIT_VOICE)"
+    );
+    assert_eq!(format!("{:?}", UserTypes::new()), "UserTypes(empty)");
+    assert_eq!(
+        format!("{:?}", StreamTypes::from_raw(0x8000_0001)),
+        "StreamTypes(VOICE | 0x80000000)"
+    );
+}
+
+#[test]
+fn flag_sets_contains_and_operators() {
+    let media = StreamTypes::MEDIAFILE;
+    assert!(media.contains(StreamTypes::MEDIAFILE_AUDIO));
+    assert!(!StreamTypes::MEDIAFILE_AUDIO.contains(media));
+    assert!(StreamTypes::MEDIAFILE_AUDIO.intersects(media));
+
+    let mut rights = UserRights::TRANSMIT_MEDIAFILE | UserRights::UPLOAD_FILES;
+    rights -= UserRights::TRANSMIT_MEDIAFILE_VIDEO;
+    assert!(!rights.contains(UserRights::TRANSMIT_MEDIAFILE));
+    assert_eq!(
+        rights & UserRights::TRANSMIT_MEDIAFILE,
+        UserRights::TRANSMIT_MEDIAFILE_AUDIO
+    );
+    rights.set(UserRights::UPLOAD_FILES, false);
+    rights.toggle(UserRights::DOWNLOAD_FILES);
+    assert_eq!(
+        rights.names(),
+        ["DOWNLOAD_FILES", "TRANSMIT_MEDIAFILE_AUDIO"]
+    );
+    assert_eq!(u32::from(UserTypes::ADMIN), UserTypes::ADMIN.raw());
+    assert!(UserTypes::from(2) == UserTypes::ADMIN);
+}
+
+#[test]
+fn media_file_info_progress() {
+    let mut info = MediaFileInfo {
+        status: ffi::MediaFileStatus::MFS_PLAYING,
+        name: "ding.wav".to_string(),
+        audio_fmt: Default::default(),
+        video_fmt: Default::default(),
+        duration_ms: 0,
+        elapsed_ms: 250,
+    };
+    assert_eq!(info.progress(), None);
+    assert!(!info.is_done());
+    info.duration_ms = 1000;
+    assert_eq!(info.progress(), Some(0.25));
+    info.elapsed_ms = 1200;
+    info.status = ffi::MediaFileStatus::MFS_FINISHED;
+    assert_eq!(info.progress(), Some(1.0));
+    assert!(info.is_done());
+}
+
+#[test]
+fn sdk_constants_match_ffi() {
+    use teamtalk::types::{MEDIA_OFFSET_IGNORE, SOUND_GAIN_DEFAULT, SOUND_GAIN_MAX};
+    assert_eq!(SOUND_GAIN_DEFAULT, 1000);
+    assert_eq!(SOUND_GAIN_MAX, 32000);
+    assert_eq!(MEDIA_OFFSET_IGNORE, u32::MAX);
+}
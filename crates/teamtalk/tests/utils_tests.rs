@@ -1,8 +1,11 @@
 use std::time::Duration;
 use teamtalk::client::ffi;
+use teamtalk::types::AudioFormat;
+use teamtalk::utils::audio::{self, Limiter};
 use teamtalk::utils::backoff::ExponentialBackoff;
-use teamtalk::utils::math::ref_gain;
-use teamtalk::utils::strings::{ToTT, copy_to_string, from_tt, to_string};
+use teamtalk::utils::csv;
+use teamtalk::utils::math::{amplitude_to_dbfs, db_to_gain, gain_to_db, ref_gain};
+use teamtalk::utils::strings::{ToTT, copy_to_string, from_tt, glob_match, to_string};
 
 #[test]
 fn ref_gain_zero() {
@@ -66,3 +69,82 @@ fn string_copy_to_string() {
     copy_to_string(&buf, &mut out);
     assert_eq!(out, input);
 }
+
+#[test]
+fn csv_roundtrip_quotes_fields() {
+    let line = csv::write_row(&["a", "b,c", "say \"hi\"", "two\nlines", ""]);
+    assert_eq!(line, "a,\"b,c\",\"say \"\"hi\"\"\",\"two\nlines\",\n");
+    let rows = csv::parse(&format!("{line}\r\nx,y\n")).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0], vec!["a", "b,c", "say \"hi\"", "two\nlines", ""]);
+    assert_eq!(rows[1], vec!["x", "y"]);
+    assert!(csv::parse("\"open").is_err());
+}
+
+#[test]
+fn db_helpers_round_trip() {
+    assert!((db_to_gain(-6.0) - 0.501).abs() < 0.001);
+    assert!((gain_to_db(db_to_gain(-12.5)) + 12.5).abs() < 1e-9);
+    assert_eq!(amplitude_to_dbfs(i16::MAX as f64), 0.0);
+    assert_eq!(amplitude_to_dbfs(0.0), f64::NEG_INFINITY);
+}
+
+#[test]
+fn audio_remix_resample_and_convert() {
+    assert_eq!(audio::remix(&[10, 30, -10, -31], 2, 1), vec![20, -21]);
+    assert_eq!(audio::remix(&[5, 7], 1, 2), vec![5, 5, 7, 7]);
+    assert_eq!(
+        audio::resample(&[0, 100], 1, 1000, 2000),
+        vec![0, 50, 100, 100]
+    );
+    assert_eq!(
+        audio::resample(&[0, 10, 20, 30], 1, 2000, 1000),
+        vec![0, 20]
+    );
+
+    let from = AudioFormat {
+        sample_rate: 8000,
+        channels: 1,
+        ..Default::default()
+    };
+    let to = AudioFormat {
+        sample_rate: 16000,
+        channels: 2,
+        ..Default::default()
+    };
+    assert_eq!(
+        audio::convert(&[0, 100], &from, &to),
+        vec![0, 0, 50, 50, 100, 100, 100, 100]
+    );
+}
+
+#[test]
+fn audio_levels_gain_and_limiting() {
+    let mut samples = vec![1000, -2000, 30000];
+    assert_eq!(audio::peak(&samples), 30000);
+    assert!((audio::rms(&[3, -4, 3, -4]) - 3.5355).abs() < 0.001);
+    assert!(audio::peak_dbfs(&[i16::MIN]) > 0.0);
+    assert_eq!(audio::rms_dbfs(&[]), f64::NEG_INFINITY);
+
+    audio::apply_gain(&mut samples, 2.0);
+    assert_eq!(samples, vec![2000, -4000, i16::MAX]);
+    audio::apply_gain_db(&mut samples, -6.0206);
+    assert_eq!(samples[0], 1000);
+    audio::hard_limit(&mut samples, 1500);
+    assert_eq!(samples, vec![1000, -1500, 1500]);
+
+    let mut limiter = Limiter::new(-6.0206, 10, 1000);
+    let mut loud = vec![32000, -32000, 8000, 8000];
+    limiter.process(&mut loud, 2);
+    assert!((loud[0] as i32 - 16384).abs() <= 1);
+    assert!(loud[2] < 8000);
+    assert!(limiter.gain() > 0.5 && limiter.gain() < 1.0);
+}
+
+#[test]
+fn glob_match_wildcards() {
+    assert!(glob_match("*USB*", "Logitech USB Headset"));
+    assert!(glob_match("mic?", "mic1"));
+    assert!(!glob_match("mic?", "mic"));
+    assert!(glob_match("**", ""));
+}